@@ -0,0 +1,305 @@
+use std::{
+    str::FromStr,
+    sync::Arc,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use ethers::utils::hex;
+use hmac::{Hmac, Mac};
+use rust_decimal::Decimal;
+use serde::Serialize;
+use sha2::Sha256;
+use tokio::task::JoinHandle;
+use tracing::warn;
+
+use crate::{WatchedPair, Watchlist, ZeroXQuoteResponse};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PriceCondition {
+    Above(Decimal),
+    Below(Decimal),
+}
+
+impl PriceCondition {
+    pub fn is_met(&self, price: Decimal) -> bool {
+        match *self {
+            PriceCondition::Above(threshold) => price > threshold,
+            PriceCondition::Below(threshold) => price < threshold,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AlertRule {
+    pub name: String,
+    pub pair: WatchedPair,
+    pub condition: PriceCondition,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct AlertEvent {
+    pub rule: String,
+    pub sell_token: String,
+    pub buy_token: String,
+    pub sell_amount: String,
+    pub price: String,
+    pub condition: String,
+    /// Unix timestamp in seconds.
+    pub triggered_at: u64,
+}
+
+impl AlertEvent {
+    fn new(rule: &AlertRule, price: Decimal) -> AlertEvent {
+        let condition = match rule.condition {
+            PriceCondition::Above(threshold) => format!("above {}", threshold),
+            PriceCondition::Below(threshold) => format!("below {}", threshold),
+        };
+
+        AlertEvent {
+            rule: rule.name.clone(),
+            sell_token: rule.pair.sell_token.clone(),
+            buy_token: rule.pair.buy_token.clone(),
+            sell_amount: rule.pair.sell_amount.clone(),
+            price: price.to_string(),
+            condition,
+            triggered_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs(),
+        }
+    }
+}
+
+/// POSTs alert events as JSON to a webhook URL.
+///
+/// Each request carries an `X-Signature: sha256=<hex>` header with the HMAC-SHA256 of the body
+/// under `secret`, so receivers can verify the sender. Failed deliveries (network errors and
+/// non-2xx responses) are retried with exponential backoff.
+#[derive(Debug, Clone)]
+pub struct WebhookNotifier {
+    url: String,
+    secret: Vec<u8>,
+    http: reqwest::Client,
+    max_retries: u32,
+    base_delay: Duration,
+}
+
+impl WebhookNotifier {
+    pub fn new(url: impl Into<String>, secret: impl Into<Vec<u8>>) -> WebhookNotifier {
+        WebhookNotifier {
+            url: url.into(),
+            secret: secret.into(),
+            http: reqwest::Client::new(),
+            max_retries: 3,
+            base_delay: Duration::from_millis(500),
+        }
+    }
+
+    pub fn retries(mut self, max_retries: u32, base_delay: Duration) -> Self {
+        self.max_retries = max_retries;
+        self.base_delay = base_delay;
+        self
+    }
+
+    pub fn sign(&self, body: &[u8]) -> String {
+        let mut mac =
+            Hmac::<Sha256>::new_from_slice(&self.secret).expect("HMAC accepts any key length");
+        mac.update(body);
+        format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+    }
+
+    pub async fn notify(&self, event: &AlertEvent) -> Result<(), reqwest::Error> {
+        let body = serde_json::to_vec(event).expect("alert events serialize");
+        let signature = self.sign(&body);
+
+        let mut attempt = 0;
+        loop {
+            let result = self
+                .http
+                .post(&self.url)
+                .header("Content-Type", "application/json")
+                .header("X-Signature", &signature)
+                .body(body.clone())
+                .send()
+                .await
+                .and_then(|resp| resp.error_for_status());
+
+            match result {
+                Ok(_) => return Ok(()),
+                Err(err) if attempt < self.max_retries => {
+                    let delay = self.base_delay * 2u32.saturating_pow(attempt);
+                    warn!(
+                        "webhook {} failed, retrying in {:?}: {}",
+                        self.url, delay, err
+                    );
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
+                }
+                Err(err) => return Err(err),
+            }
+        }
+    }
+}
+
+/// Checks [`AlertRule`]s against a [`Watchlist`] and notifies webhooks when a rule's
+/// condition becomes true. A rule fires once per crossing and re-arms when the condition
+/// stops being met. Rule pairs are registered with the watchlist.
+///
+/// The background task stops when the monitor is dropped.
+pub struct AlertMonitor {
+    task: JoinHandle<()>,
+}
+
+impl AlertMonitor {
+    pub fn start(
+        watchlist: Arc<Watchlist>,
+        rules: Vec<AlertRule>,
+        webhooks: Vec<WebhookNotifier>,
+        check_interval: Duration,
+    ) -> AlertMonitor {
+        for rule in &rules {
+            watchlist.register(rule.pair.clone());
+        }
+
+        let task = tokio::spawn(monitor_loop(watchlist, rules, webhooks, check_interval));
+
+        AlertMonitor { task }
+    }
+}
+
+impl Drop for AlertMonitor {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+fn quote_price(quote: &ZeroXQuoteResponse) -> Option<Decimal> {
+    Decimal::from_str(quote.price.as_deref()?).ok()
+}
+
+async fn monitor_loop(
+    watchlist: Arc<Watchlist>,
+    rules: Vec<AlertRule>,
+    webhooks: Vec<WebhookNotifier>,
+    check_interval: Duration,
+) {
+    let webhooks = Arc::new(webhooks);
+    let mut triggered = vec![false; rules.len()];
+
+    loop {
+        for (rule, triggered) in rules.iter().zip(triggered.iter_mut()) {
+            let Some(price) = watchlist
+                .latest(&rule.pair)
+                .and_then(|latest| quote_price(&latest.quote))
+            else {
+                continue;
+            };
+
+            let is_met = rule.condition.is_met(price);
+            if is_met && !*triggered {
+                let event = AlertEvent::new(rule, price);
+                let webhooks = webhooks.clone();
+                tokio::spawn(async move {
+                    for webhook in webhooks.iter() {
+                        if let Err(err) = webhook.notify(&event).await {
+                            warn!("giving up on webhook for alert {}: {}", event.rule, err);
+                        }
+                    }
+                });
+            }
+            *triggered = is_met;
+        }
+
+        tokio::time::sleep(check_interval).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ZeroXClient;
+    use wiremock::{
+        matchers::{header, method, path},
+        Mock, MockServer, ResponseTemplate,
+    };
+
+    #[test]
+    fn test_price_condition() {
+        let threshold = Decimal::from(2000);
+
+        assert!(PriceCondition::Above(threshold).is_met(Decimal::from(2001)));
+        assert!(!PriceCondition::Above(threshold).is_met(threshold));
+        assert!(PriceCondition::Below(threshold).is_met(Decimal::from(1999)));
+    }
+
+    #[tokio::test]
+    async fn test_webhook_retries_until_delivered() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(500))
+            .up_to_n_times(1)
+            .mount(&server)
+            .await;
+
+        let notifier = WebhookNotifier::new(format!("{}/hook", server.uri()), "secret")
+            .retries(2, Duration::from_millis(10));
+        let event = AlertEvent::new(
+            &AlertRule {
+                name: String::from("eth-above-2000"),
+                pair: WatchedPair::new("ETH", "DAI", "1"),
+                condition: PriceCondition::Above(Decimal::from(2000)),
+            },
+            Decimal::from(2001),
+        );
+        let signature = notifier.sign(&serde_json::to_vec(&event).unwrap());
+
+        Mock::given(method("POST"))
+            .and(path("/hook"))
+            .and(header("X-Signature", signature.as_str()))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        notifier.notify(&event).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_monitor_notifies_on_crossing() {
+        let upstream = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/swap/v1/quote"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(serde_json::json!({ "price": "2100" })),
+            )
+            .mount(&upstream)
+            .await;
+
+        let hooks = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&hooks)
+            .await;
+
+        let client = ZeroXClient::builder(1, String::from("test"))
+            .base_url(upstream.uri())
+            .build()
+            .unwrap();
+        let watchlist = Arc::new(Watchlist::new(Arc::new(client), Duration::from_millis(10)));
+
+        let _monitor = AlertMonitor::start(
+            watchlist,
+            vec![AlertRule {
+                name: String::from("eth-above-2000"),
+                pair: WatchedPair::new("ETH", "DAI", "1"),
+                condition: PriceCondition::Above(Decimal::from(2000)),
+            }],
+            vec![WebhookNotifier::new(hooks.uri(), "secret")],
+            Duration::from_millis(10),
+        );
+
+        tokio::time::sleep(Duration::from_millis(200)).await;
+    }
+}
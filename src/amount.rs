@@ -0,0 +1,140 @@
+use std::fmt;
+
+use ethers::core::types::U256;
+use thiserror::Error;
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum AmountParseError {
+    #[error("empty amount")]
+    Empty,
+    #[error("invalid amount: {0}")]
+    Invalid(String),
+    #[error("amount overflows 256 bits")]
+    Overflow,
+}
+
+/// A token amount in base units.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default, Hash)]
+pub struct Amount(U256);
+
+impl Amount {
+    pub fn new(base_units: U256) -> Amount {
+        Amount(base_units)
+    }
+
+    /// Parses a human decimal string such as `"1.5"` into base units of a token with
+    /// `decimals` decimals. Digits beyond `decimals` are rounded half up.
+    pub fn parse(amount: &str, decimals: u8) -> Result<Amount, AmountParseError> {
+        let amount = amount.trim().replace('_', "");
+        if amount.is_empty() {
+            return Err(AmountParseError::Empty);
+        }
+
+        let (whole, fraction) = amount.split_once('.').unwrap_or((&amount, ""));
+        let is_digits = |s: &str| s.bytes().all(|b| b.is_ascii_digit());
+        if (whole.is_empty() && fraction.is_empty()) || !is_digits(whole) || !is_digits(fraction) {
+            return Err(AmountParseError::Invalid(amount.clone()));
+        }
+
+        let decimals = decimals as usize;
+        let (kept, dropped) = fraction.split_at(fraction.len().min(decimals));
+        let digits = format!("{whole}{kept:0<decimals$}");
+        let digits = digits.trim_start_matches('0');
+
+        let mut value = if digits.is_empty() {
+            U256::zero()
+        } else {
+            U256::from_dec_str(digits).map_err(|_| AmountParseError::Overflow)?
+        };
+        if dropped.bytes().next().is_some_and(|b| b >= b'5') {
+            value = value
+                .checked_add(U256::one())
+                .ok_or(AmountParseError::Overflow)?;
+        }
+
+        Ok(Amount(value))
+    }
+
+    /// Parses an amount of a token with 18 decimals, e.g. ETH.
+    pub fn ether(amount: &str) -> Result<Amount, AmountParseError> {
+        Amount::parse(amount, 18)
+    }
+
+    pub fn as_u256(&self) -> U256 {
+        self.0
+    }
+}
+
+impl From<U256> for Amount {
+    fn from(base_units: U256) -> Amount {
+        Amount(base_units)
+    }
+}
+
+impl From<Amount> for U256 {
+    fn from(amount: Amount) -> U256 {
+        amount.0
+    }
+}
+
+/// Formats the amount in base units, as expected by the 0x API.
+impl fmt::Display for Amount {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_amount() {
+        assert_eq!(
+            Amount::parse("1.5", 6).unwrap(),
+            Amount::from(U256::from(1_500_000))
+        );
+        assert_eq!(Amount::parse("0.000001", 6).unwrap().to_string(), "1");
+        assert_eq!(Amount::parse(".5", 1).unwrap().to_string(), "5");
+        assert_eq!(Amount::parse("2.", 2).unwrap().to_string(), "200");
+        assert_eq!(Amount::parse("0", 18).unwrap(), Amount::default());
+        assert_eq!(
+            Amount::ether("1.5").unwrap().to_string(),
+            "1500000000000000000"
+        );
+        assert_eq!(Amount::parse("1_000", 0).unwrap().to_string(), "1000");
+    }
+
+    #[test]
+    fn test_parse_amount_rounding() {
+        assert_eq!(Amount::parse("1.2345", 2).unwrap().to_string(), "123");
+        assert_eq!(Amount::parse("1.2351", 2).unwrap().to_string(), "124");
+        assert_eq!(Amount::parse("0.4", 0).unwrap().to_string(), "0");
+    }
+
+    #[test]
+    fn test_parse_amount_errors() {
+        assert_eq!(Amount::parse("", 6), Err(AmountParseError::Empty));
+        assert!(matches!(
+            Amount::parse("1.2.3", 6),
+            Err(AmountParseError::Invalid(_))
+        ));
+        assert!(matches!(
+            Amount::parse("-1", 6),
+            Err(AmountParseError::Invalid(_))
+        ));
+        assert!(matches!(
+            Amount::parse(".", 6),
+            Err(AmountParseError::Invalid(_))
+        ));
+        assert_eq!(Amount::parse("1", 78), Err(AmountParseError::Overflow));
+        assert_eq!(
+            Amount::parse(&U256::MAX.to_string(), 0).unwrap().as_u256(),
+            U256::MAX
+        );
+        assert_eq!(
+            Amount::parse(&format!("{}.9", U256::MAX), 0),
+            Err(AmountParseError::Overflow)
+        );
+    }
+}
@@ -0,0 +1,135 @@
+//! An object-safe trait over the client's public operations, so applications can depend on
+//! `Arc<dyn ZeroXApi>` and substitute fakes in tests.
+
+use async_trait::async_trait;
+use serde_json::Value;
+
+use crate::{
+    nft::{NftOrderRecord, NftOrdersQuery, SignedNftOrder},
+    ZeroXClient, ZeroXClientError, ZeroXQuoteParams, ZeroXQuoteResponse,
+};
+
+/// The 0x API operations of [`ZeroXClient`], each with the same signature as the inherent
+/// method of the same name.
+#[async_trait]
+pub trait ZeroXApi: Send + Sync {
+    fn chain_id(&self) -> u64;
+
+    async fn get_quote(
+        &self,
+        params: ZeroXQuoteParams,
+    ) -> Result<ZeroXQuoteResponse, ZeroXClientError>;
+
+    async fn get_price(
+        &self,
+        params: ZeroXQuoteParams,
+    ) -> Result<ZeroXQuoteResponse, ZeroXClientError>;
+
+    async fn get_sources(&self) -> Result<Vec<String>, ZeroXClientError>;
+
+    async fn get_nft_orders(
+        &self,
+        query: NftOrdersQuery,
+    ) -> Result<Vec<NftOrderRecord>, ZeroXClientError>;
+
+    async fn post_nft_order(&self, order: &SignedNftOrder) -> Result<Value, ZeroXClientError>;
+}
+
+#[async_trait]
+impl ZeroXApi for ZeroXClient {
+    fn chain_id(&self) -> u64 {
+        ZeroXClient::chain_id(self)
+    }
+
+    async fn get_quote(
+        &self,
+        params: ZeroXQuoteParams,
+    ) -> Result<ZeroXQuoteResponse, ZeroXClientError> {
+        ZeroXClient::get_quote(self, params).await
+    }
+
+    async fn get_price(
+        &self,
+        params: ZeroXQuoteParams,
+    ) -> Result<ZeroXQuoteResponse, ZeroXClientError> {
+        ZeroXClient::get_price(self, params).await
+    }
+
+    async fn get_sources(&self) -> Result<Vec<String>, ZeroXClientError> {
+        ZeroXClient::get_sources(self).await
+    }
+
+    async fn get_nft_orders(
+        &self,
+        query: NftOrdersQuery,
+    ) -> Result<Vec<NftOrderRecord>, ZeroXClientError> {
+        ZeroXClient::get_nft_orders(self, query).await
+    }
+
+    async fn post_nft_order(&self, order: &SignedNftOrder) -> Result<Value, ZeroXClientError> {
+        ZeroXClient::post_nft_order(self, order).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+
+    struct FakeApi;
+
+    #[async_trait]
+    impl ZeroXApi for FakeApi {
+        fn chain_id(&self) -> u64 {
+            1
+        }
+
+        async fn get_quote(
+            &self,
+            _params: ZeroXQuoteParams,
+        ) -> Result<ZeroXQuoteResponse, ZeroXClientError> {
+            Ok(serde_json::from_value(
+                serde_json::json!({ "price": "2000" }),
+            )?)
+        }
+
+        async fn get_price(
+            &self,
+            params: ZeroXQuoteParams,
+        ) -> Result<ZeroXQuoteResponse, ZeroXClientError> {
+            self.get_quote(params).await
+        }
+
+        async fn get_sources(&self) -> Result<Vec<String>, ZeroXClientError> {
+            Ok(vec![String::from("Uniswap_V3")])
+        }
+
+        async fn get_nft_orders(
+            &self,
+            _query: NftOrdersQuery,
+        ) -> Result<Vec<NftOrderRecord>, ZeroXClientError> {
+            Ok(Vec::new())
+        }
+
+        async fn post_nft_order(&self, _order: &SignedNftOrder) -> Result<Value, ZeroXClientError> {
+            Ok(Value::Null)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_dyn_zerox_api() {
+        let apis: Vec<Arc<dyn ZeroXApi>> = vec![
+            Arc::new(FakeApi),
+            Arc::new(ZeroXClient::new(137, String::from("key")).unwrap()),
+        ];
+        assert_eq!(apis[1].chain_id(), 137);
+
+        let quote = apis[0]
+            .get_price(ZeroXQuoteParams::default())
+            .await
+            .unwrap();
+        assert_eq!(quote.price(), Some("2000"));
+        assert_eq!(apis[0].get_sources().await.unwrap(), ["Uniswap_V3"]);
+    }
+}
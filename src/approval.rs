@@ -0,0 +1,106 @@
+use ethers::{
+    abi::{self, Token},
+    core::types::{Address, Bytes, TransactionRequest, U256},
+    utils::id,
+};
+
+use crate::ZeroXQuoteResponse;
+
+/// Pseudo-address the 0x API uses for the chain's native token.
+pub const NATIVE_TOKEN_ADDRESS: &str = "0xeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeee";
+
+/// Calldata for `approve(spender, amount)`.
+pub fn approve_calldata(spender: Address, amount: U256) -> Bytes {
+    let mut data = id("approve(address,uint256)").to_vec();
+    data.extend(abi::encode(&[Token::Address(spender), Token::Uint(amount)]));
+    data.into()
+}
+
+/// Returns true if the quote sells the native token and therefore needs no approval.
+pub fn sells_native_token(quote: &ZeroXQuoteResponse) -> bool {
+    quote
+        .sell_token_address
+        .as_deref()
+        .is_some_and(|address| address.eq_ignore_ascii_case(NATIVE_TOKEN_ADDRESS))
+}
+
+/// Builds the ERC-20 `approve` of the quote's `sell_amount` to its `allowance_target`.
+pub fn build_approve_tx(
+    quote: &ZeroXQuoteResponse,
+) -> Result<TransactionRequest, Box<dyn std::error::Error>> {
+    let sell_amount = quote
+        .sell_amount
+        .as_ref()
+        .ok_or("Missing 'sell_amount' field")?;
+    let amount = U256::from_dec_str(sell_amount)?;
+
+    approve_tx(quote, amount)
+}
+
+/// Builds the ERC-20 `approve` of zero to the quote's `allowance_target`, revoking whatever
+/// allowance is left after the swap.
+///
+/// Uses `approve(spender, 0)` rather than `decreaseAllowance`, which is not part of ERC-20 and
+/// missing from many tokens.
+pub fn build_revoke_tx(
+    quote: &ZeroXQuoteResponse,
+) -> Result<TransactionRequest, Box<dyn std::error::Error>> {
+    approve_tx(quote, U256::zero())
+}
+
+fn approve_tx(
+    quote: &ZeroXQuoteResponse,
+    amount: U256,
+) -> Result<TransactionRequest, Box<dyn std::error::Error>> {
+    let token = quote
+        .sell_token_address
+        .as_ref()
+        .ok_or("Missing 'sell_token_address' field")?
+        .parse::<Address>()?;
+
+    let spender = quote
+        .allowance_target
+        .as_ref()
+        .ok_or("Missing 'allowance_target' field")?
+        .parse::<Address>()?;
+
+    let chain_id = quote.chain_id.ok_or("Missing 'chain_id' field")?;
+
+    Ok(TransactionRequest::new()
+        .to(token)
+        .data(approve_calldata(spender, amount))
+        .chain_id(chain_id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_revoke_tx() {
+        let spender = "0xdef1c0ded9bec7f1a1670819833240f027b25eff";
+        let quote: ZeroXQuoteResponse = serde_json::from_value(serde_json::json!({
+            "chainId": 1,
+            "sellTokenAddress": "0x6b175474e89094c44da98b954eedeac495271d0f",
+            "allowanceTarget": spender,
+            "sellAmount": "1000",
+        }))
+        .unwrap();
+
+        let approve = build_approve_tx(&quote).unwrap();
+        let revoke = build_revoke_tx(&quote).unwrap();
+        assert_eq!(revoke.to, approve.to);
+        assert_eq!(
+            revoke.data,
+            Some(approve_calldata(spender.parse().unwrap(), U256::zero()))
+        );
+
+        // Revoking does not need the amount the swap sold.
+        let quote = ZeroXQuoteResponse {
+            sell_amount: None,
+            ..quote
+        };
+        assert!(build_approve_tx(&quote).is_err());
+        assert!(build_revoke_tx(&quote).is_ok());
+    }
+}
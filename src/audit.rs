@@ -0,0 +1,296 @@
+use std::{
+    fs::{File, OpenOptions},
+    io::{BufRead, BufReader, Write},
+    path::PathBuf,
+    sync::Mutex,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use ethers::{core::types::TxHash, utils::hex};
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::Sha256;
+use thiserror::Error;
+
+use crate::ZeroXQuoteResponse;
+
+/// `prev_hash` of the first record in a trail.
+pub const GENESIS_HASH: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+#[derive(Error, Debug)]
+pub enum AuditError {
+    #[error("Audit store error: {0}")]
+    Store(Box<dyn std::error::Error + Send + Sync>),
+
+    #[error("Audit record {sequence} does not match its hash or chain")]
+    Tampered { sequence: u64 },
+}
+
+/// What was done with an audited quote.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum AuditDecision {
+    #[serde(rename_all = "camelCase")]
+    Executed {
+        tx_hash: TxHash,
+    },
+    Skipped {
+        reason: String,
+    },
+    Expired,
+}
+
+/// One entry of an [`AuditTrail`].
+///
+/// `hash` is the HMAC-SHA256 of the previous record's hash and this record's other fields, so
+/// editing, removing or reordering records breaks verification for everyone without the key.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditRecord {
+    pub sequence: u64,
+    /// Unix timestamp in milliseconds.
+    pub recorded_at: u64,
+    pub quote: Value,
+    pub decision: AuditDecision,
+    pub prev_hash: String,
+    pub hash: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct RecordBody<'a> {
+    sequence: u64,
+    recorded_at: u64,
+    quote: &'a Value,
+    decision: &'a AuditDecision,
+}
+
+impl AuditRecord {
+    fn compute_hash(&self, key: &[u8]) -> String {
+        let body = RecordBody {
+            sequence: self.sequence,
+            recorded_at: self.recorded_at,
+            quote: &self.quote,
+            decision: &self.decision,
+        };
+
+        let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts any key length");
+        mac.update(self.prev_hash.as_bytes());
+        mac.update(&serde_json::to_vec(&body).expect("audit records serialize"));
+        hex::encode(mac.finalize().into_bytes())
+    }
+}
+
+/// Checks that `records` form an unbroken chain from [`GENESIS_HASH`] under `key`.
+pub fn verify_chain(records: &[AuditRecord], key: &[u8]) -> Result<(), AuditError> {
+    let mut prev_hash = GENESIS_HASH;
+
+    for (sequence, record) in (0u64..).zip(records) {
+        if record.sequence != sequence
+            || record.prev_hash != prev_hash
+            || record.hash != record.compute_hash(key)
+        {
+            return Err(AuditError::Tampered {
+                sequence: record.sequence,
+            });
+        }
+        prev_hash = &record.hash;
+    }
+
+    Ok(())
+}
+
+/// Append-only storage for audit records.
+pub trait AuditStore: Send + Sync {
+    fn append(&self, record: &AuditRecord) -> Result<(), Box<dyn std::error::Error + Send + Sync>>;
+
+    /// Returns all records in the order they were appended.
+    fn load(&self) -> Result<Vec<AuditRecord>, Box<dyn std::error::Error + Send + Sync>>;
+}
+
+#[derive(Debug, Default)]
+pub struct MemoryAuditStore {
+    records: Mutex<Vec<AuditRecord>>,
+}
+
+impl AuditStore for MemoryAuditStore {
+    fn append(&self, record: &AuditRecord) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.records.lock().unwrap().push(record.clone());
+        Ok(())
+    }
+
+    fn load(&self) -> Result<Vec<AuditRecord>, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(self.records.lock().unwrap().clone())
+    }
+}
+
+/// Stores records as JSON lines, syncing each append to disk before it returns.
+#[derive(Debug)]
+pub struct FileAuditStore {
+    path: PathBuf,
+    file: Mutex<File>,
+}
+
+impl FileAuditStore {
+    pub fn open(path: impl Into<PathBuf>) -> std::io::Result<FileAuditStore> {
+        let path = path.into();
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+
+        Ok(FileAuditStore {
+            path,
+            file: Mutex::new(file),
+        })
+    }
+}
+
+impl AuditStore for FileAuditStore {
+    fn append(&self, record: &AuditRecord) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let mut line = serde_json::to_vec(record)?;
+        line.push(b'\n');
+
+        let mut file = self.file.lock().unwrap();
+        file.write_all(&line)?;
+        file.sync_data()?;
+        Ok(())
+    }
+
+    fn load(&self) -> Result<Vec<AuditRecord>, Box<dyn std::error::Error + Send + Sync>> {
+        let _file = self.file.lock().unwrap();
+
+        BufReader::new(File::open(&self.path)?)
+            .lines()
+            .filter(|line| !line.as_ref().is_ok_and(|line| line.is_empty()))
+            .map(|line| Ok(serde_json::from_str(&line?)?))
+            .collect()
+    }
+}
+
+/// Records every quote and the decision taken on it in a tamper-evident, HMAC-chained log.
+pub struct AuditTrail {
+    store: Box<dyn AuditStore>,
+    key: Vec<u8>,
+    head: Mutex<(u64, String)>,
+}
+
+impl AuditTrail {
+    /// Opens a trail on `store`, verifying any existing records and continuing their chain.
+    pub fn new(
+        store: impl AuditStore + 'static,
+        key: impl Into<Vec<u8>>,
+    ) -> Result<AuditTrail, AuditError> {
+        let key = key.into();
+        let records = store.load().map_err(AuditError::Store)?;
+        verify_chain(&records, &key)?;
+
+        let head = match records.last() {
+            Some(record) => (record.sequence + 1, record.hash.clone()),
+            None => (0, String::from(GENESIS_HASH)),
+        };
+
+        Ok(AuditTrail {
+            store: Box::new(store),
+            key,
+            head: Mutex::new(head),
+        })
+    }
+
+    pub fn record(
+        &self,
+        quote: &ZeroXQuoteResponse,
+        decision: AuditDecision,
+    ) -> Result<AuditRecord, AuditError> {
+        let quote = serde_json::to_value(quote).map_err(|err| AuditError::Store(Box::new(err)))?;
+
+        let mut head = self.head.lock().unwrap();
+        let mut record = AuditRecord {
+            sequence: head.0,
+            recorded_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_millis() as u64,
+            quote,
+            decision,
+            prev_hash: head.1.clone(),
+            hash: String::new(),
+        };
+        record.hash = record.compute_hash(&self.key);
+
+        self.store.append(&record).map_err(AuditError::Store)?;
+        *head = (record.sequence + 1, record.hash.clone());
+
+        Ok(record)
+    }
+
+    /// Loads the stored records and verifies the whole chain.
+    pub fn verify(&self) -> Result<Vec<AuditRecord>, AuditError> {
+        let records = self.store.load().map_err(AuditError::Store)?;
+        verify_chain(&records, &self.key)?;
+        Ok(records)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn quote(price: &str) -> ZeroXQuoteResponse {
+        serde_json::from_value(serde_json::json!({ "price": price })).unwrap()
+    }
+
+    #[test]
+    fn test_tampering_breaks_chain() {
+        let trail = AuditTrail::new(MemoryAuditStore::default(), "secret").unwrap();
+        trail
+            .record(&quote("2000"), AuditDecision::Expired)
+            .unwrap();
+        trail
+            .record(
+                &quote("2001"),
+                AuditDecision::Executed {
+                    tx_hash: TxHash::repeat_byte(1),
+                },
+            )
+            .unwrap();
+
+        let mut records = trail.verify().unwrap();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[1].prev_hash, records[0].hash);
+
+        records[0].quote["price"] = Value::from("1900");
+        assert!(matches!(
+            verify_chain(&records, b"secret"),
+            Err(AuditError::Tampered { sequence: 0 })
+        ));
+
+        records.remove(0);
+        assert!(verify_chain(&records, b"secret").is_err());
+    }
+
+    #[test]
+    fn test_file_store_resumes_chain() {
+        let path = std::env::temp_dir().join(format!("zerox-audit-{}.jsonl", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        let trail = AuditTrail::new(FileAuditStore::open(&path).unwrap(), "secret").unwrap();
+        trail
+            .record(
+                &quote("2000"),
+                AuditDecision::Skipped {
+                    reason: String::from("price impact too high"),
+                },
+            )
+            .unwrap();
+        drop(trail);
+
+        let trail = AuditTrail::new(FileAuditStore::open(&path).unwrap(), "secret").unwrap();
+        let record = trail
+            .record(&quote("2001"), AuditDecision::Expired)
+            .unwrap();
+        assert_eq!(record.sequence, 1);
+        assert_eq!(trail.verify().unwrap().len(), 2);
+
+        assert!(AuditTrail::new(FileAuditStore::open(&path).unwrap(), "other key").is_err());
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,21 @@
+use ethers::{
+    abi::{self, Token},
+    core::types::{Address, TransactionRequest, U256},
+    providers::Middleware,
+    utils::id,
+};
+
+/// Reads `token.balanceOf(owner)` via `eth_call`.
+pub async fn erc20_balance_of<M: Middleware>(
+    provider: &M,
+    token: Address,
+    owner: Address,
+) -> Result<U256, M::Error> {
+    let mut data = id("balanceOf(address)").to_vec();
+    data.extend(abi::encode(&[Token::Address(owner)]));
+
+    let tx = TransactionRequest::new().to(token).data(data);
+    let result = provider.call(&tx.into(), None).await?;
+
+    Ok(U256::from_big_endian(&result[..result.len().min(32)]))
+}
@@ -0,0 +1,143 @@
+//! Quote responses kept as the raw body and read through a borrowed view, for callers that
+//! only look at a few fields and want to avoid allocating a `String` for each.
+
+use serde::Deserialize;
+
+use crate::{FromBody, ZeroXClient, ZeroXClientError, ZeroXQuoteParams};
+
+/// The body of a quote or price response, as received.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QuoteBody {
+    body: Vec<u8>,
+}
+
+impl QuoteBody {
+    pub fn new(body: impl Into<Vec<u8>>) -> QuoteBody {
+        QuoteBody { body: body.into() }
+    }
+
+    /// Parses the body into a view borrowing its strings from the buffer.
+    pub fn view(&self) -> Result<ZeroXQuoteResponseRef<'_>, serde_json::Error> {
+        serde_json::from_slice(&self.body)
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.body
+    }
+
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.body
+    }
+}
+
+impl FromBody for QuoteBody {
+    fn from_body(body: Vec<u8>) -> Result<QuoteBody, ZeroXClientError> {
+        Ok(QuoteBody { body })
+    }
+}
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct SourceRef<'a> {
+    pub name: Option<&'a str>,
+    pub proportion: Option<&'a str>,
+}
+
+/// A borrowed view of [`ZeroXQuoteResponse`](crate::ZeroXQuoteResponse). Fields that are not
+/// listed, such as `orders`, are skipped without being allocated. String fields must not
+/// contain JSON escapes, which 0x does not use in any of them.
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct ZeroXQuoteResponseRef<'a> {
+    pub chain_id: Option<i32>,
+    pub price: Option<&'a str>,
+    pub guaranteed_price: Option<&'a str>,
+    pub estimated_price_impact: Option<&'a str>,
+    pub to: Option<&'a str>,
+    pub data: Option<&'a str>,
+    pub value: Option<&'a str>,
+    pub gas: Option<&'a str>,
+    pub estimated_gas: Option<&'a str>,
+    pub gas_price: Option<&'a str>,
+    pub protocol_fee: Option<&'a str>,
+    pub buy_token_address: Option<&'a str>,
+    pub sell_token_address: Option<&'a str>,
+    pub buy_amount: Option<&'a str>,
+    pub sell_amount: Option<&'a str>,
+    pub allowance_target: Option<&'a str>,
+    #[serde(borrow)]
+    pub sources: Option<Vec<SourceRef<'a>>>,
+}
+
+impl ZeroXClient {
+    /// Fetches a quote without parsing it; read it with [`QuoteBody::view`].
+    pub async fn get_quote_body(
+        &self,
+        params: ZeroXQuoteParams,
+    ) -> Result<QuoteBody, ZeroXClientError> {
+        self.screen(&params).await?;
+        let body: QuoteBody = self
+            .get("/swap/v1/quote", &self.quote_query(params.clone()))
+            .await?
+            .data;
+        self.validate_unparsed(&params, || serde_json::from_slice(&body.body))
+            .await?;
+        Ok(body)
+    }
+
+    pub async fn get_price_body(
+        &self,
+        params: ZeroXQuoteParams,
+    ) -> Result<QuoteBody, ZeroXClientError> {
+        self.screen(&params).await?;
+        let body: QuoteBody = self
+            .get("/swap/v1/price", &self.quote_query(params.clone()))
+            .await?
+            .data;
+        self.validate_unparsed(&params, || serde_json::from_slice(&body.body))
+            .await?;
+        Ok(body)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use wiremock::{
+        matchers::{method, path},
+        Mock, MockServer, ResponseTemplate,
+    };
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_quote_body_view() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/swap/v1/quote"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "price": "2000.5",
+                "buyAmount": "2000500000",
+                "sources": [{ "name": "Uniswap_V3", "proportion": "1" }],
+                "orders": [{ "source": "Uniswap_V3" }],
+            })))
+            .mount(&server)
+            .await;
+
+        let client = ZeroXClient::builder(1, String::from("key"))
+            .base_url(server.uri())
+            .build()
+            .unwrap();
+        let body = client
+            .get_quote_body(ZeroXQuoteParams::default())
+            .await
+            .unwrap();
+
+        let quote = body.view().unwrap();
+        assert_eq!(quote.price, Some("2000.5"));
+        assert_eq!(quote.buy_amount, Some("2000500000"));
+        assert_eq!(quote.sources.unwrap()[0].name, Some("Uniswap_V3"));
+        assert!(quote.to.is_none());
+    }
+}
@@ -0,0 +1,370 @@
+//! Declarative client configuration loaded from a file.
+//!
+//! The format follows the extension: `.json` is always supported, `.toml` needs the `toml`
+//! feature and `.yaml`/`.yml` the `yaml` feature. Durations are in milliseconds.
+//!
+//! ```toml
+//! api_key = "default-key"
+//! timeout_ms = 10000
+//!
+//! [retry]
+//! max_retries = 2
+//! base_delay_ms = 200
+//!
+//! [timeouts]
+//! quote_ms = 2000
+//! gasless_submit_ms = 15000
+//!
+//! [rate_limit]
+//! requests = 10
+//! per_ms = 1000
+//!
+//! [chains.137]
+//! api_key = "polygon-key"
+//! base_url = "https://gateway.example.com/polygon"
+//! ```
+
+use std::{collections::HashMap, path::Path, time::Duration};
+
+use serde::Deserialize;
+use thiserror::Error;
+
+use crate::{
+    default_base_url, ApiKeys, ClientConfig, Endpoint, ExponentialBackoff, MultiChainClient,
+    NoRetry, ZeroXClient, ZeroXClientBuilder, ZeroXClientError,
+};
+
+#[derive(Error, Debug)]
+pub enum ConfigError {
+    #[error("Failed to read config file: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Invalid config file: {0}")]
+    Parse(String),
+    #[error("Unsupported config file format: {0}")]
+    UnsupportedFormat(String),
+    #[error("Chain {0} is not configured")]
+    MissingChain(u64),
+    #[error(transparent)]
+    Client(#[from] ZeroXClientError),
+}
+
+#[derive(Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct RetryConfig {
+    /// `0` disables retries.
+    pub max_retries: Option<u32>,
+    pub base_delay_ms: Option<u64>,
+    pub max_delay_ms: Option<u64>,
+}
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct RateLimitConfig {
+    pub requests: u32,
+    pub per_ms: u64,
+}
+
+/// Per-endpoint timeouts, overriding `timeout_ms` for those requests.
+#[derive(Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct TimeoutsConfig {
+    pub price_ms: Option<u64>,
+    pub quote_ms: Option<u64>,
+    pub orderbook_ms: Option<u64>,
+    pub gasless_submit_ms: Option<u64>,
+}
+
+impl TimeoutsConfig {
+    fn apply(&self, mut builder: ZeroXClientBuilder) -> ZeroXClientBuilder {
+        let timeouts = [
+            (Endpoint::Price, self.price_ms),
+            (Endpoint::Quote, self.quote_ms),
+            (Endpoint::Orderbook, self.orderbook_ms),
+            (Endpoint::GaslessSubmit, self.gasless_submit_ms),
+        ];
+        for (endpoint, timeout) in timeouts {
+            if let Some(timeout) = timeout {
+                builder = builder.endpoint_timeout(endpoint, Duration::from_millis(timeout));
+            }
+        }
+        builder
+    }
+}
+
+/// Settings for one chain, overriding the top-level ones.
+#[derive(Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct ChainConfig {
+    pub api_key: Option<String>,
+    pub base_url: Option<String>,
+    pub fallback_base_url: Option<String>,
+    pub rate_limit: Option<RateLimitConfig>,
+}
+
+/// The contents of a client configuration file.
+#[derive(Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct ConfigFile {
+    /// Used for chains without their own key.
+    pub api_key: Option<String>,
+    pub v2_base_url: Option<String>,
+    /// Overall timeout for each HTTP attempt.
+    pub timeout_ms: Option<u64>,
+    pub timeouts: Option<TimeoutsConfig>,
+    pub hedge_after_ms: Option<u64>,
+    pub pool_idle_timeout_ms: Option<u64>,
+    pub tcp_keepalive_ms: Option<u64>,
+    pub max_response_size: Option<usize>,
+    pub retry: Option<RetryConfig>,
+    pub rate_limit: Option<RateLimitConfig>,
+    #[serde(default, deserialize_with = "chain_map")]
+    pub chains: HashMap<u64, ChainConfig>,
+}
+
+impl ConfigFile {
+    pub fn from_file(path: impl AsRef<Path>) -> Result<ConfigFile, ConfigError> {
+        let path = path.as_ref();
+        let contents = std::fs::read_to_string(path)?;
+        let extension = path
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .unwrap_or_default();
+
+        match extension {
+            "json" => {
+                serde_json::from_str(&contents).map_err(|e| ConfigError::Parse(e.to_string()))
+            }
+            #[cfg(feature = "toml")]
+            "toml" => toml::from_str(&contents).map_err(|e| ConfigError::Parse(e.to_string())),
+            #[cfg(feature = "yaml")]
+            "yaml" | "yml" => {
+                serde_yaml::from_str(&contents).map_err(|e| ConfigError::Parse(e.to_string()))
+            }
+            other => Err(ConfigError::UnsupportedFormat(other.to_string())),
+        }
+    }
+
+    pub fn chain(&self, chain_id: u64) -> Option<&ChainConfig> {
+        self.chains.get(&chain_id)
+    }
+
+    pub fn api_keys(&self) -> ApiKeys {
+        let mut api_keys = self.api_key.clone().map(ApiKeys::new).unwrap_or_default();
+        for (&chain_id, chain) in &self.chains {
+            if let Some(api_key) = &chain.api_key {
+                api_keys = api_keys.chain(chain_id, api_key.clone());
+            }
+        }
+        api_keys
+    }
+
+    /// The live settings for `chain_id`, e.g. to pass to [`ZeroXClient::reload`].
+    pub fn client_config(&self, chain_id: u64) -> Result<ClientConfig, ConfigError> {
+        let chain = self.chain(chain_id).cloned().unwrap_or_default();
+        let api_key = self
+            .api_keys()
+            .get(chain_id)
+            .ok_or(ZeroXClientError::MissingApiKey(chain_id))?
+            .to_string();
+        let base_url = match chain.base_url {
+            Some(base_url) => base_url,
+            None => default_base_url(chain_id).ok_or(ConfigError::MissingChain(chain_id))?,
+        };
+
+        Ok(ClientConfig {
+            api_key,
+            base_url,
+            rate_limit: chain
+                .rate_limit
+                .or(self.rate_limit)
+                .map(|limit| (limit.requests, Duration::from_millis(limit.per_ms))),
+        })
+    }
+
+    /// Applies the top-level settings and those of the builder's chain to `builder`.
+    pub fn apply(&self, mut builder: ZeroXClientBuilder) -> ZeroXClientBuilder {
+        let chain = self.chain(builder.chain_id).cloned().unwrap_or_default();
+
+        if let Some(api_key) = chain.api_key {
+            builder.api_key = api_key;
+        }
+        if let Some(base_url) = chain.base_url {
+            builder = builder.base_url(base_url);
+        }
+        if let Some(fallback_base_url) = chain.fallback_base_url {
+            builder = builder.fallback_base_url(fallback_base_url);
+        }
+        if let Some(v2_base_url) = &self.v2_base_url {
+            builder = builder.v2_base_url(v2_base_url.clone());
+        }
+        if let Some(limit) = chain.rate_limit.or(self.rate_limit) {
+            builder = builder.rate_limit(limit.requests, Duration::from_millis(limit.per_ms));
+        }
+        if let Some(timeout) = self.timeout_ms {
+            builder = builder.timeout(Duration::from_millis(timeout));
+        }
+        if let Some(timeouts) = &self.timeouts {
+            builder = timeouts.apply(builder);
+        }
+        if let Some(hedge_after) = self.hedge_after_ms {
+            builder = builder.hedge_after(Duration::from_millis(hedge_after));
+        }
+        if let Some(idle) = self.pool_idle_timeout_ms {
+            builder = builder.pool_idle_timeout(Some(Duration::from_millis(idle)));
+        }
+        if let Some(interval) = self.tcp_keepalive_ms {
+            builder = builder.tcp_keepalive(Some(Duration::from_millis(interval)));
+        }
+        if let Some(max) = self.max_response_size {
+            builder = builder.max_response_size(Some(max));
+        }
+        if let Some(retry) = &self.retry {
+            builder = match retry.max_retries {
+                Some(0) => builder.retry_policy(NoRetry),
+                max_retries => {
+                    let default = ExponentialBackoff::default();
+                    builder.retry_policy(ExponentialBackoff {
+                        max_retries: max_retries.unwrap_or(default.max_retries),
+                        base_delay: retry
+                            .base_delay_ms
+                            .map_or(default.base_delay, Duration::from_millis),
+                        max_delay: retry
+                            .max_delay_ms
+                            .map_or(default.max_delay, Duration::from_millis),
+                    })
+                }
+            };
+        }
+
+        builder
+    }
+
+    /// A builder for `chain_id` with this configuration applied.
+    pub fn builder(&self, chain_id: u64) -> Result<ZeroXClientBuilder, ConfigError> {
+        let api_key = self
+            .api_keys()
+            .get(chain_id)
+            .ok_or(ZeroXClientError::MissingApiKey(chain_id))?
+            .to_string();
+        Ok(self.apply(ZeroXClient::builder(chain_id, api_key)))
+    }
+
+    pub fn client(&self, chain_id: u64) -> Result<ZeroXClient, ConfigError> {
+        Ok(self.builder(chain_id)?.build()?)
+    }
+
+    /// A client for every configured chain, plus any other chain if a default key is set.
+    pub fn multi_chain(&self) -> MultiChainClient {
+        let config = self.clone();
+        MultiChainClient::new(self.api_keys()).configure(move |builder| config.apply(builder))
+    }
+}
+
+impl ClientConfig {
+    /// Loads the settings for `chain_id` from a configuration file; see [`ConfigFile`].
+    pub fn from_file(path: impl AsRef<Path>, chain_id: u64) -> Result<ClientConfig, ConfigError> {
+        ConfigFile::from_file(path)?.client_config(chain_id)
+    }
+}
+
+/// Chain ids are map keys, which TOML and JSON only allow as strings while YAML parses them as
+/// integers.
+fn chain_map<'de, D>(deserializer: D) -> Result<HashMap<u64, ChainConfig>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(Deserialize, PartialEq, Eq, Hash)]
+    #[serde(untagged)]
+    enum ChainKey {
+        Id(u64),
+        Text(String),
+    }
+
+    HashMap::<ChainKey, ChainConfig>::deserialize(deserializer)?
+        .into_iter()
+        .map(|(key, chain)| match key {
+            ChainKey::Id(chain_id) => Ok((chain_id, chain)),
+            ChainKey::Text(text) => text
+                .parse()
+                .map(|chain_id| (chain_id, chain))
+                .map_err(|_| serde::de::Error::custom(format!("invalid chain id '{text}'"))),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+
+    fn write_temp(name: &str, contents: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("zerox-{}-{name}", std::process::id()));
+        std::fs::write(&path, contents).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_from_file_json() {
+        let path = write_temp(
+            "config.json",
+            r#"{
+                "api_key": "default",
+                "timeout_ms": 5000,
+                "timeouts": { "quote_ms": 2000 },
+                "retry": { "max_retries": 0 },
+                "rate_limit": { "requests": 10, "per_ms": 1000 },
+                "chains": {
+                    "137": { "api_key": "polygon", "base_url": "http://localhost:1234" }
+                }
+            }"#,
+        );
+
+        let config = ConfigFile::from_file(&path).unwrap();
+        assert_eq!(
+            config.chain(137).unwrap().api_key.as_deref(),
+            Some("polygon")
+        );
+
+        assert_eq!(config.timeouts.as_ref().unwrap().quote_ms, Some(2000));
+
+        let polygon = ClientConfig::from_file(&path, 137).unwrap();
+        assert_eq!(polygon.api_key, "polygon");
+        assert_eq!(polygon.base_url, "http://localhost:1234");
+        assert_eq!(polygon.rate_limit, Some((10, Duration::from_secs(1))));
+
+        let mainnet = config.client(1).unwrap();
+        assert_eq!(mainnet.config().api_key, "default");
+        assert_eq!(mainnet.base_url(), "https://api.0x.org");
+        assert!(matches!(
+            config.client_config(424242),
+            Err(ConfigError::MissingChain(424242))
+        ));
+
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[cfg(all(feature = "toml", feature = "yaml"))]
+    #[test]
+    fn test_from_file_toml_and_yaml() {
+        let toml = write_temp(
+            "config.toml",
+            "hedge_after_ms = 300\n[chains.10]\napi_key = \"optimism\"\n",
+        );
+        let yaml = write_temp(
+            "config.yaml",
+            "hedge_after_ms: 300\nchains:\n  10:\n    api_key: optimism\n",
+        );
+
+        let from_toml = ConfigFile::from_file(&toml).unwrap();
+        assert_eq!(from_toml, ConfigFile::from_file(&yaml).unwrap());
+        assert_eq!(from_toml.client(10).unwrap().config().api_key, "optimism");
+        assert!(matches!(
+            from_toml.client(1),
+            Err(ConfigError::Client(ZeroXClientError::MissingApiKey(1)))
+        ));
+
+        std::fs::remove_file(toml).unwrap();
+        std::fs::remove_file(yaml).unwrap();
+    }
+}
@@ -0,0 +1,145 @@
+//! Parameters set once on the client and merged into every swap quote and price request.
+
+use std::collections::HashMap;
+
+use crate::{FeeBps, Percentage, QuoteMode, ZeroXQuoteParams, ZeroXQuoteParamsV2};
+
+/// Defaults for fields the request leaves unset; values set on the request always win. Fees
+/// and slippage are converted to each API version's units.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct QuoteDefaults {
+    pub slippage: Option<Percentage>,
+    /// Slippage for specific chains, taking precedence over `slippage`.
+    pub chain_slippage: HashMap<u64, Percentage>,
+    /// v1 only; v2 has no affiliate parameter.
+    pub affiliate_address: Option<String>,
+    pub fee_recipient: Option<String>,
+    pub fee: Option<FeeBps>,
+    /// v1 only.
+    pub skip_validation: Option<bool>,
+    /// v1 only. Fills the parameters the mode covers; `skip_validation` above takes
+    /// precedence.
+    pub mode: Option<QuoteMode>,
+}
+
+impl QuoteDefaults {
+    /// Sets the default slippage on `chain_id`.
+    pub fn chain_slippage(mut self, chain_id: u64, slippage: impl Into<Percentage>) -> Self {
+        self.chain_slippage.insert(chain_id, slippage.into());
+        self
+    }
+
+    fn slippage(&self, chain_id: u64) -> Option<Percentage> {
+        self.chain_slippage
+            .get(&chain_id)
+            .copied()
+            .or(self.slippage)
+    }
+
+    pub(crate) fn apply(&self, mut params: ZeroXQuoteParams, chain_id: u64) -> ZeroXQuoteParams {
+        params.slippage_percentage = params
+            .slippage_percentage
+            .or_else(|| self.slippage(chain_id));
+        params.affiliate_address = params
+            .affiliate_address
+            .or_else(|| self.affiliate_address.clone());
+        params.fee_recipient = params.fee_recipient.or_else(|| self.fee_recipient.clone());
+        params.buy_token_percentage_fee = params
+            .buy_token_percentage_fee
+            .or_else(|| self.fee.map(Percentage::from));
+        params.skip_validation = params
+            .skip_validation
+            .or(self.skip_validation)
+            .or(self.mode.map(QuoteMode::skip_validation));
+        params.include_price_comparisons = params
+            .include_price_comparisons
+            .or(self.mode.map(QuoteMode::include_price_comparisons));
+        params
+    }
+
+    pub(crate) fn apply_v2(
+        &self,
+        mut params: ZeroXQuoteParamsV2,
+        chain_id: u64,
+    ) -> ZeroXQuoteParamsV2 {
+        params.slippage_bps = params
+            .slippage_bps
+            .or_else(|| self.slippage(chain_id).and_then(Percentage::to_bps));
+        params.swap_fee_recipient = params
+            .swap_fee_recipient
+            .or_else(|| self.fee_recipient.clone());
+        params.swap_fee_bps = params.swap_fee_bps.or(self.fee);
+        params
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rust_decimal::Decimal;
+
+    use super::*;
+
+    #[test]
+    fn test_request_values_win() {
+        let defaults = QuoteDefaults {
+            slippage: Some(Percentage::from_percent(Decimal::ONE)),
+            affiliate_address: Some(String::from("0xaff")),
+            fee_recipient: Some(String::from("0xfee")),
+            fee: Some(FeeBps::new(25)),
+            skip_validation: Some(true),
+            ..Default::default()
+        };
+
+        let v1 = defaults.apply(
+            ZeroXQuoteParams {
+                slippage_percentage: Some(FeeBps::new(300).into()),
+                ..Default::default()
+            },
+            1,
+        );
+        assert_eq!(
+            v1.slippage_percentage,
+            Some(Percentage::from(FeeBps::new(300)))
+        );
+        assert_eq!(v1.affiliate_address.as_deref(), Some("0xaff"));
+        assert_eq!(
+            v1.buy_token_percentage_fee,
+            Some(Percentage::from(FeeBps::new(25)))
+        );
+        assert_eq!(v1.skip_validation, Some(true));
+
+        let v2 = defaults.apply_v2(
+            ZeroXQuoteParamsV2 {
+                swap_fee_bps: Some(FeeBps::new(10)),
+                ..Default::default()
+            },
+            1,
+        );
+        assert_eq!(v2.slippage_bps, Some(FeeBps::new(100)));
+        assert_eq!(v2.swap_fee_recipient.as_deref(), Some("0xfee"));
+        assert_eq!(v2.swap_fee_bps, Some(FeeBps::new(10)));
+    }
+
+    #[test]
+    fn test_chain_slippage() {
+        let defaults = QuoteDefaults {
+            slippage: Some(Percentage::from(FeeBps::new(50))),
+            ..Default::default()
+        }
+        .chain_slippage(42161, FeeBps::new(200));
+
+        let slippage = |chain_id| {
+            defaults
+                .apply(ZeroXQuoteParams::default(), chain_id)
+                .slippage_percentage
+        };
+        assert_eq!(slippage(1), Some(Percentage::from(FeeBps::new(50))));
+        assert_eq!(slippage(42161), Some(Percentage::from(FeeBps::new(200))));
+        assert_eq!(
+            defaults
+                .apply_v2(ZeroXQuoteParamsV2::default(), 42161)
+                .slippage_bps,
+            Some(FeeBps::new(200))
+        );
+    }
+}
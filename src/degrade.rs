@@ -0,0 +1,105 @@
+//! Falling back from a firm quote to an indicative price when 0x cannot validate the quote,
+//! e.g. because the taker lacks the balance or allowance for the simulated swap.
+
+use crate::{
+    ErrorKind, WithMetadata, ZeroXClient, ZeroXClientError, ZeroXQuoteParams, ZeroXQuoteResponse,
+};
+
+/// Returned by [`ZeroXClient::get_quote_or_price`].
+#[derive(Debug)]
+pub enum QuoteOrPrice {
+    Quote(WithMetadata<ZeroXQuoteResponse>),
+    /// An indicative price for display only: it has no transaction data and must not be
+    /// executed.
+    Indicative {
+        price: WithMetadata<ZeroXQuoteResponse>,
+        /// Why the firm quote was rejected.
+        quote_error: ZeroXClientError,
+    },
+}
+
+impl QuoteOrPrice {
+    pub fn is_indicative(&self) -> bool {
+        matches!(self, QuoteOrPrice::Indicative { .. })
+    }
+
+    /// The quote or price, for display.
+    pub fn response(&self) -> &ZeroXQuoteResponse {
+        match self {
+            QuoteOrPrice::Quote(quote) => &quote.data,
+            QuoteOrPrice::Indicative { price, .. } => &price.data,
+        }
+    }
+
+    /// The firm quote, or `None` if only a price was available.
+    pub fn into_quote(self) -> Option<WithMetadata<ZeroXQuoteResponse>> {
+        match self {
+            QuoteOrPrice::Quote(quote) => Some(quote),
+            QuoteOrPrice::Indicative { .. } => None,
+        }
+    }
+}
+
+impl ZeroXClient {
+    /// Fetches a quote, falling back to an indicative price when `/quote` rejects the request
+    /// as invalid, e.g. because the swap fails simulation for the taker. Other errors, such
+    /// as rate limits, screening or quote validators, are returned as they are, and so is the
+    /// quote's error if the price request fails too.
+    pub async fn get_quote_or_price(
+        &self,
+        params: ZeroXQuoteParams,
+    ) -> Result<QuoteOrPrice, ZeroXClientError> {
+        let quote_error = match self.get_quote_with_metadata(params.clone()).await {
+            Ok(quote) => return Ok(QuoteOrPrice::Quote(quote)),
+            Err(err) if err.kind() == ErrorKind::InvalidRequest => err,
+            Err(err) => return Err(err),
+        };
+
+        match self.get_price_with_metadata(params).await {
+            Ok(price) => Ok(QuoteOrPrice::Indicative { price, quote_error }),
+            Err(_) => Err(quote_error),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use wiremock::{
+        matchers::{method, path},
+        Mock, MockServer, ResponseTemplate,
+    };
+
+    use super::*;
+    use crate::NoRetry;
+
+    #[tokio::test]
+    async fn test_falls_back_to_price() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/swap/v1/quote"))
+            .respond_with(ResponseTemplate::new(400))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/swap/v1/price"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(serde_json::json!({ "price": "2000" })),
+            )
+            .mount(&server)
+            .await;
+
+        let client = ZeroXClient::builder(1, String::from("test"))
+            .base_url(server.uri())
+            .retry_policy(NoRetry)
+            .build()
+            .unwrap();
+
+        let result = client
+            .get_quote_or_price(ZeroXQuoteParams::default())
+            .await
+            .unwrap();
+        assert!(result.is_indicative());
+        assert_eq!(result.response().price.as_deref(), Some("2000"));
+        assert!(result.into_quote().is_none());
+    }
+}
@@ -0,0 +1,90 @@
+use ethers::core::types::Address;
+
+use crate::ZeroXQuoteResponse;
+
+/// Returns the 0x Exchange Proxy address on `chain_id`, if known.
+pub fn exchange_proxy(chain_id: u64) -> Option<Address> {
+    let address = match chain_id {
+        1 | 56 | 137 | 42161 | 43114 | 42220 | 8453 | 11155111 => {
+            "0xdef1c0ded9bec7f1a1670819833240f027b25eff"
+        }
+        10 => "0xdef1abe32c034e558cdd535791643c58a13acc10",
+        250 => "0xdef189deaef76e379df891899eb5a00a94cbc250",
+        _ => return None,
+    };
+
+    Some(address.parse().expect("valid address"))
+}
+
+/// A quote field pointing at a contract that is not a known 0x deployment.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UntrustedTarget {
+    pub chain_id: u64,
+    pub field: &'static str,
+    pub address: Option<Address>,
+}
+
+/// Verifies that the quote's `to` and `allowance_target` are the 0x Exchange Proxy for the
+/// quote's chain. A zero `allowance_target` (no approval needed) is accepted.
+pub fn verify_quote_targets(quote: &ZeroXQuoteResponse) -> Result<(), UntrustedTarget> {
+    let chain_id = quote.chain_id.unwrap_or_default() as u64;
+    let untrusted = |field, address| UntrustedTarget {
+        chain_id,
+        field,
+        address,
+    };
+
+    let exchange_proxy = exchange_proxy(chain_id).ok_or_else(|| untrusted("chain_id", None))?;
+
+    let to = quote.to.as_ref().and_then(|to| to.parse::<Address>().ok());
+    if to != Some(exchange_proxy) {
+        return Err(untrusted("to", to));
+    }
+
+    if let Some(allowance_target) = &quote.allowance_target {
+        let allowance_target = allowance_target.parse::<Address>().ok();
+        if allowance_target != Some(exchange_proxy) && allowance_target != Some(Address::zero()) {
+            return Err(untrusted("allowance_target", allowance_target));
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn quote(to: &str, allowance_target: &str) -> ZeroXQuoteResponse {
+        serde_json::from_value(serde_json::json!({
+            "chainId": 1,
+            "to": to,
+            "allowanceTarget": allowance_target,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_verify_quote_targets() {
+        let proxy = "0xdef1c0ded9bec7f1a1670819833240f027b25eff";
+        let other = "0xd8dA6BF26964aF9D7eEd9e03E53415D37aA96045";
+
+        assert!(verify_quote_targets(&quote(proxy, proxy)).is_ok());
+        assert!(
+            verify_quote_targets(&quote(proxy, "0x0000000000000000000000000000000000000000"))
+                .is_ok()
+        );
+        assert_eq!(
+            verify_quote_targets(&quote(other, proxy))
+                .unwrap_err()
+                .field,
+            "to"
+        );
+        assert_eq!(
+            verify_quote_targets(&quote(proxy, other))
+                .unwrap_err()
+                .field,
+            "allowance_target"
+        );
+    }
+}
@@ -0,0 +1,156 @@
+//! Retrying a quote with smaller sell amounts when the full amount cannot be filled, for bots
+//! that should fill what they can.
+
+use ethers::core::types::U256;
+
+use crate::{ErrorKind, ZeroXClient, ZeroXClientError, ZeroXQuoteParams, ZeroXQuoteResponse};
+
+/// Halvings between the floor and the requested amount before settling; the result is within
+/// 1/256 of that range of the largest fillable amount.
+const SEARCH_STEPS: u32 = 8;
+
+/// Returned by [`ZeroXClient::get_quote_downsized`].
+#[derive(Debug)]
+pub struct DownsizedQuote {
+    pub quote: ZeroXQuoteResponse,
+    /// The sell amount `quote` was requested with.
+    pub sell_amount: U256,
+    /// How much less than requested is sold, zero if the full amount was quoted.
+    pub shortfall: U256,
+}
+
+impl DownsizedQuote {
+    pub fn is_partial(&self) -> bool {
+        !self.shortfall.is_zero()
+    }
+}
+
+impl ZeroXClient {
+    /// Fetches a quote, and if 0x rejects the sell amount as invalid, as it does when there is
+    /// not enough liquidity, binary searches for the largest amount down to `floor` that it
+    /// does quote.
+    ///
+    /// The floor is tried first, so a request that fails for any other reason costs one
+    /// extra request and returns the original error. Errors other than invalid requests, such
+    /// as rate limits, are returned as they are.
+    pub async fn get_quote_downsized(
+        &self,
+        params: ZeroXQuoteParams,
+        floor: U256,
+    ) -> Result<DownsizedQuote, ZeroXClientError> {
+        let err = match self.get_quote(params.clone()).await {
+            Ok(quote) => {
+                return Ok(DownsizedQuote {
+                    quote,
+                    sell_amount: U256::from_dec_str(&params.sell_amount).unwrap_or_default(),
+                    shortfall: U256::zero(),
+                })
+            }
+            Err(err) if err.kind() == ErrorKind::InvalidRequest => err,
+            Err(err) => return Err(err),
+        };
+        let Ok(requested) = U256::from_dec_str(&params.sell_amount) else {
+            return Err(err);
+        };
+        if floor.is_zero() || floor >= requested {
+            return Err(err);
+        }
+
+        let quote_for = |amount: U256| {
+            self.get_quote(ZeroXQuoteParams {
+                sell_amount: amount.to_string(),
+                ..params.clone()
+            })
+        };
+        let mut best = match quote_for(floor).await {
+            Ok(quote) => (floor, quote),
+            Err(floor_err) if floor_err.kind() == ErrorKind::InvalidRequest => return Err(err),
+            Err(floor_err) => return Err(floor_err),
+        };
+
+        // `best.0` is fillable and `unfillable` is not.
+        let mut unfillable = requested;
+        for _ in 0..SEARCH_STEPS {
+            let amount = best.0 + (unfillable - best.0) / 2;
+            if amount == best.0 {
+                break;
+            }
+            match quote_for(amount).await {
+                Ok(quote) => best = (amount, quote),
+                Err(err) if err.kind() == ErrorKind::InvalidRequest => unfillable = amount,
+                Err(err) => return Err(err),
+            }
+        }
+
+        let (sell_amount, quote) = best;
+        Ok(DownsizedQuote {
+            quote,
+            sell_amount,
+            shortfall: requested - sell_amount,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use wiremock::{matchers::method, Mock, MockServer, Request, ResponseTemplate};
+
+    use super::*;
+    use crate::NoRetry;
+
+    #[tokio::test]
+    async fn test_get_quote_downsized() {
+        // Up to 700 can be filled.
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(|request: &Request| {
+                let amount: u64 = request
+                    .url
+                    .query_pairs()
+                    .find(|(name, _)| name == "sellAmount")
+                    .and_then(|(_, amount)| amount.parse().ok())
+                    .unwrap();
+                if amount <= 700 {
+                    ResponseTemplate::new(200)
+                        .set_body_json(serde_json::json!({ "sellAmount": amount.to_string() }))
+                } else {
+                    ResponseTemplate::new(400)
+                }
+            })
+            .mount(&server)
+            .await;
+
+        let client = ZeroXClient::builder(1, String::from("key"))
+            .base_url(server.uri())
+            .retry_policy(NoRetry)
+            .build()
+            .unwrap();
+        let params = |sell_amount: &str| ZeroXQuoteParams {
+            sell_token: String::from("WETH"),
+            buy_token: String::from("USDC"),
+            sell_amount: String::from(sell_amount),
+            ..Default::default()
+        };
+
+        let quote = client
+            .get_quote_downsized(params("1000"), U256::from(100))
+            .await
+            .unwrap();
+        assert!(quote.is_partial());
+        assert!(quote.sell_amount <= U256::from(700));
+        assert!(quote.sell_amount > U256::from(690));
+        assert_eq!(quote.shortfall, U256::from(1000) - quote.sell_amount);
+        assert_eq!(quote.quote.sell_amount().unwrap(), quote.sell_amount);
+
+        let full = client
+            .get_quote_downsized(params("500"), U256::from(100))
+            .await
+            .unwrap();
+        assert!(!full.is_partial());
+
+        assert!(client
+            .get_quote_downsized(params("1000"), U256::from(800))
+            .await
+            .is_err());
+    }
+}
@@ -0,0 +1,193 @@
+//! EIP-712 domains and typed data for the messages 0x asks users to sign.
+
+use std::collections::BTreeMap;
+
+use ethers::core::types::transaction::eip712::{EIP712Domain, Eip712DomainType, TypedData, Types};
+use serde_json::Value;
+use thiserror::Error;
+
+use crate::deployments::exchange_proxy;
+
+/// Permit2 is deployed at the same address on every chain.
+pub const PERMIT2_ADDRESS: &str = "0x000000000022D473030F116dDEE9F6B43aC78BA3";
+
+pub const LIMIT_ORDER_TYPE: &str = "LimitOrder(address makerToken,address takerToken,uint128 makerAmount,uint128 takerAmount,uint128 takerTokenFeeAmount,address maker,address taker,address sender,address feeRecipient,bytes32 pool,uint64 expiry,uint256 salt)";
+pub const META_TRANSACTION_TYPE: &str = "MetaTransactionData(address signer,address sender,uint256 minGasPrice,uint256 maxGasPrice,uint256 expirationTimeSeconds,uint256 salt,bytes callData,uint256 value,address feeToken,uint256 feeAmount)";
+pub const PERMIT_TRANSFER_FROM_TYPE: &str = "PermitTransferFrom(TokenPermissions permitted,address spender,uint256 nonce,uint256 deadline)TokenPermissions(address token,uint256 amount)";
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum TypedDataError {
+    #[error("No 0x Exchange Proxy known for chain {0}")]
+    UnsupportedChain(u64),
+
+    #[error("Typed data message must be a JSON object")]
+    InvalidMessage,
+}
+
+/// The `ZeroEx` domain used for v4 limit orders and meta-transactions, verified by the chain's
+/// Exchange Proxy.
+pub fn exchange_proxy_domain(chain_id: u64) -> Option<EIP712Domain> {
+    Some(EIP712Domain {
+        name: Some(String::from("ZeroEx")),
+        version: Some(String::from("1.0.0")),
+        chain_id: Some(chain_id.into()),
+        verifying_contract: Some(exchange_proxy(chain_id)?),
+        salt: None,
+    })
+}
+
+/// The Permit2 domain. It has no version field.
+pub fn permit2_domain(chain_id: u64) -> EIP712Domain {
+    EIP712Domain {
+        name: Some(String::from("Permit2")),
+        version: None,
+        chain_id: Some(chain_id.into()),
+        verifying_contract: Some(PERMIT2_ADDRESS.parse().expect("valid address")),
+        salt: None,
+    }
+}
+
+/// Typed data for a v4 limit order. `message` holds the order fields, named as in
+/// [`LIMIT_ORDER_TYPE`], with amounts as decimal strings.
+pub fn limit_order_typed_data(chain_id: u64, message: Value) -> Result<TypedData, TypedDataError> {
+    let domain =
+        exchange_proxy_domain(chain_id).ok_or(TypedDataError::UnsupportedChain(chain_id))?;
+    typed_data(domain, LIMIT_ORDER_TYPE, message)
+}
+
+pub fn meta_transaction_typed_data(
+    chain_id: u64,
+    message: Value,
+) -> Result<TypedData, TypedDataError> {
+    let domain =
+        exchange_proxy_domain(chain_id).ok_or(TypedDataError::UnsupportedChain(chain_id))?;
+    typed_data(domain, META_TRANSACTION_TYPE, message)
+}
+
+/// Typed data for a Permit2 `permitTransferFrom` signature.
+pub fn permit_transfer_from_typed_data(
+    chain_id: u64,
+    message: Value,
+) -> Result<TypedData, TypedDataError> {
+    typed_data(permit2_domain(chain_id), PERMIT_TRANSFER_FROM_TYPE, message)
+}
+
+pub(crate) fn typed_data(
+    domain: EIP712Domain,
+    encoded_type: &str,
+    message: Value,
+) -> Result<TypedData, TypedDataError> {
+    let Value::Object(message) = message else {
+        return Err(TypedDataError::InvalidMessage);
+    };
+
+    let types = parse_encoded_type(encoded_type);
+    let primary_type = encoded_type[..encoded_type.find('(').expect("valid type")].to_string();
+
+    Ok(TypedData {
+        domain,
+        types,
+        primary_type,
+        message: message.into_iter().collect::<BTreeMap<_, _>>(),
+    })
+}
+
+/// Splits an EIP-712 `encodeType` string, e.g. `A(B b)B(uint256 x)`, into its struct types.
+fn parse_encoded_type(encoded_type: &str) -> Types {
+    encoded_type
+        .split_terminator(')')
+        .map(|definition| {
+            let (name, fields) = definition.split_once('(').expect("valid type");
+            let fields = fields
+                .split(',')
+                .map(|field| {
+                    let (r#type, name) = field.split_once(' ').expect("valid field");
+                    Eip712DomainType {
+                        name: name.to_string(),
+                        r#type: r#type.to_string(),
+                    }
+                })
+                .collect();
+            (name.to_string(), fields)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use ethers::core::types::transaction::eip712::{encode_type, Eip712};
+
+    use super::*;
+
+    #[test]
+    fn test_domains() {
+        let domain = exchange_proxy_domain(1).unwrap();
+        assert_eq!(domain.name.as_deref(), Some("ZeroEx"));
+        assert_eq!(domain.version.as_deref(), Some("1.0.0"));
+        assert_eq!(domain.verifying_contract, exchange_proxy(1));
+        assert!(exchange_proxy_domain(2).is_none());
+
+        assert_eq!(permit2_domain(137).version, None);
+        assert_ne!(
+            permit2_domain(1).separator(),
+            permit2_domain(137).separator()
+        );
+    }
+
+    #[test]
+    fn test_typed_data_encodes() {
+        let order = serde_json::json!({
+            "makerToken": "0x6b175474e89094c44da98b954eedeac495271d0f",
+            "takerToken": "0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2",
+            "makerAmount": "1000",
+            "takerAmount": "1",
+            "takerTokenFeeAmount": "0",
+            "maker": "0xd8dA6BF26964aF9D7eEd9e03E53415D37aA96045",
+            "taker": "0x0000000000000000000000000000000000000000",
+            "sender": "0x0000000000000000000000000000000000000000",
+            "feeRecipient": "0x0000000000000000000000000000000000000000",
+            "pool": "0x0000000000000000000000000000000000000000000000000000000000000000",
+            "expiry": "1700000000",
+            "salt": "1",
+        });
+
+        let typed_data = limit_order_typed_data(1, order.clone()).unwrap();
+        assert_eq!(typed_data.primary_type, "LimitOrder");
+        assert_eq!(typed_data.types["LimitOrder"].len(), 12);
+        assert_eq!(
+            encode_type("LimitOrder", &typed_data.types).unwrap(),
+            LIMIT_ORDER_TYPE
+        );
+        assert_ne!(
+            typed_data.encode_eip712().unwrap(),
+            limit_order_typed_data(137, order)
+                .unwrap()
+                .encode_eip712()
+                .unwrap()
+        );
+
+        let permit = permit_transfer_from_typed_data(
+            1,
+            serde_json::json!({
+                "permitted": {
+                    "token": "0x6b175474e89094c44da98b954eedeac495271d0f",
+                    "amount": "1000",
+                },
+                "spender": "0xdef1c0ded9bec7f1a1670819833240f027b25eff",
+                "nonce": "0",
+                "deadline": "1700000000",
+            }),
+        )
+        .unwrap();
+        assert_eq!(
+            encode_type("PermitTransferFrom", &permit.types).unwrap(),
+            PERMIT_TRANSFER_FROM_TYPE
+        );
+        assert!(permit.encode_eip712().is_ok());
+
+        assert_eq!(
+            limit_order_typed_data(1, Value::Null).unwrap_err(),
+            TypedDataError::InvalidMessage
+        );
+    }
+}
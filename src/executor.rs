@@ -0,0 +1,688 @@
+use std::{sync::Arc, time::Duration};
+
+use ethers::{
+    core::types::{
+        Address, BlockNumber, Eip1559TransactionRequest, TransactionReceipt, TxHash, H256, U256,
+    },
+    providers::Middleware,
+};
+use thiserror::Error;
+use tokio::{sync::Mutex, time::Instant};
+use tracing::{debug, warn};
+
+use crate::{
+    approval::{sells_native_token, NATIVE_TOKEN_ADDRESS},
+    balance::erc20_balance_of,
+    deployments::{verify_quote_targets, UntrustedTarget},
+    fees::{gas_limit, to_eip1559_transaction_request, FeeEstimator, ProviderFeeEstimator},
+    policy::{PolicyViolation, TradingPolicy},
+    screening::{NoScreening, ScreeningRejection, ScreeningRequest, TokenScreener},
+    ZeroXQuoteResponse,
+};
+
+#[derive(Error, Debug)]
+pub enum SwapExecutorError {
+    #[error("Failed to build transaction from quote: {0}")]
+    InvalidQuote(String),
+
+    #[error("Middleware error: {0}")]
+    Middleware(Box<dyn std::error::Error + Send + Sync>),
+
+    #[error("Refusing to sign: quote {} {:?} is not a known 0x deployment on chain {}", .0.field, .0.address, .0.chain_id)]
+    UntrustedTarget(UntrustedTarget),
+
+    #[error("Token screening failed: {0}")]
+    ScreeningRejected(ScreeningRejection),
+
+    #[error("Trading policy violated: {0}")]
+    PolicyViolation(PolicyViolation),
+
+    #[error("Insufficient balance of {token:?}: required {required}, available {available}")]
+    InsufficientBalance {
+        token: Address,
+        required: U256,
+        available: U256,
+    },
+
+    #[error("Transaction {0:?} reverted")]
+    Reverted(TxHash),
+
+    #[error("Transaction {tx_hash:?} not confirmed in time ({reorgs} reorgs seen)")]
+    Unconfirmed { tx_hash: TxHash, reorgs: u32 },
+}
+
+/// A swap that reached the required number of confirmations.
+#[derive(Debug, Clone)]
+pub struct ConfirmedSwap {
+    pub receipt: TransactionReceipt,
+    /// Times the transaction was seen leaving or changing its block before it was confirmed.
+    pub reorgs: u32,
+}
+
+fn invalid_quote(err: impl ToString) -> SwapExecutorError {
+    SwapExecutorError::InvalidQuote(err.to_string())
+}
+
+fn middleware_error(err: impl std::error::Error + Send + Sync + 'static) -> SwapExecutorError {
+    SwapExecutorError::Middleware(Box::new(err))
+}
+
+/// Hands out sequential nonces for one account, fetching the starting nonce once.
+#[derive(Debug, Default)]
+struct NonceManager {
+    next: Mutex<Option<U256>>,
+}
+
+impl NonceManager {
+    async fn next<M: Middleware + 'static>(
+        &self,
+        provider: &M,
+        from: Address,
+    ) -> Result<U256, SwapExecutorError> {
+        let mut next = self.next.lock().await;
+
+        let nonce = match *next {
+            Some(nonce) => nonce,
+            None => provider
+                .get_transaction_count(from, Some(BlockNumber::Pending.into()))
+                .await
+                .map_err(middleware_error)?,
+        };
+
+        *next = Some(nonce + 1);
+        Ok(nonce)
+    }
+
+    async fn reset(&self) {
+        *self.next.lock().await = None;
+    }
+}
+
+/// Sends quoted swaps from `from` through an ethers middleware.
+pub struct SwapExecutor<M> {
+    provider: Arc<M>,
+    from: Address,
+    fee_estimator: Arc<dyn FeeEstimator>,
+    token_screener: Arc<dyn TokenScreener>,
+    policy: Option<Arc<TradingPolicy>>,
+    nonce_manager: Option<NonceManager>,
+    balance_check: bool,
+    target_check: bool,
+    explicit_gas_limit: bool,
+    confirmations: u64,
+    confirmation_timeout: Duration,
+    poll_interval: Duration,
+}
+
+impl<M: Middleware + 'static> SwapExecutor<M> {
+    pub fn new(provider: Arc<M>, from: Address) -> SwapExecutor<M> {
+        SwapExecutor {
+            fee_estimator: Arc::new(ProviderFeeEstimator::new(provider.clone())),
+            token_screener: Arc::new(NoScreening),
+            policy: None,
+            provider,
+            from,
+            nonce_manager: None,
+            balance_check: false,
+            target_check: true,
+            explicit_gas_limit: false,
+            confirmations: 1,
+            confirmation_timeout: Duration::from_secs(600),
+            poll_interval: Duration::from_secs(2),
+        }
+    }
+
+    /// Replaces the default `eth_feeHistory` based fee estimation.
+    pub fn fee_estimator(mut self, fee_estimator: impl FeeEstimator + 'static) -> Self {
+        self.fee_estimator = Arc::new(fee_estimator);
+        self
+    }
+
+    /// Screens the quote's tokens and the sender before signing.
+    pub fn token_screener(mut self, token_screener: impl TokenScreener + 'static) -> Self {
+        self.token_screener = Arc::new(token_screener);
+        self
+    }
+
+    /// Refuses swaps that break the policy's limits, and counts swaps towards its daily
+    /// notional as they are sent. Share the policy with the client that fetches the quotes.
+    pub fn policy(mut self, policy: Arc<TradingPolicy>) -> Self {
+        self.policy = Some(policy);
+        self
+    }
+
+    /// Assigns nonces locally so concurrent swaps from the same account don't collide.
+    ///
+    /// The pending nonce is fetched once and incremented for every swap. If sending fails the
+    /// local nonce is discarded and fetched again for the next swap.
+    pub fn nonce_management(mut self, enabled: bool) -> Self {
+        self.nonce_manager = enabled.then(NonceManager::default);
+        self
+    }
+
+    /// Checks before sending that `from` holds the sell amount and enough native token for
+    /// gas, failing with `InsufficientBalance` instead of sending a transaction that reverts.
+    pub fn balance_check(mut self, enabled: bool) -> Self {
+        self.balance_check = enabled;
+        self
+    }
+
+    /// Refuses to sign quotes whose `to` or `allowance_target` is not the 0x Exchange Proxy for
+    /// the chain. Enabled by default.
+    pub fn target_check(mut self, enabled: bool) -> Self {
+        self.target_check = enabled;
+        self
+    }
+
+    /// Sets the gas limit before sending: the quote's estimate, or `eth_estimateGas` when the
+    /// quote has none (e.g. with `skipValidation`). The balance check then accounts for the
+    /// estimated gas too. Off by default, leaving a missing limit to the middleware stack.
+    pub fn explicit_gas_limit(mut self, enabled: bool) -> Self {
+        self.explicit_gas_limit = enabled;
+        self
+    }
+
+    /// Blocks, including the one the transaction is in, that
+    /// [`execute_swap_confirmed`](SwapExecutor::execute_swap_confirmed) waits for within
+    /// `timeout`. Defaults to 1 block within 10 minutes.
+    pub fn confirmations(mut self, confirmations: u64, timeout: Duration) -> Self {
+        self.confirmations = confirmations.max(1);
+        self.confirmation_timeout = timeout;
+        self
+    }
+
+    /// How often receipts are polled while waiting for confirmations. Defaults to 2 seconds.
+    pub fn poll_interval(mut self, poll_interval: Duration) -> Self {
+        self.poll_interval = poll_interval;
+        self
+    }
+
+    pub fn provider(&self) -> &Arc<M> {
+        &self.provider
+    }
+
+    pub fn from(&self) -> Address {
+        self.from
+    }
+
+    pub async fn execute_swap(
+        &self,
+        quote: &ZeroXQuoteResponse,
+    ) -> Result<TxHash, SwapExecutorError> {
+        if self.target_check {
+            verify_quote_targets(quote).map_err(SwapExecutorError::UntrustedTarget)?;
+        }
+
+        let from = format!("{:?}", self.from);
+        self.token_screener
+            .screen(ScreeningRequest {
+                chain_id: quote.chain_id.unwrap_or_default() as u64,
+                sell_token: quote.sell_token_address.as_deref().unwrap_or_default(),
+                buy_token: quote.buy_token_address.as_deref().unwrap_or_default(),
+                taker: Some(&from),
+            })
+            .await
+            .map_err(SwapExecutorError::ScreeningRejected)?;
+
+        let Some(policy) = &self.policy else {
+            return self.send_swap(quote).await;
+        };
+        let reserved = policy
+            .reserve_trade(quote.chain_id().unwrap_or_default(), quote)
+            .map_err(SwapExecutorError::PolicyViolation)?;
+
+        let result = self.send_swap(quote).await;
+        if result.is_err() {
+            policy.release_trade(reserved);
+        }
+        result
+    }
+
+    async fn send_swap(&self, quote: &ZeroXQuoteResponse) -> Result<TxHash, SwapExecutorError> {
+        let mut tx = to_eip1559_transaction_request(quote, &*self.fee_estimator)
+            .await
+            .map_err(invalid_quote)?
+            .from(self.from);
+
+        if self.explicit_gas_limit {
+            let gas = gas_limit(quote, &tx.clone().into(), &*self.provider)
+                .await
+                .map_err(middleware_error)?;
+            tx = tx.gas(gas);
+        }
+
+        if self.balance_check {
+            self.check_balance(quote, &tx).await?;
+        }
+
+        let Some(nonce_manager) = &self.nonce_manager else {
+            return self.send(tx).await;
+        };
+
+        let nonce = nonce_manager.next(&*self.provider, self.from).await?;
+        let result = self.send(tx.nonce(nonce)).await;
+
+        if let Err(err) = &result {
+            debug!(
+                "resetting nonce after failed send of nonce {}: {}",
+                nonce, err
+            );
+            nonce_manager.reset().await;
+        }
+
+        result
+    }
+
+    /// Sends the swap like [`execute_swap`](SwapExecutor::execute_swap) and waits for it to be
+    /// confirmed, see [`wait_for_confirmations`](SwapExecutor::wait_for_confirmations).
+    pub async fn execute_swap_confirmed(
+        &self,
+        quote: &ZeroXQuoteResponse,
+    ) -> Result<ConfirmedSwap, SwapExecutorError> {
+        let tx_hash = self.execute_swap(quote).await?;
+        self.wait_for_confirmations(tx_hash).await
+    }
+
+    /// Polls the receipt until the transaction is the configured number of blocks deep. A
+    /// receipt that disappears or moves to another block is a reorg: the wait continues until
+    /// the transaction is included again and confirmed, or the timeout passes. Fails with
+    /// `Reverted` if the confirmed transaction reverted.
+    pub async fn wait_for_confirmations(
+        &self,
+        tx_hash: TxHash,
+    ) -> Result<ConfirmedSwap, SwapExecutorError> {
+        let deadline = Instant::now() + self.confirmation_timeout;
+        let mut included_in: Option<H256> = None;
+        let mut reorgs = 0;
+
+        loop {
+            let receipt = self
+                .provider
+                .get_transaction_receipt(tx_hash)
+                .await
+                .map_err(middleware_error)?;
+
+            match receipt {
+                Some(receipt) => {
+                    if included_in.is_some_and(|block| Some(block) != receipt.block_hash) {
+                        warn!("transaction {:?} moved to another block", tx_hash);
+                        reorgs += 1;
+                    }
+                    included_in = receipt.block_hash;
+
+                    let head = self
+                        .provider
+                        .get_block_number()
+                        .await
+                        .map_err(middleware_error)?;
+                    let depth = receipt
+                        .block_number
+                        .map_or(0, |block| (head + 1).saturating_sub(block).as_u64());
+
+                    if depth >= self.confirmations {
+                        if receipt.status == Some(0.into()) {
+                            return Err(SwapExecutorError::Reverted(tx_hash));
+                        }
+                        return Ok(ConfirmedSwap { receipt, reorgs });
+                    }
+                }
+                None => {
+                    if included_in.take().is_some() {
+                        warn!("transaction {:?} was reorged out", tx_hash);
+                        reorgs += 1;
+                    }
+                }
+            }
+
+            if Instant::now() >= deadline {
+                return Err(SwapExecutorError::Unconfirmed { tx_hash, reorgs });
+            }
+            tokio::time::sleep(self.poll_interval).await;
+        }
+    }
+
+    async fn check_balance(
+        &self,
+        quote: &ZeroXQuoteResponse,
+        tx: &Eip1559TransactionRequest,
+    ) -> Result<(), SwapExecutorError> {
+        let gas = match (tx.gas, quote.gas.as_ref().or(quote.estimated_gas.as_ref())) {
+            (Some(gas), _) => gas,
+            (None, Some(gas)) => U256::from_dec_str(gas).map_err(invalid_quote)?,
+            (None, None) => U256::zero(),
+        };
+        let native_required =
+            tx.value.unwrap_or_default() + gas * tx.max_fee_per_gas.unwrap_or_default();
+
+        if !sells_native_token(quote) {
+            let token = quote
+                .sell_token_address
+                .as_ref()
+                .ok_or_else(|| invalid_quote("Missing 'sell_token_address' field"))?
+                .parse::<Address>()
+                .map_err(invalid_quote)?;
+            let sell_amount = quote
+                .sell_amount
+                .as_ref()
+                .ok_or_else(|| invalid_quote("Missing 'sell_amount' field"))?;
+            let sell_amount = U256::from_dec_str(sell_amount).map_err(invalid_quote)?;
+
+            let available = erc20_balance_of(&*self.provider, token, self.from)
+                .await
+                .map_err(middleware_error)?;
+
+            if available < sell_amount {
+                return Err(SwapExecutorError::InsufficientBalance {
+                    token,
+                    required: sell_amount,
+                    available,
+                });
+            }
+        }
+
+        let available = self
+            .provider
+            .get_balance(self.from, None)
+            .await
+            .map_err(middleware_error)?;
+
+        if available < native_required {
+            return Err(SwapExecutorError::InsufficientBalance {
+                token: NATIVE_TOKEN_ADDRESS.parse().expect("valid address"),
+                required: native_required,
+                available,
+            });
+        }
+
+        Ok(())
+    }
+
+    async fn send(&self, tx: Eip1559TransactionRequest) -> Result<TxHash, SwapExecutorError> {
+        let pending = self
+            .provider
+            .send_transaction(tx, None)
+            .await
+            .map_err(middleware_error)?;
+
+        Ok(pending.tx_hash())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ethers::{
+        core::types::transaction::eip2718::TypedTransaction,
+        providers::{MockResponse, Provider},
+    };
+
+    use ethers::core::types::{Bytes, H256};
+
+    use super::*;
+    use crate::fees::Eip1559Fees;
+
+    const FEES: Eip1559Fees = Eip1559Fees {
+        max_fee_per_gas: U256([30_000_000_000, 0, 0, 0]),
+        max_priority_fee_per_gas: U256([1_000_000_000, 0, 0, 0]),
+    };
+
+    static VITALIK: &str = "0xd8dA6BF26964aF9D7eEd9e03E53415D37aA96045";
+
+    fn quote() -> ZeroXQuoteResponse {
+        serde_json::from_value(serde_json::json!({
+            "chainId": 1,
+            "to": "0xdef1c0ded9bec7f1a1670819833240f027b25eff",
+            "data": "0xd9627aa4",
+            "value": "1000000000000000000",
+            "gasPrice": "1000000000",
+        }))
+        .unwrap()
+    }
+
+    async fn sent_tx(nonce: u64) -> TypedTransaction {
+        to_eip1559_transaction_request(&quote(), &FEES)
+            .await
+            .unwrap()
+            .from(VITALIK.parse::<Address>().unwrap())
+            .nonce(nonce)
+            .into()
+    }
+
+    #[tokio::test]
+    async fn test_nonce_management_increments_locally() {
+        let (provider, mock) = Provider::mocked();
+        // responses are popped from the back
+        mock.push(TxHash::repeat_byte(2)).unwrap();
+        mock.push(U256::from(21000)).unwrap();
+        mock.push(TxHash::repeat_byte(1)).unwrap();
+        mock.push(U256::from(21000)).unwrap();
+        mock.push(U256::from(5)).unwrap();
+
+        let executor = SwapExecutor::new(Arc::new(provider), VITALIK.parse().unwrap())
+            .fee_estimator(FEES)
+            .nonce_management(true);
+
+        assert_eq!(
+            executor.execute_swap(&quote()).await.unwrap(),
+            TxHash::repeat_byte(1)
+        );
+        assert_eq!(
+            executor.execute_swap(&quote()).await.unwrap(),
+            TxHash::repeat_byte(2)
+        );
+
+        mock.assert_request(
+            "eth_getTransactionCount",
+            (VITALIK.parse::<Address>().unwrap(), "pending"),
+        )
+        .unwrap();
+
+        for nonce in [5, 6] {
+            let mut tx = sent_tx(nonce).await;
+            mock.assert_request("eth_estimateGas", [&tx]).unwrap();
+            tx.set_gas(21000);
+            mock.assert_request("eth_sendTransaction", [&tx]).unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn test_nonce_management_resets_on_error() {
+        let (provider, mock) = Provider::mocked();
+        mock.push(U256::from(7)).unwrap();
+        mock.push_response(MockResponse::Error(
+            serde_json::from_value(serde_json::json!({ "code": -32000, "message": "boom" }))
+                .unwrap(),
+        ));
+        mock.push(U256::from(5)).unwrap();
+
+        let executor = SwapExecutor::new(Arc::new(provider), VITALIK.parse().unwrap())
+            .fee_estimator(FEES)
+            .nonce_management(true);
+
+        assert!(executor.execute_swap(&quote()).await.is_err());
+
+        let nonce_manager = executor.nonce_manager.as_ref().unwrap();
+        let nonce = nonce_manager
+            .next(&*executor.provider, executor.from)
+            .await
+            .unwrap();
+
+        assert_eq!(nonce, U256::from(7));
+    }
+
+    #[tokio::test]
+    async fn test_policy_daily_notional_under_concurrent_swaps() {
+        let (provider, mock) = Provider::mocked();
+        // gas estimate and hash for each of the three swaps that fit, after a failed send
+        for _ in 0..6 {
+            mock.push(TxHash::repeat_byte(1)).unwrap();
+        }
+        mock.push_response(MockResponse::Error(
+            serde_json::from_value(serde_json::json!({ "code": -32000, "message": "boom" }))
+                .unwrap(),
+        ));
+
+        let policy = Arc::new(TradingPolicy::new().max_daily_notional(3.into()));
+        let executor = Arc::new(
+            SwapExecutor::new(Arc::new(provider), VITALIK.parse().unwrap())
+                .fee_estimator(FEES)
+                .policy(policy.clone()),
+        );
+        let mut quote = quote();
+        quote.sell_token_address = Some(String::from(crate::approval::NATIVE_TOKEN_ADDRESS));
+        quote.sell_amount = Some(String::from("1000000000000000000"));
+        quote.sell_token_to_eth_rate = Some(String::from("1"));
+
+        assert!(executor.execute_swap(&quote).await.is_err());
+        assert_eq!(policy.daily_notional(), 0.into());
+        let quote = Arc::new(quote);
+
+        let mut swaps = tokio::task::JoinSet::new();
+        for _ in 0..5 {
+            let (executor, quote) = (executor.clone(), quote.clone());
+            swaps.spawn(async move { executor.execute_swap(&quote).await });
+        }
+        let (mut sent, mut refused) = (0, 0);
+        while let Some(result) = swaps.join_next().await {
+            match result.unwrap() {
+                Ok(_) => sent += 1,
+                Err(SwapExecutorError::PolicyViolation(
+                    PolicyViolation::DailyNotionalExceeded { .. },
+                )) => refused += 1,
+                Err(err) => panic!("unexpected error: {}", err),
+            }
+        }
+
+        assert_eq!((sent, refused), (3, 2));
+        assert_eq!(policy.daily_notional(), 3.into());
+    }
+
+    #[tokio::test]
+    async fn test_target_check_rejects_unknown_contract() {
+        let (provider, _mock) = Provider::mocked();
+        let executor = SwapExecutor::new(Arc::new(provider), VITALIK.parse().unwrap());
+
+        let mut quote = quote();
+        quote.to = Some(String::from(VITALIK));
+
+        assert!(matches!(
+            executor.execute_swap(&quote).await,
+            Err(SwapExecutorError::UntrustedTarget(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_balance_check_rejects_insufficient_token_balance() {
+        let (provider, mock) = Provider::mocked();
+        mock.push::<Bytes, _>(Bytes::from(H256::from_low_u64_be(999).0.to_vec()))
+            .unwrap();
+
+        let executor = SwapExecutor::new(Arc::new(provider), VITALIK.parse().unwrap())
+            .fee_estimator(FEES)
+            .balance_check(true);
+
+        let mut quote = quote();
+        quote.sell_token_address = Some(String::from("0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48"));
+        quote.sell_amount = Some(String::from("1000"));
+
+        match executor.execute_swap(&quote).await {
+            Err(SwapExecutorError::InsufficientBalance {
+                required,
+                available,
+                ..
+            }) => {
+                assert_eq!(required, U256::from(1000));
+                assert_eq!(available, U256::from(999));
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_balance_check_includes_gas_for_native_sells() {
+        let (provider, mock) = Provider::mocked();
+        mock.push(U256::exp10(18)).unwrap();
+
+        let executor = SwapExecutor::new(Arc::new(provider), VITALIK.parse().unwrap())
+            .fee_estimator(FEES)
+            .balance_check(true);
+
+        let mut quote = quote();
+        quote.sell_token_address = Some(String::from(NATIVE_TOKEN_ADDRESS));
+        quote.gas = Some(String::from("100000"));
+
+        match executor.execute_swap(&quote).await {
+            Err(SwapExecutorError::InsufficientBalance { required, .. }) => {
+                assert_eq!(
+                    required,
+                    U256::exp10(18) + FEES.max_fee_per_gas * U256::from(100000)
+                );
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_wait_for_confirmations_survives_reorg() {
+        let receipt = |block: u64, hash: u64| TransactionReceipt {
+            block_number: Some(block.into()),
+            block_hash: Some(H256::from_low_u64_be(hash)),
+            status: Some(1.into()),
+            ..Default::default()
+        };
+
+        let (provider, mock) = Provider::mocked();
+        // responses are popped from the back
+        mock.push(ethers::core::types::U64::from(13)).unwrap();
+        mock.push(receipt(11, 2)).unwrap();
+        mock.push::<Option<TransactionReceipt>, _>(None).unwrap();
+        mock.push(ethers::core::types::U64::from(10)).unwrap();
+        mock.push(receipt(10, 1)).unwrap();
+
+        let executor = SwapExecutor::new(Arc::new(provider), VITALIK.parse().unwrap())
+            .confirmations(3, Duration::from_secs(5))
+            .poll_interval(Duration::from_millis(1));
+
+        let confirmed = executor
+            .wait_for_confirmations(TxHash::repeat_byte(1))
+            .await
+            .unwrap();
+        assert_eq!(confirmed.reorgs, 1);
+        assert_eq!(confirmed.receipt.block_number, Some(11.into()));
+    }
+
+    #[tokio::test]
+    async fn test_explicit_gas_limit_estimates_missing_gas() {
+        let (provider, mock) = Provider::mocked();
+        // responses are popped from the back
+        mock.push(TxHash::repeat_byte(1)).unwrap();
+        mock.push(U256::exp10(19)).unwrap();
+        mock.push(U256::from(180_000)).unwrap();
+
+        let executor = SwapExecutor::new(Arc::new(provider), VITALIK.parse().unwrap())
+            .fee_estimator(FEES)
+            .balance_check(true)
+            .explicit_gas_limit(true);
+
+        let mut quote = quote();
+        quote.sell_token_address = Some(String::from(NATIVE_TOKEN_ADDRESS));
+
+        assert_eq!(
+            executor.execute_swap(&quote).await.unwrap(),
+            TxHash::repeat_byte(1)
+        );
+
+        let mut tx: TypedTransaction = to_eip1559_transaction_request(&quote, &FEES)
+            .await
+            .unwrap()
+            .from(VITALIK.parse::<Address>().unwrap())
+            .into();
+        mock.assert_request("eth_estimateGas", [&tx]).unwrap();
+        mock.assert_request(
+            "eth_getBalance",
+            (VITALIK.parse::<Address>().unwrap(), "latest"),
+        )
+        .unwrap();
+        tx.set_gas(180_000);
+        mock.assert_request("eth_sendTransaction", [&tx]).unwrap();
+    }
+}
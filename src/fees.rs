@@ -0,0 +1,143 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use ethers::{
+    core::types::{transaction::eip2718::TypedTransaction, Eip1559TransactionRequest, U256},
+    providers::Middleware,
+};
+
+use crate::{ToTransactionRequest, ZeroXQuoteResponse};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Eip1559Fees {
+    pub max_fee_per_gas: U256,
+    pub max_priority_fee_per_gas: U256,
+}
+
+/// Chooses the EIP-1559 fees for a swap transaction.
+#[async_trait]
+pub trait FeeEstimator: Send + Sync {
+    async fn estimate_fees(&self) -> Result<Eip1559Fees, Box<dyn std::error::Error + Send + Sync>>;
+}
+
+/// Fixed fees, for chains with stable fees or for tests.
+#[async_trait]
+impl FeeEstimator for Eip1559Fees {
+    async fn estimate_fees(&self) -> Result<Eip1559Fees, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(*self)
+    }
+}
+
+/// Estimates fees from the provider's `eth_feeHistory`.
+#[derive(Debug)]
+pub struct ProviderFeeEstimator<M> {
+    provider: Arc<M>,
+}
+
+impl<M> ProviderFeeEstimator<M> {
+    pub fn new(provider: Arc<M>) -> ProviderFeeEstimator<M> {
+        ProviderFeeEstimator { provider }
+    }
+}
+
+#[async_trait]
+impl<M: Middleware + 'static> FeeEstimator for ProviderFeeEstimator<M> {
+    async fn estimate_fees(&self) -> Result<Eip1559Fees, Box<dyn std::error::Error + Send + Sync>> {
+        let (max_fee_per_gas, max_priority_fee_per_gas) =
+            self.provider.estimate_eip1559_fees(None).await?;
+
+        Ok(Eip1559Fees {
+            max_fee_per_gas,
+            max_priority_fee_per_gas,
+        })
+    }
+}
+
+/// Builds an EIP-1559 transaction for the quote using fees from `fee_estimator` instead of the
+/// quote's legacy `gasPrice`.
+pub async fn to_eip1559_transaction_request(
+    quote: &ZeroXQuoteResponse,
+    fee_estimator: &dyn FeeEstimator,
+) -> Result<Eip1559TransactionRequest, Box<dyn std::error::Error + Send + Sync>> {
+    let tx = quote
+        .to_transaction_request()
+        .map_err(|err| err.to_string())?;
+    let fees = fee_estimator.estimate_fees().await?;
+
+    let mut eip1559 = Eip1559TransactionRequest::new()
+        .max_fee_per_gas(fees.max_fee_per_gas)
+        .max_priority_fee_per_gas(fees.max_priority_fee_per_gas);
+    eip1559.to = tx.to;
+    eip1559.value = tx.value;
+    eip1559.data = tx.data;
+    eip1559.chain_id = tx.chain_id;
+
+    Ok(eip1559)
+}
+
+/// The gas limit for the quote's transaction `tx`: the quote's own estimate, or
+/// `eth_estimateGas` through `provider` when the quote has none, e.g. because it was fetched
+/// with `skipValidation`. `tx` should have `from` set so the estimate runs as the taker.
+pub async fn gas_limit<M: Middleware>(
+    quote: &ZeroXQuoteResponse,
+    tx: &TypedTransaction,
+    provider: &M,
+) -> Result<U256, M::Error> {
+    match quote.gas() {
+        Ok(gas) => Ok(gas),
+        Err(_) => provider.estimate_gas(tx, None).await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_to_eip1559_transaction_request() {
+        let quote: ZeroXQuoteResponse = serde_json::from_value(serde_json::json!({
+            "chainId": 1,
+            "to": "0xdef1c0ded9bec7f1a1670819833240f027b25eff",
+            "data": "0xd9627aa4",
+            "value": "1000",
+            "gasPrice": "1000000000",
+        }))
+        .unwrap();
+
+        let fees = Eip1559Fees {
+            max_fee_per_gas: U256::from(30_000_000_000u64),
+            max_priority_fee_per_gas: U256::from(1_000_000_000u64),
+        };
+
+        let tx = to_eip1559_transaction_request(&quote, &fees).await.unwrap();
+
+        assert_eq!(tx.max_fee_per_gas, Some(fees.max_fee_per_gas));
+        assert_eq!(
+            tx.max_priority_fee_per_gas,
+            Some(fees.max_priority_fee_per_gas)
+        );
+        assert_eq!(tx.value, Some(U256::from(1000)));
+        assert_eq!(tx.chain_id, Some(1.into()));
+    }
+
+    #[tokio::test]
+    async fn test_gas_limit_falls_back_to_provider() {
+        let (provider, mock) = ethers::providers::Provider::mocked();
+        mock.push(U256::from(180_000)).unwrap();
+
+        let mut quote: ZeroXQuoteResponse =
+            serde_json::from_value(serde_json::json!({ "estimatedGas": "150000" })).unwrap();
+        let tx = TypedTransaction::default();
+        assert_eq!(
+            gas_limit(&quote, &tx, &provider).await.unwrap(),
+            U256::from(150_000)
+        );
+
+        quote.estimated_gas = None;
+        assert_eq!(
+            gas_limit(&quote, &tx, &provider).await.unwrap(),
+            U256::from(180_000)
+        );
+        mock.assert_request("eth_estimateGas", [&tx]).unwrap();
+    }
+}
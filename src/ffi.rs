@@ -0,0 +1,153 @@
+//! uniffi bindings for the quote and price API, used by the Kotlin and Swift wallets.
+
+use std::{str::FromStr, sync::Arc};
+
+use rust_decimal::Decimal;
+use thiserror::Error;
+
+use crate::{Percentage, ZeroXClient, ZeroXClientError, ZeroXQuoteParams, ZeroXQuoteResponse};
+
+#[derive(Error, Debug, uniffi::Error)]
+pub enum FfiError {
+    #[error("{message}")]
+    InvalidChainId { message: String },
+    #[error("{message}")]
+    InvalidParams { message: String },
+    #[error("{message}")]
+    Request { message: String },
+}
+
+impl From<ZeroXClientError> for FfiError {
+    fn from(err: ZeroXClientError) -> FfiError {
+        let message = err.to_string();
+        match err {
+            ZeroXClientError::InvalidChainId(_) => FfiError::InvalidChainId { message },
+            _ => FfiError::Request { message },
+        }
+    }
+}
+
+#[derive(Debug, Clone, uniffi::Record)]
+pub struct FfiQuoteParams {
+    pub sell_token: String,
+    pub buy_token: String,
+    pub sell_amount: String,
+    pub taker_address: Option<String>,
+    /// A fraction, e.g. `"0.01"` for 1%.
+    pub slippage_percentage: Option<String>,
+}
+
+impl TryFrom<FfiQuoteParams> for ZeroXQuoteParams {
+    type Error = FfiError;
+
+    fn try_from(params: FfiQuoteParams) -> Result<ZeroXQuoteParams, FfiError> {
+        let slippage_percentage = params
+            .slippage_percentage
+            .map(|slippage| {
+                Decimal::from_str(&slippage)
+                    .map(Percentage::from_fraction)
+                    .map_err(|err| FfiError::InvalidParams {
+                        message: format!("Invalid slippage percentage {slippage:?}: {err}"),
+                    })
+            })
+            .transpose()?;
+
+        Ok(ZeroXQuoteParams {
+            sell_token: params.sell_token,
+            buy_token: params.buy_token,
+            sell_amount: params.sell_amount,
+            taker_address: params.taker_address,
+            slippage_percentage,
+            ..Default::default()
+        })
+    }
+}
+
+/// The subset of a quote a wallet needs to display and sign it.
+#[derive(Debug, Clone, uniffi::Record)]
+pub struct FfiQuote {
+    pub chain_id: Option<i32>,
+    pub price: Option<String>,
+    pub sell_amount: Option<String>,
+    pub buy_amount: Option<String>,
+    pub to: Option<String>,
+    pub data: Option<String>,
+    pub value: Option<String>,
+    pub gas: Option<String>,
+    pub gas_price: Option<String>,
+    pub allowance_target: Option<String>,
+}
+
+impl From<ZeroXQuoteResponse> for FfiQuote {
+    fn from(quote: ZeroXQuoteResponse) -> FfiQuote {
+        FfiQuote {
+            chain_id: quote.chain_id,
+            price: quote.price,
+            sell_amount: quote.sell_amount,
+            buy_amount: quote.buy_amount,
+            to: quote.to,
+            data: quote.data,
+            value: quote.value,
+            gas: quote.gas,
+            gas_price: quote.gas_price,
+            allowance_target: quote.allowance_target,
+        }
+    }
+}
+
+#[derive(uniffi::Object)]
+pub struct FfiZeroXClient {
+    client: ZeroXClient,
+}
+
+#[uniffi::export(async_runtime = "tokio")]
+impl FfiZeroXClient {
+    #[uniffi::constructor]
+    pub fn new(chain_id: u64, api_key: String) -> Result<Arc<FfiZeroXClient>, FfiError> {
+        Ok(Arc::new(FfiZeroXClient {
+            client: ZeroXClient::new(chain_id, api_key)?,
+        }))
+    }
+
+    pub async fn get_quote(&self, params: FfiQuoteParams) -> Result<FfiQuote, FfiError> {
+        Ok(self.client.get_quote(params.try_into()?).await?.into())
+    }
+
+    pub async fn get_price(&self, params: FfiQuoteParams) -> Result<FfiQuote, FfiError> {
+        Ok(self.client.get_price(params.try_into()?).await?.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_invalid_chain_id() {
+        assert!(matches!(
+            FfiZeroXClient::new(2, String::from("test")),
+            Err(FfiError::InvalidChainId { .. })
+        ));
+    }
+
+    #[test]
+    fn test_invalid_slippage() {
+        let params = |slippage: &str| FfiQuoteParams {
+            sell_token: String::from("ETH"),
+            buy_token: String::from("DAI"),
+            sell_amount: String::from("1"),
+            taker_address: None,
+            slippage_percentage: Some(String::from(slippage)),
+        };
+
+        let parsed = ZeroXQuoteParams::try_from(params("0.01")).unwrap();
+        assert_eq!(
+            parsed.slippage_percentage,
+            Some(Percentage::from_fraction(Decimal::new(1, 2)))
+        );
+        assert!(matches!(
+            ZeroXQuoteParams::try_from(params("1%")),
+            Err(FfiError::InvalidParams { .. })
+        ));
+    }
+}
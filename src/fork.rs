@@ -0,0 +1,249 @@
+//! Helpers for end-to-end swap tests against an anvil mainnet fork.
+//!
+//! Requires the `anvil` binary on `PATH`. Quotes are fetched from the live API, so the taker
+//! needs no real funds: it is impersonated on the fork and funded with [`AnvilFork::fund`].
+
+use std::sync::Arc;
+
+use ethers::{
+    abi::{self, Token},
+    core::types::{Address, TransactionRequest, TxHash, U256},
+    providers::{Http, Middleware, PendingTransaction, Provider, ProviderError},
+    utils::{id, Anvil, AnvilInstance},
+};
+use thiserror::Error;
+
+use crate::{
+    approval::{build_approve_tx, sells_native_token, NATIVE_TOKEN_ADDRESS},
+    balance::erc20_balance_of,
+    SwapExecutor, SwapExecutorError, ZeroXQuoteResponse,
+};
+
+#[derive(Error, Debug)]
+pub enum ForkError {
+    #[error("Provider error: {0}")]
+    Provider(#[from] ProviderError),
+
+    #[error("Swap failed: {0}")]
+    Executor(#[from] SwapExecutorError),
+
+    #[error("Invalid quote: {0}")]
+    InvalidQuote(String),
+
+    #[error("Transaction {0:?} reverted")]
+    Reverted(TxHash),
+}
+
+/// The result of executing a quote on the fork.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SwapOutcome {
+    pub tx_hash: TxHash,
+    /// The quote's `buy_amount`.
+    pub quoted: U256,
+    /// The taker's buy token balance change, excluding gas when buying the native token.
+    pub received: U256,
+}
+
+impl SwapOutcome {
+    /// Panics unless the taker received at least `quoted` minus `tolerance_bps`.
+    pub fn assert_received(&self, tolerance_bps: u32) {
+        let min = self.quoted * U256::from(10_000u32.saturating_sub(tolerance_bps)) / 10_000;
+        assert!(
+            self.received >= min,
+            "received {} but quoted {} (min {} at {} bps)",
+            self.received,
+            self.quoted,
+            min,
+            tolerance_bps
+        );
+    }
+}
+
+/// A running anvil fork. The anvil process is killed when this is dropped.
+pub struct AnvilFork {
+    anvil: AnvilInstance,
+    provider: Arc<Provider<Http>>,
+}
+
+impl AnvilFork {
+    /// Forks `fork_url` at its latest block. Panics if anvil cannot be started.
+    pub fn spawn(fork_url: impl Into<String>) -> AnvilFork {
+        AnvilFork::from_anvil(Anvil::new().fork(fork_url))
+    }
+
+    /// Forks `fork_url` at `block`, for reproducible tests.
+    pub fn spawn_at(fork_url: impl Into<String>, block: u64) -> AnvilFork {
+        AnvilFork::from_anvil(Anvil::new().fork(fork_url).fork_block_number(block))
+    }
+
+    fn from_anvil(anvil: Anvil) -> AnvilFork {
+        let anvil = anvil.spawn();
+        let provider = Provider::<Http>::try_from(anvil.endpoint()).expect("valid anvil endpoint");
+
+        AnvilFork {
+            anvil,
+            provider: Arc::new(provider),
+        }
+    }
+
+    pub fn provider(&self) -> Arc<Provider<Http>> {
+        self.provider.clone()
+    }
+
+    pub fn endpoint(&self) -> String {
+        self.anvil.endpoint()
+    }
+
+    /// Sets the native balance of `account`.
+    pub async fn fund(&self, account: Address, amount: U256) -> Result<(), ForkError> {
+        self.provider
+            .request::<_, ()>("anvil_setBalance", (account, amount))
+            .await?;
+        Ok(())
+    }
+
+    /// Transfers `amount` of `token` to `account` from `holder`, which is impersonated and
+    /// must hold the tokens on the forked chain.
+    pub async fn fund_token(
+        &self,
+        token: Address,
+        holder: Address,
+        account: Address,
+        amount: U256,
+    ) -> Result<(), ForkError> {
+        let mut data = id("transfer(address,uint256)").to_vec();
+        data.extend(abi::encode(&[Token::Address(account), Token::Uint(amount)]));
+
+        self.impersonate(holder).await?;
+        self.fund(holder, U256::exp10(18)).await?;
+        self.send(TransactionRequest::new().from(holder).to(token).data(data))
+            .await?;
+
+        Ok(())
+    }
+
+    /// Sends `quote` from `taker`, approving the allowance target first when selling an
+    /// ERC-20, and reports how much of the buy token the taker received.
+    pub async fn execute_quote(
+        &self,
+        taker: Address,
+        quote: &ZeroXQuoteResponse,
+    ) -> Result<SwapOutcome, ForkError> {
+        let quoted = parse_amount(quote.buy_amount.as_deref(), "buy_amount")?;
+        let buy_token = quote
+            .buy_token_address
+            .as_deref()
+            .ok_or_else(|| ForkError::InvalidQuote(String::from("Missing 'buy_token_address'")))?;
+        let buy_token = (!buy_token.eq_ignore_ascii_case(NATIVE_TOKEN_ADDRESS))
+            .then(|| buy_token.parse::<Address>())
+            .transpose()
+            .map_err(|err| ForkError::InvalidQuote(err.to_string()))?;
+
+        self.impersonate(taker).await?;
+
+        if !sells_native_token(quote) {
+            let approve =
+                build_approve_tx(quote).map_err(|err| ForkError::InvalidQuote(err.to_string()))?;
+            self.send(approve.from(taker)).await?;
+        }
+
+        let before = self.balance(buy_token, taker).await?;
+
+        let tx_hash = SwapExecutor::new(self.provider.clone(), taker)
+            .execute_swap(quote)
+            .await?;
+        let receipt = PendingTransaction::new(tx_hash, &self.provider)
+            .await?
+            .ok_or(ForkError::Reverted(tx_hash))?;
+        if receipt.status != Some(1.into()) {
+            return Err(ForkError::Reverted(tx_hash));
+        }
+
+        let mut after = self.balance(buy_token, taker).await?;
+        if buy_token.is_none() {
+            after += receipt.gas_used.unwrap_or_default()
+                * receipt.effective_gas_price.unwrap_or_default();
+        }
+
+        Ok(SwapOutcome {
+            tx_hash,
+            quoted,
+            received: after.saturating_sub(before),
+        })
+    }
+
+    async fn impersonate(&self, account: Address) -> Result<(), ForkError> {
+        self.provider
+            .request::<_, ()>("anvil_impersonateAccount", [account])
+            .await?;
+        Ok(())
+    }
+
+    async fn balance(&self, token: Option<Address>, account: Address) -> Result<U256, ForkError> {
+        Ok(match token {
+            Some(token) => erc20_balance_of(&*self.provider, token, account).await?,
+            None => self.provider.get_balance(account, None).await?,
+        })
+    }
+
+    async fn send(&self, tx: TransactionRequest) -> Result<(), ForkError> {
+        let pending = self.provider.send_transaction(tx, None).await?;
+        let tx_hash = pending.tx_hash();
+
+        match pending.await? {
+            Some(receipt) if receipt.status == Some(1.into()) => Ok(()),
+            _ => Err(ForkError::Reverted(tx_hash)),
+        }
+    }
+}
+
+fn parse_amount(amount: Option<&str>, field: &str) -> Result<U256, ForkError> {
+    let amount =
+        amount.ok_or_else(|| ForkError::InvalidQuote(format!("Missing '{}' field", field)))?;
+    U256::from_dec_str(amount).map_err(|err| ForkError::InvalidQuote(err.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ZeroXClient, ZeroXQuoteParams};
+
+    #[test]
+    fn test_assert_received_tolerance() {
+        let outcome = SwapOutcome {
+            tx_hash: TxHash::zero(),
+            quoted: U256::from(10_000),
+            received: U256::from(9_950),
+        };
+
+        outcome.assert_received(50);
+        assert!(std::panic::catch_unwind(|| outcome.assert_received(49)).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_swap_eth_for_dai_on_fork() {
+        dotenv::dotenv().ok();
+
+        let fork = AnvilFork::spawn(std::env::var("FORK_URL").unwrap());
+        let taker = Address::repeat_byte(0x42);
+        fork.fund(taker, U256::exp10(19)).await.unwrap();
+
+        let client = ZeroXClient::new(1, std::env::var("ZEROX_API_KEY").unwrap()).unwrap();
+        let quote = client
+            .get_quote(ZeroXQuoteParams {
+                sell_amount: String::from("1000000000000000000"),
+                sell_token: String::from("ETH"),
+                buy_token: String::from("0x6b175474e89094c44da98b954eedeac495271d0f"), //DAI
+                taker_address: Some(format!("{:?}", taker)),
+                skip_validation: Some(true),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+
+        fork.execute_quote(taker, &quote)
+            .await
+            .unwrap()
+            .assert_received(100);
+    }
+}
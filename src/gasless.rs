@@ -0,0 +1,620 @@
+//! The 0x Gasless (Tx Relay) API, where the taker signs EIP-712 messages and 0x submits the
+//! trade and pays its gas.
+
+use std::{collections::HashMap, time::Duration};
+
+use ethers::core::types::{Signature, TxHash};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::Value;
+use thiserror::Error;
+use tracing::debug;
+
+use crate::{
+    policy::V2Terms,
+    screening::ScreeningRequest,
+    v2::{FeesV2, Route},
+    FeeBps, ZeroXClient, ZeroXClientError,
+};
+
+#[derive(Serialize, Deserialize, Default, Clone, Debug)]
+pub struct GaslessParams {
+    pub sell_token: String,
+    pub buy_token: String,
+    pub sell_amount: String,
+    /// Required for quotes; optional for prices.
+    pub taker: Option<String>,
+    pub slippage_bps: Option<FeeBps>,
+    pub excluded_sources: Option<Vec<String>>,
+    pub swap_fee_recipient: Option<String>,
+    pub swap_fee_bps: Option<FeeBps>,
+    pub swap_fee_token: Option<String>,
+}
+
+/// An EIP-712 message for the taker to sign.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct GaslessSignable {
+    #[serde(rename = "type")]
+    pub type_: Option<String>,
+    pub hash: Option<String>,
+    pub eip712: Option<Value>,
+}
+
+/// An indicative gasless price. Cheap to poll; nothing in it can be signed.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct GaslessPrice {
+    pub block_number: Option<String>,
+    pub buy_amount: Option<String>,
+    pub buy_token: Option<String>,
+    pub sell_amount: Option<String>,
+    pub sell_token: Option<String>,
+    pub min_buy_amount: Option<String>,
+    pub liquidity_available: Option<bool>,
+    pub allowance_target: Option<String>,
+    pub fees: Option<FeesV2>,
+    pub issues: Option<Value>,
+    pub route: Option<Route>,
+    pub zid: Option<String>,
+}
+
+/// A firm gasless quote with the trade, and the approval if the sell token supports gasless
+/// approvals and one is needed, ready to be signed.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct GaslessQuote {
+    pub block_number: Option<String>,
+    pub buy_amount: Option<String>,
+    pub buy_token: Option<String>,
+    pub sell_amount: Option<String>,
+    pub sell_token: Option<String>,
+    pub min_buy_amount: Option<String>,
+    pub liquidity_available: Option<bool>,
+    pub fees: Option<FeesV2>,
+    pub issues: Option<Value>,
+    pub route: Option<Route>,
+    pub trade: Option<GaslessSignable>,
+    pub approval: Option<GaslessSignable>,
+    pub zid: Option<String>,
+}
+
+/// A [`GaslessSignable`] from a quote with the taker's EIP-712 signature over it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SignedGasless {
+    pub signable: GaslessSignable,
+    pub signature: Signature,
+}
+
+impl SignedGasless {
+    fn to_json(&self) -> Value {
+        serde_json::json!({
+            "type": self.signable.type_,
+            "eip712": self.signable.eip712,
+            "signature": {
+                "v": self.signature.v,
+                "r": format!("0x{:064x}", self.signature.r),
+                "s": format!("0x{:064x}", self.signature.s),
+                // EIP-712
+                "signatureType": 2,
+            },
+        })
+    }
+}
+
+/// A gasless trade accepted by the relayer; follow it with [`ZeroXClient::await_confirmation`].
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct GaslessSubmission {
+    pub trade_hash: String,
+    #[serde(rename = "type")]
+    pub type_: Option<String>,
+    pub zid: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct GaslessChain {
+    pub chain_id: String,
+    pub chain_name: String,
+}
+
+#[derive(Deserialize)]
+struct GaslessChainsResponse {
+    chains: Vec<GaslessChain>,
+}
+
+#[derive(Deserialize)]
+struct GaslessApprovalTokensResponse {
+    tokens: Vec<String>,
+}
+
+const STATUS_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+#[derive(Error, Debug)]
+pub enum GaslessStatusError {
+    #[error(transparent)]
+    Client(#[from] ZeroXClientError),
+
+    #[error("Gasless trade did not reach a final status within {0:?}")]
+    Timeout(Duration),
+}
+
+/// Status of a submitted gasless trade.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(try_from = "RawGaslessStatus", into = "RawGaslessStatus")]
+pub enum GaslessStatus {
+    /// Accepted by 0x, not yet broadcast.
+    Pending,
+    /// Broadcast, not yet mined.
+    Submitted,
+    Succeeded {
+        tx_hash: TxHash,
+    },
+    Failed {
+        reason: String,
+    },
+    Expired,
+}
+
+impl GaslessStatus {
+    /// Whether the status can no longer change.
+    pub fn is_terminal(&self) -> bool {
+        !matches!(self, GaslessStatus::Pending | GaslessStatus::Submitted)
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct RawGaslessTransaction {
+    hash: TxHash,
+}
+
+#[derive(Serialize, Deserialize)]
+struct RawGaslessStatus {
+    status: String,
+    #[serde(default)]
+    transactions: Vec<RawGaslessTransaction>,
+    reason: Option<String>,
+}
+
+impl TryFrom<RawGaslessStatus> for GaslessStatus {
+    type Error = String;
+
+    fn try_from(raw: RawGaslessStatus) -> Result<Self, Self::Error> {
+        Ok(match raw.status.as_str() {
+            "pending" => GaslessStatus::Pending,
+            "submitted" => GaslessStatus::Submitted,
+            // the trade's transaction is the last one submitted; earlier ones were replaced
+            "succeeded" | "confirmed" => GaslessStatus::Succeeded {
+                tx_hash: raw
+                    .transactions
+                    .last()
+                    .map(|tx| tx.hash)
+                    .ok_or_else(|| format!("'{}' status without a transaction", raw.status))?,
+            },
+            "failed" => GaslessStatus::Failed {
+                reason: raw.reason.unwrap_or_default(),
+            },
+            "expired" => GaslessStatus::Expired,
+            status => return Err(format!("unknown gasless status '{}'", status)),
+        })
+    }
+}
+
+impl From<GaslessStatus> for RawGaslessStatus {
+    fn from(status: GaslessStatus) -> Self {
+        let (status, transactions, reason) = match status {
+            GaslessStatus::Pending => ("pending", vec![], None),
+            GaslessStatus::Submitted => ("submitted", vec![], None),
+            GaslessStatus::Succeeded { tx_hash } => (
+                "succeeded",
+                vec![RawGaslessTransaction { hash: tx_hash }],
+                None,
+            ),
+            GaslessStatus::Failed { reason } => ("failed", vec![], Some(reason)),
+            GaslessStatus::Expired => ("expired", vec![], None),
+        };
+
+        RawGaslessStatus {
+            status: String::from(status),
+            transactions,
+            reason,
+        }
+    }
+}
+
+fn gasless_query(chain_id: u64, params: GaslessParams) -> HashMap<&'static str, String> {
+    let mut map = HashMap::new();
+    map.insert("chainId", chain_id.to_string());
+    map.insert("sellToken", params.sell_token);
+    map.insert("buyToken", params.buy_token);
+    map.insert("sellAmount", params.sell_amount);
+
+    if let Some(taker) = params.taker {
+        map.insert("taker", taker);
+    }
+
+    if let Some(slippage_bps) = params.slippage_bps {
+        map.insert("slippageBps", slippage_bps.to_string());
+    }
+
+    if let Some(excluded_sources) = params.excluded_sources {
+        map.insert("excludedSources", excluded_sources.join(","));
+    }
+
+    if let Some(swap_fee_recipient) = params.swap_fee_recipient {
+        map.insert("swapFeeRecipient", swap_fee_recipient);
+    }
+
+    if let Some(swap_fee_bps) = params.swap_fee_bps {
+        map.insert("swapFeeBps", swap_fee_bps.to_string());
+    }
+
+    if let Some(swap_fee_token) = params.swap_fee_token {
+        map.insert("swapFeeToken", swap_fee_token);
+    }
+
+    map
+}
+
+impl ZeroXClient {
+    pub async fn get_gasless_quote(
+        &self,
+        params: GaslessParams,
+    ) -> Result<GaslessQuote, ZeroXClientError> {
+        let quote: GaslessQuote = self.gasless("/gasless/quote", params).await?;
+        self.check_gasless_terms(V2Terms {
+            sell_token: &quote.sell_token,
+            buy_token: &quote.buy_token,
+            sell_amount: &quote.sell_amount,
+            buy_amount: &quote.buy_amount,
+            min_buy_amount: &quote.min_buy_amount,
+        })?;
+        Ok(quote)
+    }
+
+    /// Fetches an indicative gasless price, for polling before requesting a quote.
+    pub async fn get_gasless_price(
+        &self,
+        params: GaslessParams,
+    ) -> Result<GaslessPrice, ZeroXClientError> {
+        let price: GaslessPrice = self.gasless("/gasless/price", params).await?;
+        self.check_gasless_terms(V2Terms {
+            sell_token: &price.sell_token,
+            buy_token: &price.buy_token,
+            sell_amount: &price.sell_amount,
+            buy_amount: &price.buy_amount,
+            min_buy_amount: &price.min_buy_amount,
+        })?;
+        Ok(price)
+    }
+
+    /// Submits a signed gasless trade, and the signed approval if the quote had one, to the
+    /// relayer. Uses the [`Endpoint::GaslessSubmit`](crate::Endpoint::GaslessSubmit) timeout
+    /// if one is set, since the relayer can take seconds to accept a trade.
+    pub async fn submit_gasless(
+        &self,
+        trade: &SignedGasless,
+        approval: Option<&SignedGasless>,
+    ) -> Result<GaslessSubmission, ZeroXClientError> {
+        let mut body = serde_json::json!({
+            "chainId": self.chain_id,
+            "trade": trade.to_json(),
+        });
+        if let Some(approval) = approval {
+            body["approval"] = approval.to_json();
+        }
+        Ok(self.post_v2("/gasless/submit", &body).await?.data)
+    }
+
+    /// Chains the gasless API supports.
+    pub async fn gasless_chains(&self) -> Result<Vec<GaslessChain>, ZeroXClientError> {
+        Ok(self
+            .get_v2::<GaslessChainsResponse>("/gasless/chains", &HashMap::new())
+            .await?
+            .data
+            .chains)
+    }
+
+    /// Tokens on this client's chain that support gasless approvals, so selling them needs no
+    /// prior on-chain approval transaction.
+    pub async fn gasless_approval_tokens(&self) -> Result<Vec<String>, ZeroXClientError> {
+        let query = HashMap::from([("chainId", self.chain_id.to_string())]);
+        Ok(self
+            .get_v2::<GaslessApprovalTokensResponse>("/gasless/gasless-approval-tokens", &query)
+            .await?
+            .data
+            .tokens)
+    }
+
+    /// Whether the gasless path can be offered for selling `token` without the taker first
+    /// sending an approval: the chain must be supported and the token must allow gasless
+    /// approvals.
+    pub async fn supports_gasless_approval(&self, token: &str) -> Result<bool, ZeroXClientError> {
+        let chain_id = self.chain_id.to_string();
+        if !self
+            .gasless_chains()
+            .await?
+            .iter()
+            .any(|chain| chain.chain_id == chain_id)
+        {
+            return Ok(false);
+        }
+
+        Ok(self
+            .gasless_approval_tokens()
+            .await?
+            .iter()
+            .any(|t| t.eq_ignore_ascii_case(token)))
+    }
+
+    pub async fn get_gasless_status(
+        &self,
+        trade_hash: &str,
+    ) -> Result<GaslessStatus, ZeroXClientError> {
+        let query = HashMap::from([("chainId", self.chain_id.to_string())]);
+        Ok(self
+            .get_v2(&format!("/gasless/status/{}", trade_hash), &query)
+            .await?
+            .data)
+    }
+
+    /// Polls the status of a submitted gasless trade until it succeeds, fails or expires.
+    /// Errors fetching the status end the wait; retries are left to the client's retry policy.
+    pub async fn await_confirmation(
+        &self,
+        trade_hash: &str,
+        timeout: Duration,
+    ) -> Result<GaslessStatus, GaslessStatusError> {
+        let poll = async {
+            loop {
+                let status = self.get_gasless_status(trade_hash).await?;
+                if status.is_terminal() {
+                    return Ok(status);
+                }
+                debug!("gasless trade {} is {:?}", trade_hash, status);
+                tokio::time::sleep(STATUS_POLL_INTERVAL).await;
+            }
+        };
+
+        tokio::time::timeout(timeout, poll)
+            .await
+            .map_err(|_| GaslessStatusError::Timeout(timeout))?
+    }
+
+    fn check_gasless_terms(&self, terms: V2Terms<'_>) -> Result<(), ZeroXClientError> {
+        match &self.policy {
+            Some(policy) => policy
+                .check_v2_terms(self.chain_id, terms)
+                .map_err(ZeroXClientError::PolicyViolation),
+            None => Ok(()),
+        }
+    }
+
+    async fn gasless<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        params: GaslessParams,
+    ) -> Result<T, ZeroXClientError> {
+        self.screen_request(ScreeningRequest {
+            chain_id: self.chain_id,
+            sell_token: &params.sell_token,
+            buy_token: &params.buy_token,
+            taker: params.taker.as_deref(),
+        })
+        .await?;
+        if let Some(policy) = &self.policy {
+            policy
+                .check_v2_slippage(params.slippage_bps)
+                .map_err(ZeroXClientError::PolicyViolation)?;
+        }
+
+        let query = gasless_query(self.chain_id, params);
+        Ok(self.get_v2(path, &query).await?.data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use wiremock::{
+        matchers::{body_partial_json, header, method, path, query_param},
+        Mock, MockServer, ResponseTemplate,
+    };
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_gasless_price_and_quote() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/gasless/price"))
+            .and(header("0x-version", "v2"))
+            .and(query_param("chainId", "137"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "buyAmount": "2000000",
+                "liquidityAvailable": true,
+                "allowanceTarget": "0x06",
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/gasless/quote"))
+            .and(query_param("taker", "0x07"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "buyAmount": "2000000",
+                "trade": { "type": "settler_metatransaction", "hash": "0x08", "eip712": {} },
+                "approval": null,
+            })))
+            .mount(&server)
+            .await;
+
+        let client = ZeroXClient::builder(137, String::from("key"))
+            .v2_base_url(server.uri())
+            .build()
+            .unwrap();
+        let params = GaslessParams {
+            sell_token: String::from("WETH"),
+            buy_token: String::from("USDC"),
+            sell_amount: String::from("1000000000000000"),
+            ..Default::default()
+        };
+
+        let price = client.get_gasless_price(params.clone()).await.unwrap();
+        assert_eq!(price.liquidity_available, Some(true));
+        assert_eq!(price.allowance_target.as_deref(), Some("0x06"));
+
+        let quote = client
+            .get_gasless_quote(GaslessParams {
+                taker: Some(String::from("0x07")),
+                ..params
+            })
+            .await
+            .unwrap();
+        assert_eq!(quote.trade.unwrap().hash.as_deref(), Some("0x08"));
+        assert!(quote.approval.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_submit_gasless() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/gasless/submit"))
+            .and(header("0x-version", "v2"))
+            .and(body_partial_json(serde_json::json!({
+                "chainId": 137,
+                "trade": {
+                    "type": "settler_metatransaction",
+                    "signature": { "v": 27, "signatureType": 2 },
+                },
+            })))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(serde_json::json!({ "tradeHash": "0x0b", "zid": "0x0c" }))
+                    .set_delay(Duration::from_millis(200)),
+            )
+            .mount(&server)
+            .await;
+
+        // the submit timeout replaces the client-wide one
+        let client = ZeroXClient::builder(137, String::from("key"))
+            .v2_base_url(server.uri())
+            .timeout(Duration::from_millis(50))
+            .endpoint_timeout(crate::Endpoint::GaslessSubmit, Duration::from_secs(5))
+            .build()
+            .unwrap();
+        let trade = SignedGasless {
+            signable: GaslessSignable {
+                type_: Some(String::from("settler_metatransaction")),
+                hash: Some(String::from("0x08")),
+                eip712: Some(serde_json::json!({})),
+            },
+            signature: Signature {
+                r: 1.into(),
+                s: 2.into(),
+                v: 27,
+            },
+        };
+
+        let submission = client.submit_gasless(&trade, None).await.unwrap();
+        assert_eq!(submission.trade_hash, "0x0b");
+        assert_eq!(submission.zid.as_deref(), Some("0x0c"));
+    }
+
+    #[tokio::test]
+    async fn test_await_confirmation() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/gasless/status/0x09"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "status": "submitted",
+                "transactions": [],
+            })))
+            .up_to_n_times(1)
+            .with_priority(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/gasless/status/0x09"))
+            .and(query_param("chainId", "137"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "status": "confirmed",
+                "transactions": [
+                    { "hash": format!("{:?}", TxHash::repeat_byte(1)), "timestamp": 1 },
+                    { "hash": format!("{:?}", TxHash::repeat_byte(2)), "timestamp": 2 },
+                ],
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/gasless/status/0x0a"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "status": "pending",
+            })))
+            .mount(&server)
+            .await;
+
+        let client = ZeroXClient::builder(137, String::from("key"))
+            .v2_base_url(server.uri())
+            .build()
+            .unwrap();
+
+        let status = client
+            .await_confirmation("0x09", Duration::from_secs(5))
+            .await
+            .unwrap();
+        assert_eq!(
+            status,
+            GaslessStatus::Succeeded {
+                tx_hash: TxHash::repeat_byte(2)
+            }
+        );
+
+        assert!(matches!(
+            client
+                .await_confirmation("0x0a", Duration::from_millis(100))
+                .await,
+            Err(GaslessStatusError::Timeout(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_gasless_discovery() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/gasless/chains"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "chains": [{ "chainId": "137", "chainName": "Polygon" }],
+                "zid": "0x01",
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/gasless/gasless-approval-tokens"))
+            .and(query_param("chainId", "137"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "tokens": ["0x3c499c542cEF5E3811e1192ce70d8cC03d5c3359"],
+                "zid": "0x02",
+            })))
+            .mount(&server)
+            .await;
+
+        let client = ZeroXClient::builder(137, String::from("key"))
+            .v2_base_url(server.uri())
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            client.gasless_chains().await.unwrap()[0].chain_name,
+            "Polygon"
+        );
+        assert!(client
+            .supports_gasless_approval("0x3c499c542cef5e3811e1192ce70d8cc03d5c3359")
+            .await
+            .unwrap());
+        assert!(!client.supports_gasless_approval("0x01").await.unwrap());
+    }
+}
@@ -0,0 +1,167 @@
+//! A tonic service streaming the quotes a [`Watchlist`] refreshes to gRPC clients, so services
+//! not written in Rust can consume them. The schema is `PriceStream` in
+//! `proto/zerox/v1/quote.proto`.
+
+use std::{net::SocketAddr, pin::Pin, sync::Arc, time::Duration};
+
+use async_trait::async_trait;
+use tokio::sync::broadcast;
+use tokio_stream::{wrappers::BroadcastStream, Stream, StreamExt};
+use tonic::{Request, Response, Status};
+use tracing::debug;
+
+use crate::{
+    proto::pb::{
+        price_stream_server::{PriceStream, PriceStreamServer},
+        PairFilter, QuoteUpdate, SubscribeRequest,
+    },
+    sink::{QuoteEvent, QuoteSink},
+    WatchedPair, Watchlist, ZeroXClient,
+};
+
+/// Fans out quote events to subscribers. Pass [`PriceStreamService::sink`] to
+/// [`Watchlist::with_sink`] to stream the watchlist's refreshes.
+///
+/// Subscribers that fall more than `capacity` events behind skip the events they missed.
+#[derive(Clone)]
+pub struct PriceStreamService {
+    events: broadcast::Sender<QuoteEvent>,
+}
+
+impl PriceStreamService {
+    pub fn new(capacity: usize) -> PriceStreamService {
+        PriceStreamService {
+            events: broadcast::channel(capacity).0,
+        }
+    }
+
+    pub fn sink(&self) -> Arc<dyn QuoteSink> {
+        Arc::new(BroadcastSink(self.events.clone()))
+    }
+
+    pub fn into_server(self) -> PriceStreamServer<PriceStreamService> {
+        PriceStreamServer::new(self)
+    }
+}
+
+struct BroadcastSink(broadcast::Sender<QuoteEvent>);
+
+#[async_trait]
+impl QuoteSink for BroadcastSink {
+    async fn publish(
+        &self,
+        event: &QuoteEvent,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        // No subscribers is not an error; the event is dropped.
+        let _ = self.0.send(event.clone());
+        Ok(())
+    }
+}
+
+fn matches(filter: &PairFilter, event: &QuoteEvent) -> bool {
+    let field = |filter: &str, value: &str| filter.is_empty() || filter.eq_ignore_ascii_case(value);
+    field(&filter.sell_token, &event.sell_token)
+        && field(&filter.buy_token, &event.buy_token)
+        && field(&filter.sell_amount, &event.sell_amount)
+}
+
+#[async_trait]
+impl PriceStream for PriceStreamService {
+    type SubscribeStream = Pin<Box<dyn Stream<Item = Result<QuoteUpdate, Status>> + Send>>;
+
+    async fn subscribe(
+        &self,
+        request: Request<SubscribeRequest>,
+    ) -> Result<Response<Self::SubscribeStream>, Status> {
+        let filters = request.into_inner().pairs;
+        let updates =
+            BroadcastStream::new(self.events.subscribe()).filter_map(move |event| match event {
+                Ok(event) if filters.is_empty() || filters.iter().any(|f| matches(f, &event)) => {
+                    match QuoteUpdate::try_from(&event) {
+                        Ok(update) => Some(Ok(update)),
+                        Err(err) => {
+                            debug!("failed to encode quote update: {}", err);
+                            None
+                        }
+                    }
+                }
+                Ok(_) => None,
+                Err(err) => {
+                    debug!("quote subscriber lagged: {}", err);
+                    None
+                }
+            });
+
+        Ok(Response::new(Box::pin(updates)))
+    }
+}
+
+/// Serves `PriceStream` on `addr`, streaming quotes for `pairs` refreshed every
+/// `refresh_interval`.
+pub async fn serve(
+    addr: SocketAddr,
+    client: Arc<ZeroXClient>,
+    refresh_interval: Duration,
+    pairs: Vec<WatchedPair>,
+) -> Result<(), tonic::transport::Error> {
+    let service = PriceStreamService::new(256);
+    let watchlist = Watchlist::with_sink(client, refresh_interval, service.sink());
+    for pair in pairs {
+        watchlist.register(pair);
+    }
+
+    tonic::transport::Server::builder()
+        .add_service(service.into_server())
+        .serve(addr)
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{proto::pb::quote_update::Payload, sink::QuoteEventKind, ZeroXQuoteResponse};
+
+    use super::*;
+
+    fn event(buy_token: &str) -> QuoteEvent {
+        let quote: ZeroXQuoteResponse =
+            serde_json::from_value(serde_json::json!({ "price": "2000" })).unwrap();
+        QuoteEvent::new(
+            QuoteEventKind::Price,
+            1,
+            "ETH",
+            buy_token,
+            "1",
+            Arc::new(quote),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_subscribe_filters_pairs() {
+        let service = PriceStreamService::new(16);
+        let request = SubscribeRequest {
+            pairs: vec![PairFilter {
+                buy_token: String::from("dai"),
+                ..Default::default()
+            }],
+        };
+        let mut updates = service
+            .subscribe(Request::new(request))
+            .await
+            .unwrap()
+            .into_inner();
+
+        let sink = service.sink();
+        sink.publish(&event("USDC")).await.unwrap();
+        sink.publish(&event("DAI")).await.unwrap();
+
+        let update = tokio::time::timeout(Duration::from_secs(1), updates.next())
+            .await
+            .unwrap()
+            .unwrap()
+            .unwrap();
+        assert_eq!(update.buy_token, "DAI");
+        assert!(
+            matches!(update.payload, Some(Payload::Price(price)) if price.price.as_deref() == Some("2000"))
+        );
+    }
+}
@@ -0,0 +1,342 @@
+//! Building a price history from 0x: a sampler that fetches indicative prices for a set of
+//! pairs on an interval and appends them to a [`PriceStore`].
+
+use std::{
+    fs::{File, OpenOptions},
+    io::Write,
+    path::Path,
+    sync::{Arc, Mutex},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use serde::Serialize;
+use tokio::{task::JoinHandle, time::MissedTickBehavior};
+use tracing::debug;
+
+use crate::{scheduler::MIN_INTERVAL, WatchedPair, WithMetadata, ZeroXClient, ZeroXQuoteResponse};
+
+/// A liquidity source's share of a sampled price.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct SourceShare {
+    pub name: String,
+    pub proportion: String,
+}
+
+/// One price observation for a pair.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct PriceSample {
+    pub chain_id: u64,
+    pub sell_token: String,
+    pub buy_token: String,
+    pub sell_amount: String,
+    /// Unix timestamp in milliseconds.
+    pub sampled_at: u64,
+    pub price: Option<String>,
+    pub buy_amount: Option<String>,
+    pub estimated_gas: Option<String>,
+    /// Time the attempt that returned the price took.
+    pub latency_ms: u64,
+    /// Sources with a non-zero share.
+    pub sources: Vec<SourceShare>,
+}
+
+impl PriceSample {
+    fn new(
+        chain_id: u64,
+        pair: &WatchedPair,
+        response: WithMetadata<ZeroXQuoteResponse>,
+    ) -> PriceSample {
+        let latency = response.metadata.timings.total;
+        let price = response.data;
+        PriceSample {
+            chain_id,
+            sell_token: pair.sell_token.clone(),
+            buy_token: pair.buy_token.clone(),
+            sell_amount: pair.sell_amount.clone(),
+            sampled_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_millis() as u64,
+            price: price.price,
+            buy_amount: price.buy_amount,
+            estimated_gas: price.estimated_gas,
+            latency_ms: latency.as_millis() as u64,
+            sources: price
+                .sources
+                .into_iter()
+                .flatten()
+                .filter_map(|source| {
+                    let proportion = source.proportion?;
+                    let is_zero = proportion.trim_matches(|c| c == '0' || c == '.').is_empty();
+                    (!is_zero).then_some(SourceShare {
+                        name: source.name?,
+                        proportion,
+                    })
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Append-only storage for price samples.
+pub trait PriceStore: Send + Sync {
+    fn append(&self, sample: &PriceSample) -> Result<(), Box<dyn std::error::Error + Send + Sync>>;
+}
+
+#[derive(Debug, Default)]
+pub struct MemoryPriceStore {
+    samples: Mutex<Vec<PriceSample>>,
+}
+
+impl MemoryPriceStore {
+    pub fn samples(&self) -> Vec<PriceSample> {
+        self.samples.lock().unwrap().clone()
+    }
+}
+
+impl PriceStore for MemoryPriceStore {
+    fn append(&self, sample: &PriceSample) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.samples.lock().unwrap().push(sample.clone());
+        Ok(())
+    }
+}
+
+const CSV_HEADER: &str = "chain_id,sell_token,buy_token,sell_amount,sampled_at,price,buy_amount,estimated_gas,latency_ms,sources";
+
+/// Stores samples as CSV rows, writing a header to new files. Sources are written as
+/// `name:proportion` joined with `;`.
+#[derive(Debug)]
+pub struct CsvPriceStore {
+    file: Mutex<File>,
+}
+
+impl CsvPriceStore {
+    pub fn open(path: impl AsRef<Path>) -> std::io::Result<CsvPriceStore> {
+        let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+        if file.metadata()?.len() == 0 {
+            writeln!(file, "{CSV_HEADER}")?;
+        }
+
+        Ok(CsvPriceStore {
+            file: Mutex::new(file),
+        })
+    }
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+impl PriceStore for CsvPriceStore {
+    fn append(&self, sample: &PriceSample) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let sources = sample
+            .sources
+            .iter()
+            .map(|source| format!("{}:{}", source.name, source.proportion))
+            .collect::<Vec<_>>()
+            .join(";");
+        let row = [
+            sample.chain_id.to_string(),
+            csv_field(&sample.sell_token),
+            csv_field(&sample.buy_token),
+            csv_field(&sample.sell_amount),
+            sample.sampled_at.to_string(),
+            csv_field(sample.price.as_deref().unwrap_or_default()),
+            csv_field(sample.buy_amount.as_deref().unwrap_or_default()),
+            csv_field(sample.estimated_gas.as_deref().unwrap_or_default()),
+            sample.latency_ms.to_string(),
+            csv_field(&sources),
+        ]
+        .join(",");
+
+        writeln!(self.file.lock().unwrap(), "{row}")?;
+        Ok(())
+    }
+}
+
+/// Samples the price of each pair every `interval`, at least
+/// [`MIN_INTERVAL`](crate::scheduler::MIN_INTERVAL), and appends it to a store. Failed
+/// requests and store errors are logged and the pair is tried again on the next tick.
+///
+/// The background task stops when the sampler is dropped.
+pub struct PriceSampler {
+    task: JoinHandle<()>,
+}
+
+impl PriceSampler {
+    pub fn start(
+        client: Arc<ZeroXClient>,
+        pairs: Vec<WatchedPair>,
+        interval: Duration,
+        store: Arc<dyn PriceStore>,
+    ) -> PriceSampler {
+        let interval = interval.max(MIN_INTERVAL);
+        let task = tokio::spawn(sample_loop(client, pairs, interval, store));
+
+        PriceSampler { task }
+    }
+}
+
+impl Drop for PriceSampler {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+async fn sample_loop(
+    client: Arc<ZeroXClient>,
+    pairs: Vec<WatchedPair>,
+    interval: Duration,
+    store: Arc<dyn PriceStore>,
+) {
+    let mut ticks = tokio::time::interval(interval);
+    ticks.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+    loop {
+        ticks.tick().await;
+
+        for pair in &pairs {
+            let response = match client.get_price_with_metadata(pair.to_params()).await {
+                Ok(response) => response,
+                Err(err) => {
+                    debug!("failed to sample {:?}: {}", pair, err);
+                    continue;
+                }
+            };
+
+            let sample = PriceSample::new(client.chain_id(), pair, response);
+            if let Err(err) = store.append(&sample) {
+                debug!("failed to store sample for {:?}: {}", pair, err);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use wiremock::{
+        matchers::{method, path},
+        Mock, MockServer, ResponseTemplate,
+    };
+
+    use super::*;
+
+    fn sample() -> PriceSample {
+        PriceSample {
+            chain_id: 1,
+            sell_token: String::from("ETH"),
+            buy_token: String::from("DAI"),
+            sell_amount: String::from("1"),
+            sampled_at: 1_700_000_000_000,
+            price: Some(String::from("2000")),
+            buy_amount: None,
+            estimated_gas: Some(String::from("150000")),
+            latency_ms: 42,
+            sources: vec![
+                SourceShare {
+                    name: String::from("Uniswap_V3"),
+                    proportion: String::from("0.6"),
+                },
+                SourceShare {
+                    name: String::from("Curve"),
+                    proportion: String::from("0.4"),
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn test_csv_store() {
+        let path = std::env::temp_dir().join(format!("zerox-history-{}.csv", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        CsvPriceStore::open(&path)
+            .unwrap()
+            .append(&sample())
+            .unwrap();
+        CsvPriceStore::open(&path)
+            .unwrap()
+            .append(&sample())
+            .unwrap();
+
+        let contents = std::fs::read_to_string(&path).unwrap();
+        let lines: Vec<_> = contents.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0], CSV_HEADER);
+        assert_eq!(
+            lines[1],
+            "1,ETH,DAI,1,1700000000000,2000,,150000,42,Uniswap_V3:0.6;Curve:0.4"
+        );
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_sampler_records_prices() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/swap/v1/price"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "price": "2000",
+                "sources": [
+                    { "name": "Uniswap_V3", "proportion": "1" },
+                    { "name": "Curve", "proportion": "0" },
+                ],
+            })))
+            .mount(&server)
+            .await;
+
+        let client = ZeroXClient::builder(1, String::from("test"))
+            .base_url(server.uri())
+            .build()
+            .unwrap();
+
+        let client = Arc::new(client);
+        let store = Arc::new(MemoryPriceStore::default());
+        let _sampler = PriceSampler::start(
+            client.clone(),
+            vec![WatchedPair::new("ETH", "DAI", "1")],
+            Duration::from_millis(20),
+            store.clone(),
+        );
+
+        for _ in 0..50 {
+            if store.samples().len() >= 2 {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+
+        let samples = store.samples();
+        assert!(samples.len() >= 2);
+        assert_eq!(samples[0].price.as_deref(), Some("2000"));
+        assert_eq!(
+            samples[0].sources,
+            vec![SourceShare {
+                name: String::from("Uniswap_V3"),
+                proportion: String::from("1"),
+            }]
+        );
+
+        // a zero interval used to panic the sampling task
+        let store = Arc::new(MemoryPriceStore::default());
+        let _sampler = PriceSampler::start(
+            client,
+            vec![WatchedPair::new("ETH", "DAI", "1")],
+            Duration::ZERO,
+            store.clone(),
+        );
+        for _ in 0..50 {
+            if store.samples().len() >= 2 {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        assert!(store.samples().len() >= 2);
+    }
+}
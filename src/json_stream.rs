@@ -0,0 +1,165 @@
+//! Incremental decoding of the items of one array field in a JSON object, so large response
+//! pages can be consumed without buffering the whole body.
+
+use serde::de::{DeserializeOwned, Error};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Phase {
+    /// Looking for the array under `key` in the top-level object.
+    Seek,
+    Items,
+    Done,
+}
+
+/// Splits the object items of the top-level `key` array out of a JSON body fed in chunks.
+/// Only the item being parsed is kept in memory.
+pub(crate) struct ArrayItems {
+    key: &'static [u8],
+    buf: Vec<u8>,
+    pos: usize,
+    depth: u32,
+    in_string: bool,
+    escaped: bool,
+    string_start: usize,
+    key_matches: bool,
+    item_start: Option<usize>,
+    phase: Phase,
+}
+
+impl ArrayItems {
+    pub(crate) fn new(key: &'static str) -> ArrayItems {
+        ArrayItems {
+            key: key.as_bytes(),
+            buf: Vec::new(),
+            pos: 0,
+            depth: 0,
+            in_string: false,
+            escaped: false,
+            string_start: 0,
+            key_matches: false,
+            item_start: None,
+            phase: Phase::Seek,
+        }
+    }
+
+    pub(crate) fn push(&mut self, chunk: &[u8]) {
+        if self.phase != Phase::Done {
+            self.buf.extend_from_slice(chunk);
+        }
+    }
+
+    /// The next item complete in the bytes pushed so far, if any.
+    pub(crate) fn next<T: DeserializeOwned>(&mut self) -> Option<Result<T, serde_json::Error>> {
+        while self.pos < self.buf.len() && self.phase != Phase::Done {
+            let byte = self.buf[self.pos];
+            self.pos += 1;
+
+            if self.in_string {
+                if self.escaped {
+                    self.escaped = false;
+                } else if byte == b'\\' {
+                    self.escaped = true;
+                } else if byte == b'"' {
+                    self.in_string = false;
+                    if self.phase == Phase::Seek && self.depth == 1 {
+                        self.key_matches = &self.buf[self.string_start..self.pos - 1] == self.key;
+                    }
+                }
+                continue;
+            }
+
+            match byte {
+                b'"' => {
+                    self.in_string = true;
+                    self.string_start = self.pos;
+                }
+                b'{' | b'[' => {
+                    if self.phase == Phase::Items && self.depth == 2 {
+                        self.item_start = Some(self.pos - 1);
+                    } else if self.phase == Phase::Seek
+                        && self.depth == 1
+                        && byte == b'['
+                        && self.key_matches
+                    {
+                        self.phase = Phase::Items;
+                    }
+                    self.depth += 1;
+                }
+                b'}' | b']' => {
+                    self.depth = self.depth.saturating_sub(1);
+                    if self.phase == Phase::Items && self.depth == 1 {
+                        self.phase = Phase::Done;
+                    } else if self.phase == Phase::Items && self.depth == 2 {
+                        if let Some(start) = self.item_start.take() {
+                            let item = serde_json::from_slice(&self.buf[start..self.pos]);
+                            self.compact();
+                            return Some(item);
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        self.compact();
+        None
+    }
+
+    /// Call once the body has ended. Fails if it ended before the array did.
+    pub(crate) fn finish(&self) -> Result<(), serde_json::Error> {
+        match self.phase {
+            Phase::Done => Ok(()),
+            Phase::Seek => Err(serde_json::Error::custom(format!(
+                "no '{}' array in response",
+                String::from_utf8_lossy(self.key)
+            ))),
+            Phase::Items => Err(serde_json::Error::custom("response ended inside the array")),
+        }
+    }
+
+    /// Drops the bytes that have been fully consumed.
+    fn compact(&mut self) {
+        let keep_from = match (self.item_start, self.in_string) {
+            (Some(start), _) => start,
+            (None, true) => self.string_start,
+            (None, false) => self.pos,
+        };
+
+        self.buf.drain(..keep_from);
+        self.pos -= keep_from;
+        self.string_start = self.string_start.saturating_sub(keep_from);
+        self.item_start = self.item_start.map(|start| start - keep_from);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::Value;
+
+    use super::*;
+
+    #[test]
+    fn test_items_across_chunks() {
+        let body = br#"{"total":2,"note":"orders","orders":[{"id":1,"s":"a}\"]"},{"id":2,"nested":{"orders":[]}}],"page":1}"#;
+
+        for chunk_size in [1, 3, 7, body.len()] {
+            let mut items = ArrayItems::new("orders");
+            let mut ids = Vec::new();
+            for chunk in body.chunks(chunk_size) {
+                items.push(chunk);
+                while let Some(item) = items.next::<Value>() {
+                    ids.push(item.unwrap()["id"].as_u64().unwrap());
+                }
+                assert!(items.buf.len() <= 40);
+            }
+            assert_eq!(ids, vec![1, 2]);
+            assert!(items.finish().is_ok());
+        }
+
+        let mut truncated = ArrayItems::new("orders");
+        truncated.push(br#"{"orders":[{"id":1},{"id""#);
+        assert!(truncated.next::<Value>().unwrap().is_ok());
+        assert!(truncated.next::<Value>().is_none());
+        assert!(truncated.finish().is_err());
+    }
+}
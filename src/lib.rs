@@ -1,25 +1,169 @@
 use reqwest::{
-    header::{HeaderValue, InvalidHeaderValue},
-    StatusCode,
+    header::{HeaderMap, HeaderValue, InvalidHeaderValue},
+    Method, StatusCode,
 };
-use serde::{Deserialize, Serialize};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use serde_json::Value;
-use std::collections::HashMap;
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex, RwLock},
+    time::{Duration, Instant},
+};
 use thiserror::Error;
 use tracing::debug;
 
-#[derive(Serialize, Deserialize, Default)]
+pub mod alerts;
+pub mod amount;
+pub mod api;
+pub mod approval;
+pub mod audit;
+pub mod balance;
+pub mod borrowed;
+pub mod config;
+pub mod defaults;
+pub mod degrade;
+pub mod deployments;
+pub mod downsize;
+pub mod eip712;
+pub mod executor;
+pub mod fees;
+#[cfg(feature = "uniffi")]
+pub mod ffi;
+#[cfg(feature = "fork-tests")]
+pub mod fork;
+pub mod gasless;
+#[cfg(feature = "grpc")]
+pub mod grpc;
+pub mod history;
+mod json_stream;
+#[cfg(feature = "rpc")]
+pub mod limit_orders;
+pub mod logging;
+pub mod metadata;
+pub mod mev;
+pub mod multicall;
+pub mod multichain;
+pub mod nft;
+pub mod oracle;
+pub mod parity;
+pub mod permit;
+pub mod policy;
+pub mod presets;
+pub mod price_only;
+pub mod price_stream;
+#[cfg(feature = "protobuf")]
+pub mod proto;
+pub mod provider;
+pub mod quick;
+pub mod quoter;
+mod rate_limit;
+pub mod rebalance;
+pub mod report;
+pub mod request_log;
+pub mod retry;
+pub mod scheduler;
+pub mod screening;
+#[cfg(feature = "server")]
+pub mod server;
+pub mod session;
+pub mod simulate;
+pub mod sink;
+pub mod snapshot;
+pub mod source_comparison;
+mod sources;
+pub mod sweep;
+mod tls;
+pub mod tokens;
+pub mod transport;
+pub mod twap;
+pub mod units;
+pub mod usage;
+pub mod v2;
+pub mod validation;
+pub mod watchlist;
+
+pub use alerts::{AlertEvent, AlertMonitor, AlertRule, PriceCondition, WebhookNotifier};
+pub use amount::{Amount, AmountParseError};
+pub use api::ZeroXApi;
+pub use audit::{AuditDecision, AuditRecord, AuditStore, AuditTrail};
+pub use borrowed::{QuoteBody, ZeroXQuoteResponseRef};
+pub use config::{ConfigError, ConfigFile};
+pub use defaults::QuoteDefaults;
+pub use degrade::QuoteOrPrice;
+pub use downsize::DownsizedQuote;
+pub use executor::{ConfirmedSwap, SwapExecutor, SwapExecutorError};
+pub use fees::{Eip1559Fees, FeeEstimator, ProviderFeeEstimator};
+pub use gasless::{
+    GaslessChain, GaslessParams, GaslessPrice, GaslessQuote, GaslessStatus, GaslessStatusError,
+    GaslessSubmission, SignedGasless,
+};
+pub use history::{PriceSample, PriceSampler, PriceStore};
+pub use logging::LogRedaction;
+use logging::RequestLog;
+use metadata::{HandshakeTimings, TimingResolver};
+pub use metadata::{RequestTimings, ResponseMetadata, WithMetadata};
+pub use mev::{RiskLevel, SandwichRisk};
+pub use multichain::{ApiKeys, MultiChainClient};
+pub use oracle::{PriceOracle, ZeroXPriceOracle};
+pub use parity::{VersionComparison, VersionDiff};
+pub use policy::{PolicyViolation, TradingPolicy};
+pub use presets::{QuoteMode, SourcePreset};
+pub use price_only::PriceOnlyClient;
+pub use price_stream::{PriceStream, PriceStreamExt, PriceTick};
+pub use provider::{SwapProvider, SwapProviderError, ZeroXMiddlewareExt};
+pub use quoter::{NormalizedQuote, SwapQuoter, SwapRequest};
+use rate_limit::RateLimiter;
+pub use rate_limit::{LocalRateLimit, RateLimitStatus, RequestPriority, ServerRateLimit};
+pub use rebalance::{RebalancePlan, RebalancePlanner};
+pub use report::{ReportFormat, TradeReport};
+pub use request_log::JsonlRequestLog;
+pub use retry::{ExponentialBackoff, NoRetry, RetryContext, RetryPolicy};
+pub use scheduler::{QuoteHandler, QuoteScheduler, Schedule};
+pub use screening::{Blocklist, NoScreening, ScreeningRejection, ScreeningRequest, TokenScreener};
+pub use session::{QuoteSession, QuoteSessionConfig};
+pub use sink::{QuoteEvent, QuoteEventKind, QuoteSink};
+pub use snapshot::{QuoteReplay, QuoteSnapshot, SnapshotWriter};
+pub use source_comparison::{SourceComparison, SourceEstimate};
+use sources::SourcesCache;
+pub use sweep::{SweepError, SweepRequest};
+use tls::TlsSettings;
+pub use transport::{HttpResponse, HttpTransport, TransportClient};
+pub use twap::{TwapExecution, TwapPlan, TwapProgress, TwapSlice};
+pub use units::{FeeBps, Percentage};
+use usage::UsageTracker;
+pub use usage::{UsageLogger, UsageStats};
+pub use v2::{QuoteResponse, SwapFlow, ZeroXQuoteParamsV2, ZeroXQuoteResponseV2};
+pub use validation::{QuoteRejected, QuoteValidator};
+pub use watchlist::{CachedQuote, WatchedPair, Watchlist};
+
+#[cfg(feature = "uniffi")]
+uniffi::setup_scaffolding!();
+
+#[derive(Serialize, Deserialize, Default, Clone, Debug)]
+#[cfg_attr(feature = "proptest", derive(proptest_derive::Arbitrary))]
 pub struct ZeroXQuoteParams {
     pub sell_token: String,
     pub buy_token: String,
     pub sell_amount: String,
     pub fee_recipient: Option<String>,
-    pub buy_token_percentage_fee: Option<String>,
+    /// Sent as a fraction, e.g. `0.01` for 1%.
+    pub buy_token_percentage_fee: Option<Percentage>,
     pub taker_address: Option<String>,
-    pub slippage_percentage: Option<String>,
+    /// Sent as a fraction, e.g. `0.01` for 1%.
+    pub slippage_percentage: Option<Percentage>,
     pub excluded_sources: Option<Vec<String>>,
     pub included_sources: Option<Vec<String>>,
-    pub skip_validation: Option<String>,
+    #[serde(default, deserialize_with = "bool_or_string")]
+    pub skip_validation: Option<bool>,
+    /// Address credited for the trade in 0x's analytics.
+    pub affiliate_address: Option<String>,
+    /// Sells the taker's whole ERC-20 balance at execution, even if it changed after quoting.
+    /// Requires `taker_address`.
+    #[serde(default, deserialize_with = "bool_or_string")]
+    pub should_sell_entire_balance: Option<bool>,
+    /// Adds `priceComparisons` with the price each single source would have given.
+    #[serde(default, deserialize_with = "bool_or_string")]
+    pub include_price_comparisons: Option<bool>,
 }
 
 #[derive(Error, Debug)]
@@ -36,47 +180,968 @@ pub enum ZeroXClientError {
     #[error("Invalid response status code from 0x API: {0}")]
     ZeroXInvalidResponseStatusCode(StatusCode),
 
+    /// The API key was rejected with 401 or 403.
+    #[error("API key rejected by 0x API: {0}")]
+    InvalidApiKey(StatusCode),
+
     #[error("Failed to parse response from 0x API: {0}")]
     ZeroXInvalidResponse(#[from] serde_json::Error),
+
+    #[error("Quote is stale: fetched {0:?} ago")]
+    StaleQuote(Duration),
+
+    #[error("Token screening failed: {0}")]
+    ScreeningRejected(ScreeningRejection),
+
+    #[error("Quote rejected by {0}")]
+    QuoteRejected(QuoteRejected),
+
+    #[error("Trading policy violated: {0}")]
+    PolicyViolation(PolicyViolation),
+
+    #[error("Transport error: {0}")]
+    Transport(Box<dyn std::error::Error + Send + Sync>),
+
+    #[error("Response body exceeds the {limit} byte limit")]
+    ResponseTooLarge { limit: usize },
+
+    #[error("No API key configured for chain {0}")]
+    MissingApiKey(u64),
+
+    #[error("Unknown liquidity source: {0}")]
+    UnknownSource(String),
+
+    #[error("RPC error: {0}")]
+    Rpc(Box<dyn std::error::Error + Send + Sync>),
+
+    #[error("Invalid TLS configuration: {0}")]
+    InvalidTlsConfig(String),
+
+    #[error(transparent)]
+    InvalidAmount(#[from] AmountParseError),
+}
+
+/// Broad classification of a [`ZeroXClientError`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorKind {
+    /// HTTP 429.
+    RateLimited,
+    /// Timeouts, connection failures and 5xx responses.
+    Transient,
+    /// HTTP 401 or 403, usually a missing or invalid API key.
+    Unauthorized,
+    /// Other 4xx responses, e.g. validation errors.
+    InvalidRequest,
+    /// The response body could not be parsed.
+    InvalidResponse,
+    /// Rejected by the client: invalid configuration, screening, a stale quote or a failed
+    /// quote validator.
+    Client,
+}
+
+impl ZeroXClientError {
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            ZeroXClientError::ZeroXQuoteError(err) => match err.status() {
+                Some(status) => status_kind(status),
+                None if err.is_decode() => ErrorKind::InvalidResponse,
+                None if err.is_builder() => ErrorKind::Client,
+                None => ErrorKind::Transient,
+            },
+            ZeroXClientError::ZeroXInvalidResponseStatusCode(status) => status_kind(*status),
+            ZeroXClientError::InvalidApiKey(_) => ErrorKind::Unauthorized,
+            ZeroXClientError::ZeroXInvalidResponse(_)
+            | ZeroXClientError::ResponseTooLarge { .. } => ErrorKind::InvalidResponse,
+            ZeroXClientError::Transport(_) | ZeroXClientError::Rpc(_) => ErrorKind::Transient,
+            ZeroXClientError::InvalidChainId(_)
+            | ZeroXClientError::MissingApiKey(_)
+            | ZeroXClientError::UnknownSource(_)
+            | ZeroXClientError::InvalidTlsConfig(_)
+            | ZeroXClientError::InvalidAmount(_)
+            | ZeroXClientError::ZeroXInvalidHeaderValue(_)
+            | ZeroXClientError::StaleQuote(_)
+            | ZeroXClientError::ScreeningRejected(_)
+            | ZeroXClientError::QuoteRejected(_)
+            | ZeroXClientError::PolicyViolation(_) => ErrorKind::Client,
+        }
+    }
+
+    /// Whether retrying the same request may succeed.
+    pub fn is_retryable(&self) -> bool {
+        matches!(self.kind(), ErrorKind::RateLimited | ErrorKind::Transient)
+    }
+}
+
+/// The error for a non-200 response.
+fn status_error(status: StatusCode) -> ZeroXClientError {
+    match status {
+        StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => ZeroXClientError::InvalidApiKey(status),
+        status => ZeroXClientError::ZeroXInvalidResponseStatusCode(status),
+    }
+}
+
+fn status_kind(status: StatusCode) -> ErrorKind {
+    match status {
+        StatusCode::TOO_MANY_REQUESTS => ErrorKind::RateLimited,
+        StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => ErrorKind::Unauthorized,
+        status if status.is_server_error() => ErrorKind::Transient,
+        status if status.is_client_error() => ErrorKind::InvalidRequest,
+        _ => ErrorKind::InvalidResponse,
+    }
+}
+
+/// Whether a failed request is resent to the fallback base URL: like retries, only idempotent
+/// requests and only for errors a retry may fix.
+fn falls_back(request: &ApiRequest<'_>, err: &ZeroXClientError) -> bool {
+    request.method == Method::GET && err.is_retryable()
 }
 
+/// A 0x API client.
+///
+/// Cloning is cheap: clones share the connection pool, rate limiter, retry policy, token
+/// screener and runtime configuration, so a client can be stored in server state or handed
+/// to tasks directly.
+///
+/// The client, its builder, the response types and [`ZeroXClientError`] are all
+/// `Send + Sync + 'static`; this is checked at compile time.
+#[derive(Clone)]
 pub struct ZeroXClient {
-    base_url: String,
+    chain_id: u64,
+    live: Arc<RwLock<LiveConfig>>,
+    fallback_base_url: Option<String>,
+    v2_base_url: String,
+    v1_fallback: bool,
+    defaults: Arc<QuoteDefaults>,
+    sources_cache: Option<Arc<SourcesCache>>,
+    hedge_after: Option<Duration>,
+    endpoint_timeouts: Arc<HashMap<Endpoint, Duration>>,
+    http: reqwest::Client,
+    resolver: Arc<TimingResolver>,
+    handshakes: HandshakeTimings,
+    retry_policy: Arc<dyn RetryPolicy>,
+    token_screener: Arc<dyn TokenScreener>,
+    quote_validators: Arc<[Arc<dyn QuoteValidator>]>,
+    policy: Option<Arc<TradingPolicy>>,
+    log_redaction: LogRedaction,
+    log_bodies: bool,
+    max_response_size: Option<usize>,
+    server_rate_limit: Arc<Mutex<Option<ServerRateLimit>>>,
+    usage: Arc<UsageTracker>,
+    request_log: Option<Arc<JsonlRequestLog>>,
+    priority: RequestPriority,
+}
+
+/// Settings that can be changed on a live client with [`ZeroXClient::reload`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClientConfig {
+    pub api_key: String,
+    pub base_url: String,
+    pub rate_limit: Option<(u32, Duration)>,
+}
+
+/// Which generation of the 0x Swap API a request targets.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ApiVersion {
+    V1,
+    V2,
+}
+
+/// Groups of API endpoints that can be given their own timeout with
+/// [`ZeroXClientBuilder::endpoint_timeout`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Endpoint {
+    /// Indicative prices: `/swap/*/price` and `/gasless/price`.
+    Price,
+    /// Firm quotes: `/swap/*/quote` and `/gasless/quote`.
+    Quote,
+    /// `/orderbook/*`.
+    Orderbook,
+    /// `/gasless/submit`, which waits for the relayer to accept the trade.
+    GaslessSubmit,
+}
+
+impl Endpoint {
+    fn of(path: &str) -> Option<Endpoint> {
+        if path.starts_with("/orderbook/") {
+            Some(Endpoint::Orderbook)
+        } else if path == "/gasless/submit" {
+            Some(Endpoint::GaslessSubmit)
+        } else if path.ends_with("/price") {
+            Some(Endpoint::Price)
+        } else if path.ends_with("/quote") {
+            Some(Endpoint::Quote)
+        } else {
+            None
+        }
+    }
+}
+
+struct ApiRequest<'a> {
+    method: Method,
+    version: ApiVersion,
+    path: &'a str,
+    query: &'a HashMap<&'a str, String>,
+    body: Option<&'a Value>,
+}
+
+struct LiveConfig {
+    config: ClientConfig,
+    rate_limiter: Option<Arc<RateLimiter>>,
+}
+
+impl LiveConfig {
+    fn new(config: ClientConfig) -> LiveConfig {
+        LiveConfig {
+            rate_limiter: config
+                .rate_limit
+                .map(|(requests, per)| Arc::new(RateLimiter::new(requests, per))),
+            config,
+        }
+    }
+}
+
+pub struct ZeroXClientBuilder {
+    chain_id: u64,
     api_key: String,
+    base_url: Option<String>,
+    fallback_base_url: Option<String>,
+    v2_base_url: String,
+    v1_fallback: bool,
+    defaults: QuoteDefaults,
+    validate_sources: Option<Duration>,
+    hedge_after: Option<Duration>,
+    rate_limit: Option<(u32, Duration)>,
+    retry_policy: Arc<dyn RetryPolicy>,
+    token_screener: Arc<dyn TokenScreener>,
+    quote_validators: Vec<Arc<dyn QuoteValidator>>,
+    policy: Option<Arc<TradingPolicy>>,
+    log_redaction: LogRedaction,
+    log_bodies: bool,
+    max_response_size: Option<usize>,
+    pool_max_idle_per_host: usize,
+    pool_idle_timeout: Option<Duration>,
+    tcp_keepalive: Option<Duration>,
+    timeout: Option<Duration>,
+    endpoint_timeouts: HashMap<Endpoint, Duration>,
+    tls: TlsSettings,
+    monthly_request_quota: Option<u64>,
+    request_log: Option<Arc<JsonlRequestLog>>,
+}
+
+impl ZeroXClientBuilder {
+    pub fn retry_policy(mut self, retry_policy: impl RetryPolicy + 'static) -> Self {
+        self.retry_policy = Arc::new(retry_policy);
+        self
+    }
+
+    /// Screens the tokens and taker of every quote request before it is sent.
+    pub fn token_screener(mut self, token_screener: impl TokenScreener + 'static) -> Self {
+        self.token_screener = Arc::new(token_screener);
+        self
+    }
+
+    /// Adds a check run on every v1 quote and price before it is returned, after those added
+    /// before it. A failing check returns [`ZeroXClientError::QuoteRejected`]. See
+    /// [`validation`] for the methods validators cover.
+    pub fn quote_validator(mut self, validator: impl QuoteValidator + 'static) -> Self {
+        self.quote_validators.push(Arc::new(validator));
+        self
+    }
+
+    /// Enforces trading limits on every quote and price, v1, v2 and gasless: the chain, tokens
+    /// and slippage before the request is sent, and the quote's tokens, implied slippage and
+    /// notional once it arrives. A violation returns
+    /// [`ZeroXClientError::PolicyViolation`]. Share the policy with a
+    /// [`SwapExecutor`](crate::SwapExecutor) to count executed swaps towards the daily limit.
+    pub fn policy(mut self, policy: Arc<TradingPolicy>) -> Self {
+        self.policy = Some(policy);
+        self
+    }
+
+    /// Overrides the base URL derived from the chain id, e.g. to route through a gateway.
+    pub fn base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = Some(base_url.into());
+        self
+    }
+
+    /// Base URL tried when a GET to the primary base URL fails with a retryable error, and
+    /// used as the hedge target.
+    pub fn fallback_base_url(mut self, fallback_base_url: impl Into<String>) -> Self {
+        self.fallback_base_url = Some(fallback_base_url.into());
+        self
+    }
+
+    /// Base URL for v2 endpoints, which serve every chain from one host. Defaults to
+    /// [`v2::V2_BASE_URL`].
+    pub fn v2_base_url(mut self, v2_base_url: impl Into<String>) -> Self {
+        self.v2_base_url = v2_base_url.into();
+        self
+    }
+
+    /// Makes [`ZeroXClient::get_quote_versioned`] retry on the chain's v1 endpoint when v2
+    /// rejects the request as unknown or unsupported. Off by default.
+    pub fn v1_fallback(mut self, enabled: bool) -> Self {
+        self.v1_fallback = enabled;
+        self
+    }
+
+    /// Values merged into every swap quote and price request that leaves them unset.
+    pub fn default_params(mut self, defaults: QuoteDefaults) -> Self {
+        self.defaults = defaults;
+        self
+    }
+
+    /// Rejects quotes that include or exclude a liquidity source unknown on the chain. The
+    /// sources list is fetched on first use and then refreshed every `refresh_every` in the
+    /// background, so quotes never wait on a refresh.
+    pub fn validate_sources(mut self, refresh_every: Duration) -> Self {
+        self.validate_sources = Some(refresh_every);
+        self
+    }
+
+    /// Sends a second request if the first has not completed after `hedge_after`, taking
+    /// whichever succeeds first. The hedge goes to the fallback base URL if one is set,
+    /// otherwise it duplicates the request against the primary.
+    pub fn hedge_after(mut self, hedge_after: Duration) -> Self {
+        self.hedge_after = Some(hedge_after);
+        self
+    }
+
+    /// Limits outgoing requests to `requests` per `per`, allowing bursts of up to `requests`.
+    /// Every HTTP attempt counts, including retries and hedges.
+    pub fn rate_limit(mut self, requests: u32, per: Duration) -> Self {
+        self.rate_limit = Some((requests, per));
+        self
+    }
+
+    /// Requests the plan allows per month, so [`ZeroXClient::usage_stats`] can report the
+    /// share used and the share projected by month end.
+    pub fn monthly_request_quota(mut self, requests: u64) -> Self {
+        self.monthly_request_quota = Some(requests);
+        self
+    }
+
+    /// Controls which secrets are masked in the per-request `zerox_client::request` events.
+    pub fn log_redaction(mut self, log_redaction: LogRedaction) -> Self {
+        self.log_redaction = log_redaction;
+        self
+    }
+
+    /// Also writes every request to a JSON lines file, independently of `tracing`.
+    pub fn request_log(mut self, request_log: JsonlRequestLog) -> Self {
+        self.request_log = Some(Arc::new(request_log));
+        self
+    }
+
+    /// Logs full request URLs and raw response bodies at `debug` level under the
+    /// `zerox_client::body` target. Meant for troubleshooting; off by default.
+    pub fn log_bodies(mut self, enabled: bool) -> Self {
+        self.log_bodies = enabled;
+        self
+    }
+
+    /// Maximum response body size in bytes, `None` for no limit. Larger responses fail with
+    /// `ResponseTooLarge`. Defaults to 8 MiB.
+    pub fn max_response_size(mut self, max_response_size: Option<usize>) -> Self {
+        self.max_response_size = max_response_size;
+        self
+    }
+
+    /// Maximum idle connections kept per host. Unlimited by default.
+    pub fn pool_max_idle_per_host(mut self, max: usize) -> Self {
+        self.pool_max_idle_per_host = max;
+        self
+    }
+
+    /// How long idle pooled connections are kept, `None` to keep them indefinitely. Defaults
+    /// to 90 seconds.
+    pub fn pool_idle_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.pool_idle_timeout = timeout;
+        self
+    }
+
+    /// Enables TCP keepalive probes at `interval` so idle pooled connections aren't silently
+    /// dropped by NATs and load balancers between bursts. Disabled by default.
+    pub fn tcp_keepalive(mut self, interval: Option<Duration>) -> Self {
+        self.tcp_keepalive = interval;
+        self
+    }
+
+    /// Overall timeout for each HTTP attempt, from connecting until the body is read. None by
+    /// default.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Timeout for each HTTP attempt to `endpoint`, replacing [`ZeroXClientBuilder::timeout`]
+    /// for those requests.
+    pub fn endpoint_timeout(mut self, endpoint: Endpoint, timeout: Duration) -> Self {
+        self.endpoint_timeouts.insert(endpoint, timeout);
+        self
+    }
+
+    /// Trusts the certificates in `pem`, one or more PEM blocks, e.g. the CA of an internal
+    /// gateway. Can be called repeatedly.
+    pub fn add_root_certificates(mut self, pem: impl Into<Vec<u8>>) -> Self {
+        self.tls.root_certificates.push(pem.into());
+        self
+    }
+
+    /// Whether to trust the bundled Mozilla root certificates. Disable to trust only those
+    /// added with [`add_root_certificates`](Self::add_root_certificates). Enabled by default.
+    pub fn built_in_root_certificates(mut self, enabled: bool) -> Self {
+        self.tls.built_in_roots = enabled;
+        self
+    }
+
+    /// Only accepts servers whose certificate chain, after normal validation, contains a
+    /// certificate with this SHA-256 fingerprint of its DER encoding. Call repeatedly to
+    /// allow several, e.g. the current and next certificate during a rotation.
+    #[cfg(feature = "tls-pinning")]
+    pub fn pin_certificate_sha256(mut self, fingerprint: [u8; 32]) -> Self {
+        self.tls.pins.push(fingerprint);
+        self
+    }
+
+    pub fn build(self) -> Result<ZeroXClient, ZeroXClientError> {
+        let base_url = match self.base_url {
+            Some(base_url) => base_url,
+            None => default_base_url(self.chain_id)
+                .ok_or(ZeroXClientError::InvalidChainId(self.chain_id))?,
+        };
+
+        let resolver = Arc::new(TimingResolver::default());
+        let mut http = reqwest::Client::builder()
+            .dns_resolver(resolver.clone())
+            .pool_max_idle_per_host(self.pool_max_idle_per_host)
+            .pool_idle_timeout(self.pool_idle_timeout)
+            .tcp_keepalive(self.tcp_keepalive);
+        if let Some(timeout) = self.timeout {
+            http = http.timeout(timeout);
+        }
+        let handshakes = HandshakeTimings::default();
+        let http = self.tls.apply(http, handshakes.clone())?.build()?;
+
+        Ok(ZeroXClient {
+            chain_id: self.chain_id,
+            live: Arc::new(RwLock::new(LiveConfig::new(ClientConfig {
+                api_key: self.api_key,
+                base_url,
+                rate_limit: self.rate_limit,
+            }))),
+            fallback_base_url: self.fallback_base_url,
+            v2_base_url: self.v2_base_url,
+            v1_fallback: self.v1_fallback,
+            defaults: Arc::new(self.defaults),
+            sources_cache: self
+                .validate_sources
+                .map(|refresh_every| Arc::new(SourcesCache::new(refresh_every))),
+            hedge_after: self.hedge_after,
+            endpoint_timeouts: Arc::new(self.endpoint_timeouts),
+            http,
+            resolver,
+            handshakes,
+            retry_policy: self.retry_policy,
+            token_screener: self.token_screener,
+            quote_validators: self.quote_validators.into(),
+            policy: self.policy,
+            log_redaction: self.log_redaction,
+            log_bodies: self.log_bodies,
+            max_response_size: self.max_response_size,
+            server_rate_limit: Arc::default(),
+            usage: Arc::new(UsageTracker::new(self.monthly_request_quota)),
+            request_log: self.request_log,
+            priority: RequestPriority::Normal,
+        })
+    }
 }
 
 impl ZeroXClient {
     pub fn new(chain_id: u64, api_key: String) -> Result<ZeroXClient, ZeroXClientError> {
-        let base_url_hashmap: HashMap<u64, String> = vec![
-            (1, "https://api.0x.org".to_string()),
-            (42161, "https://arbitrum.api.0x.org".to_string()),
-            (43114, "https://avalanche.api.0x.org".to_string()),
-            (250, "https://fantom.api.0x.org".to_string()),
-            (137, "https://polygon.api.0x.org".to_string()),
-            (42220, "https://celo.api.0x.org".to_string()),
-            (56, "https://bsc.api.0x.org".to_string()),
-            (10, "https://optimisim.api.0x.org".to_string()),
-            (11155111, "https://sepolia.api.0x.org".to_string()),
-        ]
-        .into_iter()
-        .collect();
-
-        let base_url = base_url_hashmap
-            .get(&chain_id)
-            .ok_or(ZeroXClientError::InvalidChainId(chain_id))?
-            .clone();
-
-        Ok(ZeroXClient { base_url, api_key })
+        ZeroXClient::builder(chain_id, api_key).build()
+    }
+
+    pub fn builder(chain_id: u64, api_key: String) -> ZeroXClientBuilder {
+        ZeroXClientBuilder {
+            chain_id,
+            api_key,
+            base_url: None,
+            fallback_base_url: None,
+            v2_base_url: String::from(v2::V2_BASE_URL),
+            v1_fallback: false,
+            defaults: QuoteDefaults::default(),
+            validate_sources: None,
+            hedge_after: None,
+            rate_limit: None,
+            retry_policy: Arc::new(ExponentialBackoff::default()),
+            token_screener: Arc::new(NoScreening),
+            quote_validators: Vec::new(),
+            policy: None,
+            log_redaction: LogRedaction::default(),
+            log_bodies: false,
+            max_response_size: Some(8 * 1024 * 1024),
+            pool_max_idle_per_host: usize::MAX,
+            pool_idle_timeout: Some(Duration::from_secs(90)),
+            tcp_keepalive: None,
+            timeout: None,
+            endpoint_timeouts: HashMap::new(),
+            tls: TlsSettings::default(),
+            monthly_request_quota: None,
+            request_log: None,
+        }
+    }
+
+    pub fn chain_id(&self) -> u64 {
+        self.chain_id
+    }
+
+    /// The chains a client can be built for without a custom base URL.
+    pub fn supported_chains() -> &'static [SupportedChain] {
+        SUPPORTED_CHAINS
+    }
+
+    pub fn is_chain_supported(chain_id: u64) -> bool {
+        default_base_url(chain_id).is_some()
+    }
+
+    pub fn base_url(&self) -> String {
+        self.live.read().unwrap().config.base_url.clone()
+    }
+
+    pub fn config(&self) -> ClientConfig {
+        self.live.read().unwrap().config.clone()
+    }
+
+    /// Replaces the API key, base URL and rate limit for this client and all its clones.
+    /// Requests already in flight finish with the previous settings. The rate limiter keeps
+    /// its state unless the limit itself changes.
+    pub fn reload(&self, config: ClientConfig) {
+        let mut live = self.live.write().unwrap();
+        if live.config.rate_limit == config.rate_limit {
+            live.config = config;
+        } else {
+            *live = LiveConfig::new(config);
+        }
+    }
+
+    /// Rotates the API key, keeping the rest of the configuration.
+    pub fn set_api_key(&self, api_key: impl Into<String>) {
+        self.live.write().unwrap().config.api_key = api_key.into();
+    }
+
+    fn api_key(&self) -> String {
+        self.live.read().unwrap().config.api_key.clone()
+    }
+
+    fn rate_limiter(&self) -> Option<Arc<RateLimiter>> {
+        self.live.read().unwrap().rate_limiter.clone()
+    }
+
+    /// A clone whose requests wait for the rate limiter with `priority`, e.g. `High` for
+    /// quotes on the execution path and `Low` for background price refreshes. When the limiter
+    /// is saturated, higher-priority requests are sent first.
+    pub fn prioritized(&self, priority: RequestPriority) -> ZeroXClient {
+        ZeroXClient {
+            priority,
+            ..self.clone()
+        }
+    }
+
+    /// The rate limit last reported by the API and the occupancy of the local limiter, so
+    /// callers can pace requests before hitting 429s. Shared by all clones of the client.
+    pub fn rate_limit_status(&self) -> RateLimitStatus {
+        RateLimitStatus {
+            server: *self.server_rate_limit.lock().unwrap(),
+            local: self.rate_limiter().map(|limiter| limiter.status()),
+        }
+    }
+
+    /// Resolves and connects to the configured base URLs so that the first request reuses a
+    /// pooled connection instead of paying for DNS and the TLS handshake.
+    pub async fn warm_up(&self) -> Result<(), ZeroXClientError> {
+        let base_url = self.base_url();
+        let primary = self.warm_up_base_url(&base_url);
+
+        match &self.fallback_base_url {
+            Some(fallback_base_url) => {
+                let (primary, fallback) =
+                    tokio::join!(primary, self.warm_up_base_url(fallback_base_url));
+                primary.and(fallback)
+            }
+            None => primary.await,
+        }
+    }
+
+    async fn warm_up_base_url(&self, base_url: &str) -> Result<(), ZeroXClientError> {
+        let started = Instant::now();
+        let resp = self.http.head(base_url).send().await?;
+
+        debug!(
+            "warmed up {} in {:?} ({})",
+            base_url,
+            started.elapsed(),
+            resp.status()
+        );
+
+        Ok(())
     }
 
     pub async fn get_quote(
         &self,
         params: ZeroXQuoteParams,
     ) -> Result<ZeroXQuoteResponse, ZeroXClientError> {
-        let url = format!("{}/swap/v1/quote", self.base_url);
+        Ok(self.get_quote_with_metadata(params).await?.data)
+    }
+
+    pub async fn get_quote_with_metadata(
+        &self,
+        params: ZeroXQuoteParams,
+    ) -> Result<WithMetadata<ZeroXQuoteResponse>, ZeroXClientError> {
+        self.screen(&params).await?;
+        let quote = self
+            .get("/swap/v1/quote", &self.quote_query(params.clone()))
+            .await?;
+        self.validate_quote(&params, &quote.data).await?;
+        Ok(quote)
+    }
+
+    /// Fetches a quote as untyped JSON, including fields `ZeroXQuoteResponse` does not model.
+    pub async fn get_quote_raw(&self, params: ZeroXQuoteParams) -> Result<Value, ZeroXClientError> {
+        self.screen(&params).await?;
+        let quote = self
+            .get::<Value>("/swap/v1/quote", &self.quote_query(params.clone()))
+            .await?
+            .data;
+        self.validate_unparsed(&params, || serde_json::from_value(quote.clone()))
+            .await?;
+        Ok(quote)
+    }
+
+    /// Fetches an indicative price. Takes the same parameters as a quote and returns the same
+    /// shape, without the transaction data.
+    pub async fn get_price(
+        &self,
+        params: ZeroXQuoteParams,
+    ) -> Result<ZeroXQuoteResponse, ZeroXClientError> {
+        Ok(self.get_price_with_metadata(params).await?.data)
+    }
+
+    pub async fn get_price_with_metadata(
+        &self,
+        params: ZeroXQuoteParams,
+    ) -> Result<WithMetadata<ZeroXQuoteResponse>, ZeroXClientError> {
+        self.screen(&params).await?;
+        let price = self
+            .get("/swap/v1/price", &self.quote_query(params.clone()))
+            .await?;
+        self.validate_quote(&params, &price.data).await?;
+        Ok(price)
+    }
+
+    /// The v1 query for `params`, with the client's defaults filled in.
+    fn quote_query(&self, params: ZeroXQuoteParams) -> HashMap<&'static str, String> {
+        quote_query(self.defaults.apply(params, self.chain_id))
+    }
+
+    async fn screen(&self, params: &ZeroXQuoteParams) -> Result<(), ZeroXClientError> {
+        self.screen_request(ScreeningRequest {
+            chain_id: self.chain_id,
+            sell_token: &params.sell_token,
+            buy_token: &params.buy_token,
+            taker: params.taker_address.as_deref(),
+        })
+        .await?;
+        if let Some(policy) = &self.policy {
+            policy
+                .check_v1_slippage(&self.defaults.apply(params.clone(), self.chain_id))
+                .map_err(ZeroXClientError::PolicyViolation)?;
+        }
+        self.validate_sources(params).await
+    }
+
+    async fn screen_request(&self, request: ScreeningRequest<'_>) -> Result<(), ZeroXClientError> {
+        if let Some(policy) = &self.policy {
+            let tokens = [request.sell_token, request.buy_token].map(String::from);
+            policy
+                .check_tokens(request.chain_id, &tokens)
+                .map_err(ZeroXClientError::PolicyViolation)?;
+        }
+        self.token_screener
+            .screen(request)
+            .await
+            .map_err(ZeroXClientError::ScreeningRejected)
+    }
 
-        let mut headers = reqwest::header::HeaderMap::new();
-        let value = match HeaderValue::from_str(&self.api_key) {
+    /// [`validate_quote`](Self::validate_quote) for a quote the caller gets unparsed, parsing
+    /// it only if there is a policy or validator to run.
+    async fn validate_unparsed(
+        &self,
+        params: &ZeroXQuoteParams,
+        parse: impl FnOnce() -> Result<ZeroXQuoteResponse, serde_json::Error>,
+    ) -> Result<(), ZeroXClientError> {
+        if self.policy.is_none() && self.quote_validators.is_empty() {
+            return Ok(());
+        }
+        self.validate_quote(params, &parse()?).await
+    }
+
+    async fn validate_quote(
+        &self,
+        params: &ZeroXQuoteParams,
+        quote: &ZeroXQuoteResponse,
+    ) -> Result<(), ZeroXClientError> {
+        if let Some(policy) = &self.policy {
+            policy
+                .check_quote(self.chain_id, quote)
+                .map_err(ZeroXClientError::PolicyViolation)?;
+        }
+        validation::validate_all(&self.quote_validators, params, quote)
+            .await
+            .map_err(ZeroXClientError::QuoteRejected)
+    }
+
+    async fn get<T: FromBody>(
+        &self,
+        path: &str,
+        query: &HashMap<&str, String>,
+    ) -> Result<WithMetadata<T>, ZeroXClientError> {
+        self.request(&ApiRequest {
+            method: Method::GET,
+            version: ApiVersion::V1,
+            path,
+            query,
+            body: None,
+        })
+        .await
+    }
+
+    async fn get_v2<T: FromBody>(
+        &self,
+        path: &str,
+        query: &HashMap<&str, String>,
+    ) -> Result<WithMetadata<T>, ZeroXClientError> {
+        self.request(&ApiRequest {
+            method: Method::GET,
+            version: ApiVersion::V2,
+            path,
+            query,
+            body: None,
+        })
+        .await
+    }
+
+    async fn post<T: FromBody>(
+        &self,
+        path: &str,
+        body: &Value,
+    ) -> Result<WithMetadata<T>, ZeroXClientError> {
+        self.request(&ApiRequest {
+            method: Method::POST,
+            version: ApiVersion::V1,
+            path,
+            query: &HashMap::new(),
+            body: Some(body),
+        })
+        .await
+    }
+
+    async fn post_v2<T: FromBody>(
+        &self,
+        path: &str,
+        body: &Value,
+    ) -> Result<WithMetadata<T>, ZeroXClientError> {
+        self.request(&ApiRequest {
+            method: Method::POST,
+            version: ApiVersion::V2,
+            path,
+            query: &HashMap::new(),
+            body: Some(body),
+        })
+        .await
+    }
+
+    async fn request<T: FromBody>(
+        &self,
+        request: &ApiRequest<'_>,
+    ) -> Result<WithMetadata<T>, ZeroXClientError> {
+        let mut attempt = 0;
+        loop {
+            attempt += 1;
+            match self.request_once(request).await {
+                Ok(mut response) => {
+                    response.metadata.attempts = attempt;
+                    return Ok(response);
+                }
+                Err(err) => {
+                    let ctx = RetryContext {
+                        method: &request.method,
+                        path: request.path,
+                        attempt,
+                    };
+                    match self.retry_policy.retry_after(ctx, &err) {
+                        Some(delay) => {
+                            debug!("retrying {} after {:?}: {}", request.path, delay, err);
+                            tokio::time::sleep(delay).await;
+                        }
+                        None => return Err(err),
+                    }
+                }
+            }
+        }
+    }
+
+    async fn request_once<T: FromBody>(
+        &self,
+        request: &ApiRequest<'_>,
+    ) -> Result<WithMetadata<T>, ZeroXClientError> {
+        // v2 is served from a single host, so there is nothing to fall back or hedge to
+        let (base_url, fallback_base_url) = match request.version {
+            ApiVersion::V1 => (self.base_url(), self.fallback_base_url.as_ref()),
+            ApiVersion::V2 => (self.v2_base_url.clone(), None),
+        };
+        let hedge_base_url = fallback_base_url.unwrap_or(&base_url);
+
+        let primary = self.send(request, &base_url);
+        tokio::pin!(primary);
+
+        // only idempotent requests are hedged
+        let Some(hedge_after) = self
+            .hedge_after
+            .filter(|_| request.method == Method::GET && request.version == ApiVersion::V1)
+        else {
+            return match (primary.await, fallback_base_url) {
+                (Err(err), Some(fallback_base_url)) if falls_back(request, &err) => {
+                    debug!("primary request failed, trying fallback: {}", err);
+                    self.send(request, fallback_base_url).await
+                }
+                (res, _) => res,
+            };
+        };
+
+        tokio::select! {
+            res = &mut primary => {
+                return match res {
+                    Err(err) if fallback_base_url.is_some() && falls_back(request, &err) => {
+                        debug!("primary request failed, trying fallback: {}", err);
+                        self.send(request, hedge_base_url).await
+                    }
+                    res => res,
+                };
+            }
+            _ = tokio::time::sleep(hedge_after) => {}
+        }
+
+        debug!(
+            "hedging request to {} after {:?}",
+            hedge_base_url, hedge_after
+        );
+
+        let hedge = self.send(request, hedge_base_url);
+        tokio::pin!(hedge);
+
+        tokio::select! {
+            res = &mut primary => match res {
+                Ok(v) => Ok(v),
+                Err(_) => hedge.await,
+            },
+            res = &mut hedge => match res {
+                Ok(v) => Ok(v),
+                Err(_) => primary.await,
+            },
+        }
+    }
+
+    async fn send<T: FromBody>(
+        &self,
+        request: &ApiRequest<'_>,
+        base_url: &str,
+    ) -> Result<WithMetadata<T>, ZeroXClientError> {
+        let api_key = self.api_key();
+        let started = Instant::now();
+        let result = self.send_request(request, base_url, &api_key).await;
+        self.usage.record(&api_key, request.path, result.is_ok());
+
+        let log = RequestLog {
+            chain_id: self.chain_id,
+            base_url,
+            path: request.path,
+            query: request.query,
+            api_key: &api_key,
+            redaction: self.log_redaction,
+        };
+        log.emit(&result, started.elapsed());
+        if let Some(request_log) = &self.request_log {
+            request_log.write(&request.method, &log, &result, started.elapsed());
+        }
+
+        result
+    }
+
+    async fn send_request<T: FromBody>(
+        &self,
+        request: &ApiRequest<'_>,
+        base_url: &str,
+        api_key: &str,
+    ) -> Result<WithMetadata<T>, ZeroXClientError> {
+        let (resp, started) = self.open(request, base_url, api_key).await?;
+
+        let headers = started.elapsed();
+        let host = resp.url().host_str();
+        let dns = host.and_then(|host| self.resolver.dns_time(host, started));
+        let connect = host.and_then(|host| {
+            self.handshakes
+                .connect_time(host, started + dns.unwrap_or_default())
+        });
+        let ttfb = headers.saturating_sub(dns.unwrap_or_default() + connect.unwrap_or_default());
+        let status = resp.status();
+
+        let body = read_body(resp, self.max_response_size).await?;
+
+        let total = started.elapsed();
+
+        if self.log_bodies {
+            logging::log_response_body(status, &body);
+        }
+
+        let response = T::from_body(body)?;
+
+        Ok(WithMetadata {
+            data: response,
+            metadata: ResponseMetadata {
+                base_url: base_url.to_string(),
+                status,
+                attempts: 1,
+                timings: RequestTimings {
+                    dns,
+                    connect,
+                    ttfb,
+                    total,
+                },
+            },
+        })
+    }
+
+    /// Sends a single request and returns the successful response with its body unread,
+    /// along with when it was sent.
+    async fn open(
+        &self,
+        request: &ApiRequest<'_>,
+        base_url: &str,
+        api_key: &str,
+    ) -> Result<(reqwest::Response, Instant), ZeroXClientError> {
+        let ApiRequest {
+            method,
+            version,
+            path,
+            query,
+            body,
+        } = request;
+
+        let url = format!("{}{}", base_url, path);
+
+        let mut headers = HeaderMap::new();
+        let value = match HeaderValue::from_str(api_key) {
             Ok(v) => v,
             Err(err) => {
                 return Err(ZeroXClientError::ZeroXInvalidHeaderValue(err));
@@ -84,71 +1149,240 @@ impl ZeroXClient {
         };
         headers.append("0x-api-key", value);
         headers.append("Content-Type", HeaderValue::from_static("application/json"));
-
-        let mut map = HashMap::new();
-        map.insert("sellToken", params.sell_token);
-        map.insert("buyToken", params.buy_token);
-        map.insert("sellAmount", params.sell_amount);
-
-        if let Some(taker_address) = params.taker_address {
-            map.insert("takerAddress", taker_address);
+        if *version == ApiVersion::V2 {
+            headers.append("0x-version", HeaderValue::from_static("v2"));
         }
 
-        if let Some(fee_recipient) = params.fee_recipient {
-            map.insert("feeRecipient", fee_recipient);
+        if let Some(rate_limiter) = self.rate_limiter() {
+            rate_limiter.acquire(self.priority).await;
         }
 
-        if let Some(buy_token_percentage_fee) = params.buy_token_percentage_fee {
-            map.insert("buyTokenPercentageFee", buy_token_percentage_fee);
-        }
+        let started = Instant::now();
 
-        if let Some(slippage_percentage) = params.slippage_percentage {
-            map.insert("slippagePercentage", slippage_percentage);
+        let mut request = self
+            .http
+            .request(method.clone(), &url)
+            .query(query)
+            .headers(headers);
+        if let Some(body) = body {
+            request = request.json(body);
         }
-
-        if let Some(excluded_sources) = params.excluded_sources {
-            map.insert("excludedSources", excluded_sources.join(","));
+        if let Some(timeout) = Endpoint::of(path).and_then(|e| self.endpoint_timeouts.get(&e)) {
+            request = request.timeout(*timeout);
+        }
+        let request = request.build()?;
+        if self.log_bodies {
+            logging::log_request_url(
+                request.url(),
+                self.log_redaction.api_key(api_key),
+                self.log_redaction,
+            );
         }
 
-        if let Some(included_sources) = params.included_sources {
-            map.insert("includedSources", included_sources.join(","));
+        let resp = self.http.execute(request).await?;
+
+        let status = resp.status();
+        if let Some(limit) = ServerRateLimit::from_response(status, resp.headers(), Instant::now())
+        {
+            *self.server_rate_limit.lock().unwrap() = Some(limit);
+        }
+        if status.as_u16() != 200 {
+            if self.log_bodies {
+                let body = read_body(resp, self.max_response_size).await;
+                logging::log_response_body(status, &body.unwrap_or_default());
+            }
+            return Err(status_error(status));
         }
 
-        if let Some(skip_validation) = params.skip_validation {
-            map.insert("skipValidation", skip_validation);
+        Ok((resp, started))
+    }
+}
+
+/// Reads the response body, failing as soon as it exceeds `limit` bytes.
+async fn read_body(
+    mut resp: reqwest::Response,
+    limit: Option<usize>,
+) -> Result<Vec<u8>, ZeroXClientError> {
+    let Some(limit) = limit else {
+        return Ok(resp.bytes().await?.to_vec());
+    };
+
+    let too_large = || ZeroXClientError::ResponseTooLarge { limit };
+    if resp.content_length().is_some_and(|len| len > limit as u64) {
+        return Err(too_large());
+    }
+
+    let mut body = Vec::new();
+    while let Some(chunk) = resp.chunk().await? {
+        if body.len() + chunk.len() > limit {
+            return Err(too_large());
         }
+        body.extend_from_slice(&chunk);
+    }
 
-        let client = reqwest::Client::new();
+    Ok(body)
+}
 
-        let resp = client.get(&url).query(&map).headers(headers).send().await?;
+/// How a response type is built from the raw body.
+trait FromBody: Sized {
+    fn from_body(body: Vec<u8>) -> Result<Self, ZeroXClientError>;
+}
 
-        debug!("{:#?}", resp);
+impl<T: DeserializeOwned> FromBody for T {
+    fn from_body(mut body: Vec<u8>) -> Result<T, ZeroXClientError> {
+        Ok(decode(&mut body)?)
+    }
+}
 
-        if resp.status().as_u16() != 200 {
-            return Err(ZeroXClientError::ZeroXInvalidResponseStatusCode(
-                resp.status(),
-            ));
-        }
+/// Parses a response body. With the `simd-json` feature the body is parsed in place, so it
+/// is left garbled afterwards.
+#[cfg(not(feature = "simd-json"))]
+fn decode<T: DeserializeOwned>(body: &mut [u8]) -> Result<T, serde_json::Error> {
+    serde_json::from_slice(body)
+}
+
+#[cfg(feature = "simd-json")]
+fn decode<T: DeserializeOwned>(body: &mut [u8]) -> Result<T, serde_json::Error> {
+    simd_json::serde::from_slice(body).map_err(serde::de::Error::custom)
+}
+
+/// A chain served by a v1 Swap API endpoint.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SupportedChain {
+    pub chain_id: u64,
+    pub name: &'static str,
+    pub base_url: &'static str,
+}
+
+const SUPPORTED_CHAINS: &[SupportedChain] = &[
+    SupportedChain {
+        chain_id: 1,
+        name: "Ethereum",
+        base_url: "https://api.0x.org",
+    },
+    SupportedChain {
+        chain_id: 42161,
+        name: "Arbitrum",
+        base_url: "https://arbitrum.api.0x.org",
+    },
+    SupportedChain {
+        chain_id: 43114,
+        name: "Avalanche",
+        base_url: "https://avalanche.api.0x.org",
+    },
+    SupportedChain {
+        chain_id: 250,
+        name: "Fantom",
+        base_url: "https://fantom.api.0x.org",
+    },
+    SupportedChain {
+        chain_id: 137,
+        name: "Polygon",
+        base_url: "https://polygon.api.0x.org",
+    },
+    SupportedChain {
+        chain_id: 42220,
+        name: "Celo",
+        base_url: "https://celo.api.0x.org",
+    },
+    SupportedChain {
+        chain_id: 56,
+        name: "BNB Chain",
+        base_url: "https://bsc.api.0x.org",
+    },
+    SupportedChain {
+        chain_id: 10,
+        name: "Optimism",
+        base_url: "https://optimisim.api.0x.org",
+    },
+    SupportedChain {
+        chain_id: 11155111,
+        name: "Sepolia",
+        base_url: "https://sepolia.api.0x.org",
+    },
+];
+
+fn default_base_url(chain_id: u64) -> Option<String> {
+    SUPPORTED_CHAINS
+        .iter()
+        .find(|chain| chain.chain_id == chain_id)
+        .map(|chain| chain.base_url.to_string())
+}
+
+fn quote_query(params: ZeroXQuoteParams) -> HashMap<&'static str, String> {
+    let mut map = HashMap::new();
+    map.insert("sellToken", params.sell_token);
+    map.insert("buyToken", params.buy_token);
+    map.insert("sellAmount", params.sell_amount);
+
+    if let Some(taker_address) = params.taker_address {
+        map.insert("takerAddress", taker_address);
+    }
+
+    if let Some(fee_recipient) = params.fee_recipient {
+        map.insert("feeRecipient", fee_recipient);
+    }
+
+    if let Some(buy_token_percentage_fee) = params.buy_token_percentage_fee {
+        map.insert(
+            "buyTokenPercentageFee",
+            buy_token_percentage_fee.fraction().to_string(),
+        );
+    }
 
-        let quote_response: Value = resp.json().await?;
+    if let Some(slippage_percentage) = params.slippage_percentage {
+        map.insert(
+            "slippagePercentage",
+            slippage_percentage.fraction().to_string(),
+        );
+    }
 
-        debug!("{:#?}", quote_response);
+    if let Some(excluded_sources) = params.excluded_sources {
+        map.insert("excludedSources", excluded_sources.join(","));
+    }
 
-        let quote_response = serde_json::from_value::<ZeroXQuoteResponse>(quote_response)?;
+    if let Some(included_sources) = params.included_sources {
+        map.insert("includedSources", included_sources.join(","));
+    }
+
+    if let Some(skip_validation) = params.skip_validation {
+        map.insert("skipValidation", skip_validation.to_string());
+    }
+
+    if let Some(affiliate_address) = params.affiliate_address {
+        map.insert("affiliateAddress", affiliate_address);
+    }
+
+    if let Some(include_price_comparisons) = params.include_price_comparisons {
+        map.insert(
+            "includePriceComparisons",
+            include_price_comparisons.to_string(),
+        );
+    }
 
-        Ok(quote_response)
+    if let Some(should_sell_entire_balance) = params.should_sell_entire_balance {
+        map.insert(
+            "shouldSellEntireBalance",
+            should_sell_entire_balance.to_string(),
+        );
     }
+
+    map
 }
 
 #[derive(Serialize, Deserialize, Debug)]
+#[cfg_attr(feature = "proptest", derive(proptest_derive::Arbitrary))]
 #[serde(rename_all = "camelCase")]
+#[non_exhaustive]
 pub struct FillData {
     pub token_address_path: Option<Vec<String>>,
     pub router: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
+#[cfg_attr(feature = "proptest", derive(proptest_derive::Arbitrary))]
 #[serde(rename_all = "camelCase")]
+#[non_exhaustive]
 pub struct Order {
     pub maker_token: Option<String>,
     pub taker_token: Option<String>,
@@ -162,20 +1396,41 @@ pub struct Order {
 }
 
 #[derive(Serialize, Deserialize, Debug)]
+#[cfg_attr(feature = "proptest", derive(proptest_derive::Arbitrary))]
 #[serde(rename_all = "camelCase")]
+#[non_exhaustive]
 pub struct Source {
     pub name: Option<String>,
     pub proportion: Option<String>,
 }
 
+/// The quote a single liquidity source would have given, requested with
+/// `include_price_comparisons`.
 #[derive(Serialize, Deserialize, Debug)]
+#[cfg_attr(feature = "proptest", derive(proptest_derive::Arbitrary))]
 #[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct PriceComparison {
+    pub name: Option<String>,
+    pub price: Option<String>,
+    pub gas: Option<String>,
+    pub savings_in_eth: Option<String>,
+    pub buy_amount: Option<String>,
+    pub sell_amount: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[cfg_attr(feature = "proptest", derive(proptest_derive::Arbitrary))]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
 pub struct Fees {
     pub zero_ex_fee: Option<ZeroExFee>,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
+#[cfg_attr(feature = "proptest", derive(proptest_derive::Arbitrary))]
 #[serde(rename_all = "camelCase")]
+#[non_exhaustive]
 pub struct ZeroExFee {
     pub billing_type: Option<String>,
     pub fee_amount: Option<String>,
@@ -184,7 +1439,9 @@ pub struct ZeroExFee {
 }
 
 #[derive(Serialize, Deserialize, Debug)]
+#[cfg_attr(feature = "proptest", derive(proptest_derive::Arbitrary))]
 #[serde(rename_all = "camelCase")]
+#[non_exhaustive]
 pub struct ZeroXQuoteResponse {
     pub chain_id: Option<i32>,
     pub price: Option<String>,
@@ -211,8 +1468,170 @@ pub struct ZeroXQuoteResponse {
     pub gross_price: Option<String>,
     pub gross_buy_amount: Option<String>,
     pub gross_sell_amount: Option<String>,
+    pub price_comparisons: Option<Vec<PriceComparison>>,
+}
+
+/// Error returned by the [`ZeroXQuoteResponse`] accessors.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum QuoteFieldError {
+    #[error("Missing '{0}' field")]
+    Missing(&'static str),
+
+    #[error("Invalid '{field}' field: {value:?}")]
+    Invalid { field: &'static str, value: String },
+}
+
+/// Reads an optional flag given as a bool or as the string `"true"` or `"false"`.
+fn bool_or_string<'de, D: serde::Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<bool>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Repr {
+        Bool(bool),
+        Text(String),
+    }
+
+    match Option::<Repr>::deserialize(deserializer)? {
+        None => Ok(None),
+        Some(Repr::Bool(value)) => Ok(Some(value)),
+        Some(Repr::Text(text)) => text.parse().map(Some).map_err(serde::de::Error::custom),
+    }
+}
+
+fn required<'a>(
+    value: &'a Option<String>,
+    field: &'static str,
+) -> Result<&'a str, QuoteFieldError> {
+    value.as_deref().ok_or(QuoteFieldError::Missing(field))
+}
+
+fn parse_field<T: std::str::FromStr>(
+    value: &str,
+    field: &'static str,
+) -> Result<T, QuoteFieldError> {
+    value.parse().map_err(|_| QuoteFieldError::Invalid {
+        field,
+        value: value.to_string(),
+    })
 }
 
+fn parse_dec(value: &str, field: &'static str) -> Result<U256, QuoteFieldError> {
+    U256::from_dec_str(value).map_err(|_| QuoteFieldError::Invalid {
+        field,
+        value: value.to_string(),
+    })
+}
+
+/// Typed accessors. Prefer these over the fields, which may gain siblings in any release.
+impl ZeroXQuoteResponse {
+    pub fn chain_id(&self) -> Option<u64> {
+        self.chain_id
+            .and_then(|chain_id| u64::try_from(chain_id).ok())
+    }
+
+    pub fn price(&self) -> Option<&str> {
+        self.price.as_deref()
+    }
+
+    pub fn guaranteed_price(&self) -> Option<&str> {
+        self.guaranteed_price.as_deref()
+    }
+
+    pub fn buy_amount(&self) -> Result<U256, QuoteFieldError> {
+        parse_dec(required(&self.buy_amount, "buy_amount")?, "buy_amount")
+    }
+
+    pub fn sell_amount(&self) -> Result<U256, QuoteFieldError> {
+        parse_dec(required(&self.sell_amount, "sell_amount")?, "sell_amount")
+    }
+
+    pub fn buy_token(&self) -> Result<Address, QuoteFieldError> {
+        parse_field(
+            required(&self.buy_token_address, "buy_token_address")?,
+            "buy_token_address",
+        )
+    }
+
+    pub fn sell_token(&self) -> Result<Address, QuoteFieldError> {
+        parse_field(
+            required(&self.sell_token_address, "sell_token_address")?,
+            "sell_token_address",
+        )
+    }
+
+    pub fn to(&self) -> Result<Address, QuoteFieldError> {
+        parse_field(required(&self.to, "to")?, "to")
+    }
+
+    pub fn allowance_target(&self) -> Result<Address, QuoteFieldError> {
+        parse_field(
+            required(&self.allowance_target, "allowance_target")?,
+            "allowance_target",
+        )
+    }
+
+    pub fn data(&self) -> Result<Bytes, QuoteFieldError> {
+        parse_field(required(&self.data, "data")?, "data")
+    }
+
+    /// Native token sent with the swap, zero if absent.
+    pub fn value(&self) -> Result<U256, QuoteFieldError> {
+        self.value
+            .as_deref()
+            .map_or(Ok(U256::zero()), |value| parse_dec(value, "value"))
+    }
+
+    /// The gas limit, falling back to `estimated_gas`.
+    pub fn gas(&self) -> Result<U256, QuoteFieldError> {
+        match (&self.gas, &self.estimated_gas) {
+            (Some(gas), _) => parse_dec(gas, "gas"),
+            (None, Some(gas)) => parse_dec(gas, "estimated_gas"),
+            (None, None) => Err(QuoteFieldError::Missing("gas")),
+        }
+    }
+
+    pub fn gas_price(&self) -> Result<U256, QuoteFieldError> {
+        parse_dec(required(&self.gas_price, "gas_price")?, "gas_price")
+    }
+
+    /// Zero if absent.
+    pub fn protocol_fee(&self) -> Result<U256, QuoteFieldError> {
+        self.protocol_fee
+            .as_deref()
+            .map_or(Ok(U256::zero()), |fee| parse_dec(fee, "protocol_fee"))
+    }
+
+    /// Liquidity sources and their proportions, empty if absent.
+    pub fn sources(&self) -> &[Source] {
+        self.sources.as_deref().unwrap_or_default()
+    }
+
+    pub fn orders(&self) -> &[Order] {
+        self.orders.as_deref().unwrap_or_default()
+    }
+
+    pub fn zero_ex_fee(&self) -> Option<&ZeroExFee> {
+        self.fees.as_ref()?.zero_ex_fee.as_ref()
+    }
+}
+
+// Fails to compile if any public type stops being usable across tasks.
+const _: fn() = || {
+    fn assert_send_sync<T: Send + Sync + 'static>() {}
+
+    assert_send_sync::<ZeroXClient>();
+    assert_send_sync::<ZeroXClientBuilder>();
+    assert_send_sync::<ZeroXClientError>();
+    assert_send_sync::<ZeroXQuoteParams>();
+    assert_send_sync::<ZeroXQuoteResponse>();
+    assert_send_sync::<QuoteResponse>();
+    assert_send_sync::<WithMetadata<ZeroXQuoteResponse>>();
+    assert_send_sync::<ResponseMetadata>();
+    assert_send_sync::<MultiChainClient>();
+    assert_send_sync::<SwapExecutorError>();
+};
+
 // #[cfg(feature = "transaction_request")]
 use ethers::core::types::{Address, Bytes, TransactionRequest, U256};
 
@@ -236,17 +1655,11 @@ impl ToTransactionRequest for ZeroXQuoteResponse {
             .ok_or("Missing 'data' field")?
             .parse::<Bytes>()?;
 
-        let value = self
-            .value
-            .as_ref()
-            .ok_or("Missing 'value' field")?
-            .parse::<U256>()?;
+        let value = self.value.as_ref().ok_or("Missing 'value' field")?;
+        let value = U256::from_dec_str(value)?;
 
-        let gas_price = self
-            .gas_price
-            .as_ref()
-            .ok_or("Missing 'gas_price' field")?
-            .parse::<U256>()?;
+        let gas_price = self.gas_price.as_ref().ok_or("Missing 'gas_price' field")?;
+        let gas_price = U256::from_dec_str(gas_price)?;
 
         let chain_id = self.chain_id.ok_or("Missing 'chain_id' field")?;
 
@@ -267,6 +1680,10 @@ impl ToTransactionRequest for ZeroXQuoteResponse {
 mod tests {
 
     use ethers::utils::parse_ether;
+    use wiremock::{
+        matchers::{header, method, path},
+        Mock, MockServer, ResponseTemplate,
+    };
 
     use super::*;
 
@@ -277,7 +1694,7 @@ mod tests {
         dotenv::dotenv().ok();
 
         let client = ZeroXClient::new(1, String::from("test")).unwrap();
-        assert_eq!(client.base_url, "https://api.0x.org");
+        assert_eq!(client.base_url(), "https://api.0x.org");
     }
 
     #[test]
@@ -288,6 +1705,488 @@ mod tests {
         assert!(client.is_err());
     }
 
+    #[test]
+    fn test_decode() {
+        let mut body =
+            br#"{"price":"2000.5","sources":[{"name":"Uniswap_V3","proportion":"1"}]}"#.to_vec();
+        let quote = decode::<ZeroXQuoteResponse>(&mut body).unwrap();
+        assert_eq!(quote.price(), Some("2000.5"));
+        assert_eq!(quote.sources()[0].name.as_deref(), Some("Uniswap_V3"));
+
+        assert!(decode::<ZeroXQuoteResponse>(&mut b"{\"price\":".to_vec()).is_err());
+    }
+
+    #[test]
+    fn test_response_accessors() {
+        let quote: ZeroXQuoteResponse = serde_json::from_value(serde_json::json!({
+            "chainId": 1,
+            "buyAmount": "2000",
+            "sellAmount": "not a number",
+            "to": "0xdef1c0ded9bec7f1a1670819833240f027b25eff",
+            "estimatedGas": "21000",
+        }))
+        .unwrap();
+
+        assert_eq!(quote.chain_id(), Some(1));
+        assert_eq!(quote.buy_amount(), Ok(U256::from(2000)));
+        assert_eq!(
+            quote.sell_amount(),
+            Err(QuoteFieldError::Invalid {
+                field: "sell_amount",
+                value: String::from("not a number"),
+            })
+        );
+        assert_eq!(
+            quote.to().unwrap(),
+            "0xdef1c0ded9bec7f1a1670819833240f027b25eff"
+                .parse::<Address>()
+                .unwrap()
+        );
+        assert_eq!(quote.gas(), Ok(U256::from(21000)));
+        assert_eq!(quote.value(), Ok(U256::zero()));
+        assert_eq!(quote.data(), Err(QuoteFieldError::Missing("data")));
+        assert!(quote.sources().is_empty());
+    }
+
+    #[test]
+    fn test_supported_chains() {
+        assert!(ZeroXClient::is_chain_supported(137));
+        assert!(!ZeroXClient::is_chain_supported(2));
+
+        for chain in ZeroXClient::supported_chains() {
+            let client = ZeroXClient::new(chain.chain_id, String::from("test")).unwrap();
+            assert_eq!(client.base_url(), chain.base_url);
+        }
+    }
+
+    #[test]
+    fn test_typed_params_query() {
+        let params: ZeroXQuoteParams = serde_json::from_value(serde_json::json!({
+            "sell_token": "ETH",
+            "buy_token": "DAI",
+            "sell_amount": "1",
+            "buy_token_percentage_fee": "0.01",
+            "skip_validation": "true",
+        }))
+        .unwrap();
+        assert_eq!(params.skip_validation, Some(true));
+
+        let query = quote_query(params);
+        assert_eq!(query["buyTokenPercentageFee"], "0.01");
+        assert_eq!(query["skipValidation"], "true");
+
+        assert!(
+            serde_json::from_value::<ZeroXQuoteParams>(serde_json::json!({
+                "sell_token": "ETH",
+                "buy_token": "DAI",
+                "sell_amount": "1",
+                "skip_validation": "True",
+            }))
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn test_error_kind() {
+        let status = ZeroXClientError::ZeroXInvalidResponseStatusCode;
+
+        assert_eq!(
+            status(StatusCode::TOO_MANY_REQUESTS).kind(),
+            ErrorKind::RateLimited
+        );
+        assert_eq!(status(StatusCode::BAD_GATEWAY).kind(), ErrorKind::Transient);
+        assert_eq!(
+            status(StatusCode::FORBIDDEN).kind(),
+            ErrorKind::Unauthorized
+        );
+        assert_eq!(
+            status(StatusCode::BAD_REQUEST).kind(),
+            ErrorKind::InvalidRequest
+        );
+        assert!(status(StatusCode::SERVICE_UNAVAILABLE).is_retryable());
+        assert!(!status(StatusCode::UNAUTHORIZED).is_retryable());
+        assert!(!ZeroXClientError::InvalidChainId(2).is_retryable());
+        assert_eq!(
+            ZeroXClientError::InvalidApiKey(StatusCode::UNAUTHORIZED).kind(),
+            ErrorKind::Unauthorized
+        );
+    }
+
+    #[tokio::test]
+    async fn test_rejected_api_key() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(401))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = ZeroXClient::builder(1, String::from("revoked"))
+            .base_url(server.uri())
+            .build()
+            .unwrap();
+
+        assert!(matches!(
+            client.get_quote(ZeroXQuoteParams::default()).await,
+            Err(ZeroXClientError::InvalidApiKey(StatusCode::UNAUTHORIZED))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_hedged_request_takes_first_success() {
+        let slow = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/swap/v1/quote"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(serde_json::json!({ "price": "1" }))
+                    .set_delay(std::time::Duration::from_secs(5)),
+            )
+            .mount(&slow)
+            .await;
+
+        let fast = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/swap/v1/quote"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(serde_json::json!({ "price": "2" })),
+            )
+            .mount(&fast)
+            .await;
+
+        let client = ZeroXClient::builder(1, String::from("test"))
+            .base_url(slow.uri())
+            .fallback_base_url(fast.uri())
+            .hedge_after(Duration::from_millis(50))
+            .build()
+            .unwrap();
+
+        let started = std::time::Instant::now();
+        let quote = client
+            .get_quote_with_metadata(ZeroXQuoteParams::default())
+            .await
+            .unwrap();
+
+        assert_eq!(quote.data.price.as_deref(), Some("2"));
+        assert_eq!(quote.metadata.base_url, fast.uri());
+        assert!(quote.metadata.timings.total < Duration::from_secs(5));
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+
+    #[tokio::test]
+    async fn test_fallback_after_primary_failure() {
+        let broken = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(503))
+            .mount(&broken)
+            .await;
+
+        let fallback = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/swap/v1/quote"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(serde_json::json!({ "price": "2" })),
+            )
+            .mount(&fallback)
+            .await;
+
+        let client = ZeroXClient::builder(1, String::from("test"))
+            .base_url(broken.uri())
+            .fallback_base_url(fallback.uri())
+            .build()
+            .unwrap();
+
+        let quote = client.get_quote(ZeroXQuoteParams::default()).await.unwrap();
+
+        assert_eq!(quote.price.as_deref(), Some("2"));
+    }
+
+    #[tokio::test]
+    async fn test_no_fallback_for_invalid_request() {
+        let primary = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(400))
+            .expect(1)
+            .mount(&primary)
+            .await;
+
+        let fallback = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(0)
+            .mount(&fallback)
+            .await;
+
+        let client = ZeroXClient::builder(1, String::from("test"))
+            .base_url(primary.uri())
+            .fallback_base_url(fallback.uri())
+            .build()
+            .unwrap();
+
+        let err = client
+            .get_quote(ZeroXQuoteParams::default())
+            .await
+            .unwrap_err();
+
+        assert_eq!(err.kind(), ErrorKind::InvalidRequest);
+    }
+
+    #[tokio::test]
+    async fn test_warm_up() {
+        let server = MockServer::start().await;
+        Mock::given(method("HEAD"))
+            .respond_with(ResponseTemplate::new(404))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = ZeroXClient::builder(1, String::from("test"))
+            .base_url(server.uri())
+            .pool_max_idle_per_host(4)
+            .pool_idle_timeout(Some(Duration::from_secs(300)))
+            .tcp_keepalive(Some(Duration::from_secs(30)))
+            .build()
+            .unwrap();
+
+        assert!(client.warm_up().await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_token_screener_blocks_quote() {
+        let client = ZeroXClient::builder(1, String::from("test"))
+            .base_url("http://127.0.0.1:9")
+            .token_screener(Blocklist::new([VITALIK]))
+            .build()
+            .unwrap();
+
+        let quote = client
+            .get_quote(ZeroXQuoteParams {
+                sell_amount: String::from("1000000000000000000"),
+                sell_token: String::from("ETH"),
+                buy_token: String::from("0x6b175474e89094c44da98b954eedeac495271d0f"), //DAI
+                taker_address: Some(VITALIK.to_lowercase()),
+                ..Default::default()
+            })
+            .await;
+
+        assert!(matches!(quote, Err(ZeroXClientError::ScreeningRejected(_))));
+    }
+
+    #[tokio::test]
+    async fn test_quote_validator_rejects_quote() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/swap/v1/quote"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(serde_json::json!({ "gas": "900000" })),
+            )
+            .mount(&server)
+            .await;
+
+        let client = ZeroXClient::builder(1, String::from("test"))
+            .base_url(server.uri())
+            .quote_validator(validation::FnValidator::new(
+                "max_gas",
+                |_, quote| match quote.gas().map_err(|err| err.to_string())? {
+                    gas if gas > U256::from(500_000) => Err(format!("gas {gas} over 500000")),
+                    _ => Ok(()),
+                },
+            ))
+            .build()
+            .unwrap();
+
+        let err = client
+            .get_quote(ZeroXQuoteParams {
+                sell_token: String::from("ETH"),
+                buy_token: String::from("DAI"),
+                sell_amount: String::from("1"),
+                ..Default::default()
+            })
+            .await
+            .unwrap_err();
+
+        assert!(matches!(
+            err,
+            ZeroXClientError::QuoteRejected(QuoteRejected { ref rule, .. }) if rule == "max_gas"
+        ));
+        assert_eq!(err.kind(), ErrorKind::Client);
+    }
+
+    #[tokio::test]
+    async fn test_clones_share_rate_limiter() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({})))
+            .mount(&server)
+            .await;
+
+        let client = ZeroXClient::builder(1, String::from("test"))
+            .base_url(server.uri())
+            .rate_limit(1, Duration::from_millis(300))
+            .build()
+            .unwrap();
+        let clone = client.clone();
+
+        let started = Instant::now();
+        client.get_quote(ZeroXQuoteParams::default()).await.unwrap();
+        clone.get_quote(ZeroXQuoteParams::default()).await.unwrap();
+
+        assert!(started.elapsed() >= Duration::from_millis(250));
+    }
+
+    #[tokio::test]
+    async fn test_rate_limit_status() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("x-ratelimit-limit", "10")
+                    .insert_header("x-ratelimit-remaining", "9")
+                    .insert_header("x-ratelimit-reset", "1")
+                    .set_body_json(serde_json::json!({})),
+            )
+            .mount(&server)
+            .await;
+
+        let client = ZeroXClient::builder(1, String::from("test"))
+            .base_url(server.uri())
+            .rate_limit(2, Duration::from_secs(60))
+            .build()
+            .unwrap();
+        assert_eq!(client.rate_limit_status().server, None);
+
+        client.get_quote(ZeroXQuoteParams::default()).await.unwrap();
+
+        let status = client.clone().rate_limit_status();
+        let server = status.server.unwrap();
+        assert_eq!((server.limit, server.remaining), (Some(10), Some(9)));
+        assert!(server.reset_at.unwrap() > server.observed_at);
+        assert_eq!(status.local.unwrap().available, 1);
+    }
+
+    #[tokio::test]
+    async fn test_reload_applies_to_clones() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(header("0x-api-key", "rotated"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({})))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = ZeroXClient::new(1, String::from("test")).unwrap();
+        let clone = client.clone();
+
+        client.reload(ClientConfig {
+            api_key: String::from("rotated"),
+            base_url: server.uri(),
+            rate_limit: None,
+        });
+
+        assert_eq!(clone.base_url(), server.uri());
+        assert!(clone.get_quote(ZeroXQuoteParams::default()).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_response_size_limit() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/swap/v1/quote"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(serde_json::json!({ "data": "0x".repeat(1024) })),
+            )
+            .mount(&server)
+            .await;
+
+        let client = ZeroXClient::builder(1, String::from("test"))
+            .base_url(server.uri())
+            .max_response_size(Some(1024))
+            .build()
+            .unwrap();
+
+        let quote = client.get_quote(ZeroXQuoteParams::default()).await;
+        assert!(matches!(
+            quote,
+            Err(ZeroXClientError::ResponseTooLarge { limit: 1024 })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_endpoint_timeouts() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(serde_json::json!({}))
+                    .set_delay(Duration::from_millis(200)),
+            )
+            .mount(&server)
+            .await;
+
+        let client = ZeroXClient::builder(1, String::from("test"))
+            .base_url(server.uri())
+            .retry_policy(NoRetry)
+            .endpoint_timeout(Endpoint::Price, Duration::from_millis(50))
+            .build()
+            .unwrap();
+
+        let price = client.get_price(ZeroXQuoteParams::default()).await;
+        assert!(matches!(price, Err(ZeroXClientError::ZeroXQuoteError(err)) if err.is_timeout()));
+        assert!(client.get_quote(ZeroXQuoteParams::default()).await.is_ok());
+
+        assert_eq!(Endpoint::of("/gasless/price"), Some(Endpoint::Price));
+        assert_eq!(
+            Endpoint::of("/orderbook/v1/nft/orders"),
+            Some(Endpoint::Orderbook)
+        );
+        assert_eq!(Endpoint::of("/gasless/chains"), None);
+    }
+
+    #[cfg(feature = "proptest")]
+    proptest::proptest! {
+        #[test]
+        fn test_arbitrary_serde_roundtrip(
+            params: ZeroXQuoteParams,
+            response: ZeroXQuoteResponse,
+        ) {
+            let value = serde_json::to_value(&params).unwrap();
+            let parsed: ZeroXQuoteParams = serde_json::from_value(value.clone()).unwrap();
+            proptest::prop_assert_eq!(serde_json::to_value(parsed).unwrap(), value);
+
+            let value = serde_json::to_value(&response).unwrap();
+            let parsed: ZeroXQuoteResponse = serde_json::from_value(value.clone()).unwrap();
+            proptest::prop_assert_eq!(serde_json::to_value(parsed).unwrap(), value);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_get_quote_typed_and_raw() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/swap/v1/quote"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "price": "2000",
+                "unmodeledField": true,
+            })))
+            .mount(&server)
+            .await;
+
+        let client = ZeroXClient::builder(1, String::from("test"))
+            .base_url(server.uri())
+            .build()
+            .unwrap();
+
+        let quote = client.get_quote(ZeroXQuoteParams::default()).await.unwrap();
+        assert_eq!(quote.price.as_deref(), Some("2000"));
+
+        let raw = client
+            .get_quote_raw(ZeroXQuoteParams::default())
+            .await
+            .unwrap();
+        assert_eq!(raw["unmodeledField"], Value::Bool(true));
+    }
+
     #[tokio::test]
     async fn test_get_quote() {
         tokio::time::sleep(std::time::Duration::from_secs(1)).await;
@@ -318,7 +2217,7 @@ mod tests {
                 sell_amount: String::from("1000000000000000000"),
                 sell_token: String::from("ETH"),
                 buy_token: String::from("0x6b175474e89094c44da98b954eedeac495271d0f"), //DAI
-                slippage_percentage: Some(String::from("0.1")),
+                slippage_percentage: Some(FeeBps::new(1000).into()),
                 ..Default::default()
             })
             .await;
@@ -376,7 +2275,7 @@ mod tests {
                 sell_token: String::from("ETH"),
                 buy_token: String::from("0x6b175474e89094c44da98b954eedeac495271d0f"), //DAI
                 fee_recipient: Some(String::from(VITALIK)),
-                buy_token_percentage_fee: Some(String::from("0.1")),
+                buy_token_percentage_fee: Some(FeeBps::new(1000).into()),
                 ..Default::default()
             })
             .await;
@@ -471,12 +2370,12 @@ mod tests {
 
         assert_eq!(
             transaction_request.value,
-            Some(sell_amount.parse::<U256>().unwrap())
+            Some(U256::from_dec_str(&sell_amount).unwrap())
         );
 
         assert_eq!(
             transaction_request.gas_price,
-            Some(quote.gas_price.unwrap().parse::<U256>().unwrap())
+            Some(U256::from_dec_str(&quote.gas_price.unwrap()).unwrap())
         );
 
         assert_eq!(
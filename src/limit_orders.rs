@@ -0,0 +1,607 @@
+//! On-chain state of 0x v4 limit orders, read from the Exchange Proxy, and a tracker that
+//! turns it into lifecycle events.
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use ethers::{
+    abi::{self, ParamType, Token},
+    core::types::{transaction::eip712::TypedData, Address, TransactionRequest, H256, U256},
+    providers::Middleware,
+    utils::id,
+};
+use thiserror::Error;
+use tokio::{sync::mpsc, task::JoinHandle, time::MissedTickBehavior};
+use tracing::debug;
+
+use crate::{
+    deployments::exchange_proxy,
+    eip712::{limit_order_typed_data, TypedDataError},
+    scheduler::MIN_INTERVAL,
+};
+
+const LIMIT_ORDER: &str =
+    "(address,address,uint128,uint128,uint128,address,address,address,address,bytes32,uint64,uint256)";
+const SIGNATURE: &str = "(uint8,uint8,bytes32,bytes32)";
+
+#[derive(Error, Debug)]
+pub enum FillabilityError {
+    #[error("No 0x Exchange Proxy known for chain {0}")]
+    UnsupportedChain(u64),
+
+    #[error("Middleware error: {0}")]
+    Middleware(Box<dyn std::error::Error + Send + Sync>),
+
+    #[error("Failed to decode order state: {0}")]
+    InvalidResponse(String),
+}
+
+/// A signed 0x v4 limit order, as returned by the orderbook.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LimitOrder {
+    pub maker_token: Address,
+    pub taker_token: Address,
+    pub maker_amount: U256,
+    pub taker_amount: U256,
+    pub taker_token_fee_amount: U256,
+    pub maker: Address,
+    pub taker: Address,
+    pub sender: Address,
+    pub fee_recipient: Address,
+    pub pool: H256,
+    pub expiry: u64,
+    pub salt: U256,
+}
+
+impl LimitOrder {
+    /// EIP-712 typed data for signing this order on `chain_id`.
+    pub fn typed_data(&self, chain_id: u64) -> Result<TypedData, TypedDataError> {
+        limit_order_typed_data(
+            chain_id,
+            serde_json::json!({
+                "makerToken": format!("{:?}", self.maker_token),
+                "takerToken": format!("{:?}", self.taker_token),
+                "makerAmount": self.maker_amount.to_string(),
+                "takerAmount": self.taker_amount.to_string(),
+                "takerTokenFeeAmount": self.taker_token_fee_amount.to_string(),
+                "maker": format!("{:?}", self.maker),
+                "taker": format!("{:?}", self.taker),
+                "sender": format!("{:?}", self.sender),
+                "feeRecipient": format!("{:?}", self.fee_recipient),
+                "pool": format!("{:?}", self.pool),
+                "expiry": self.expiry.to_string(),
+                "salt": self.salt.to_string(),
+            }),
+        )
+    }
+
+    fn into_token(self) -> Token {
+        Token::Tuple(vec![
+            Token::Address(self.maker_token),
+            Token::Address(self.taker_token),
+            Token::Uint(self.maker_amount),
+            Token::Uint(self.taker_amount),
+            Token::Uint(self.taker_token_fee_amount),
+            Token::Address(self.maker),
+            Token::Address(self.taker),
+            Token::Address(self.sender),
+            Token::Address(self.fee_recipient),
+            Token::FixedBytes(self.pool.as_bytes().to_vec()),
+            Token::Uint(self.expiry.into()),
+            Token::Uint(self.salt),
+        ])
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Signature {
+    pub signature_type: u8,
+    pub v: u8,
+    pub r: H256,
+    pub s: H256,
+}
+
+impl Signature {
+    fn into_token(self) -> Token {
+        Token::Tuple(vec![
+            Token::Uint(self.signature_type.into()),
+            Token::Uint(self.v.into()),
+            Token::FixedBytes(self.r.as_bytes().to_vec()),
+            Token::FixedBytes(self.s.as_bytes().to_vec()),
+        ])
+    }
+}
+
+/// `LibNativeOrder.OrderStatus`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OrderStatus {
+    Invalid,
+    Fillable,
+    Filled,
+    Cancelled,
+    Expired,
+}
+
+impl OrderStatus {
+    fn from_u8(status: u8) -> Option<OrderStatus> {
+        Some(match status {
+            0 => OrderStatus::Invalid,
+            1 => OrderStatus::Fillable,
+            2 => OrderStatus::Filled,
+            3 => OrderStatus::Cancelled,
+            4 => OrderStatus::Expired,
+            _ => return None,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OrderState {
+    pub order_hash: H256,
+    pub status: OrderStatus,
+    pub taker_token_filled_amount: U256,
+    /// Taker token amount that can still be filled, accounting for the maker's balance and
+    /// allowance.
+    pub fillable_taker_amount: U256,
+    pub is_signature_valid: bool,
+}
+
+impl OrderState {
+    /// Whether filling the order now can succeed.
+    pub fn is_fillable(&self) -> bool {
+        self.status == OrderStatus::Fillable
+            && self.is_signature_valid
+            && !self.fillable_taker_amount.is_zero()
+    }
+}
+
+/// Calls `getLimitOrderRelevantState` on the chain's Exchange Proxy.
+pub async fn limit_order_state<M: Middleware + 'static>(
+    provider: &M,
+    chain_id: u64,
+    order: &LimitOrder,
+    signature: &Signature,
+) -> Result<OrderState, FillabilityError> {
+    let result = call(
+        provider,
+        chain_id,
+        &format!("getLimitOrderRelevantState({},{})", LIMIT_ORDER, SIGNATURE),
+        &[order.clone().into_token(), signature.into_token()],
+    )
+    .await?;
+
+    let tokens = abi::decode(
+        &[order_info_type(), ParamType::Uint(128), ParamType::Bool],
+        &result,
+    )
+    .map_err(invalid_response)?;
+
+    match <[Token; 3]>::try_from(tokens) {
+        Ok([info, Token::Uint(fillable), Token::Bool(is_signature_valid)]) => {
+            order_state(info, fillable, is_signature_valid)
+        }
+        _ => Err(invalid_response("unexpected return values")),
+    }
+}
+
+/// Checks many orders in one `eth_call` via `batchGetLimitOrderRelevantStates`. States are
+/// returned in the order of `orders`.
+pub async fn limit_order_states<M: Middleware + 'static>(
+    provider: &M,
+    chain_id: u64,
+    orders: &[(LimitOrder, Signature)],
+) -> Result<Vec<OrderState>, FillabilityError> {
+    if orders.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let (order_tokens, signature_tokens) = orders
+        .iter()
+        .map(|(order, signature)| (order.clone().into_token(), signature.into_token()))
+        .unzip();
+
+    let result = call(
+        provider,
+        chain_id,
+        &format!(
+            "batchGetLimitOrderRelevantStates({}[],{}[])",
+            LIMIT_ORDER, SIGNATURE
+        ),
+        &[Token::Array(order_tokens), Token::Array(signature_tokens)],
+    )
+    .await?;
+
+    let tokens = abi::decode(
+        &[
+            ParamType::Array(Box::new(order_info_type())),
+            ParamType::Array(Box::new(ParamType::Uint(128))),
+            ParamType::Array(Box::new(ParamType::Bool)),
+        ],
+        &result,
+    )
+    .map_err(invalid_response)?;
+
+    let Ok([Token::Array(infos), Token::Array(fillable), Token::Array(is_signature_valid)]) =
+        <[Token; 3]>::try_from(tokens)
+    else {
+        return Err(invalid_response("unexpected return values"));
+    };
+
+    if infos.len() != orders.len()
+        || fillable.len() != orders.len()
+        || is_signature_valid.len() != orders.len()
+    {
+        return Err(invalid_response("result length does not match orders"));
+    }
+
+    infos
+        .into_iter()
+        .zip(fillable)
+        .zip(is_signature_valid)
+        .map(
+            |((info, fillable), is_signature_valid)| match (fillable, is_signature_valid) {
+                (Token::Uint(fillable), Token::Bool(is_signature_valid)) => {
+                    order_state(info, fillable, is_signature_valid)
+                }
+                _ => Err(invalid_response("unexpected return values")),
+            },
+        )
+        .collect()
+}
+
+/// A change in a tracked order's state. Each carries the state it was observed in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LimitOrderEvent {
+    /// First seen fillable with nothing filled.
+    Open(OrderState),
+    /// More of the order was filled, but some remains.
+    PartiallyFilled(OrderState),
+    Filled(OrderState),
+    Cancelled(OrderState),
+    Expired(OrderState),
+    /// The exchange reports the order as invalid, e.g. zero-sized; it can never be filled.
+    Invalid(OrderState),
+}
+
+impl LimitOrderEvent {
+    pub fn state(&self) -> &OrderState {
+        match self {
+            LimitOrderEvent::Open(state)
+            | LimitOrderEvent::PartiallyFilled(state)
+            | LimitOrderEvent::Filled(state)
+            | LimitOrderEvent::Cancelled(state)
+            | LimitOrderEvent::Expired(state)
+            | LimitOrderEvent::Invalid(state) => state,
+        }
+    }
+
+    /// The event for `state`, if it differs from `previous`.
+    fn between(previous: Option<&OrderState>, state: OrderState) -> Option<LimitOrderEvent> {
+        if previous.is_some_and(|previous| {
+            previous.status == state.status
+                && previous.taker_token_filled_amount == state.taker_token_filled_amount
+        }) {
+            return None;
+        }
+
+        match state.status {
+            OrderStatus::Fillable if state.taker_token_filled_amount.is_zero() => {
+                Some(LimitOrderEvent::Open(state))
+            }
+            OrderStatus::Fillable => Some(LimitOrderEvent::PartiallyFilled(state)),
+            OrderStatus::Filled => Some(LimitOrderEvent::Filled(state)),
+            OrderStatus::Cancelled => Some(LimitOrderEvent::Cancelled(state)),
+            OrderStatus::Expired => Some(LimitOrderEvent::Expired(state)),
+            OrderStatus::Invalid => Some(LimitOrderEvent::Invalid(state)),
+        }
+    }
+}
+
+type TrackedOrders = Arc<Mutex<Vec<(LimitOrder, Signature)>>>;
+
+/// Polls the on-chain state of posted orders and sends a [`LimitOrderEvent`] whenever one
+/// changes.
+///
+/// All tracked orders are read in one `batchGetLimitOrderRelevantStates` call per interval.
+/// Orders are dropped once filled, cancelled, expired or found invalid. Polling stops when
+/// the tracker or the event receiver is dropped.
+pub struct LimitOrderTracker {
+    orders: TrackedOrders,
+    task: JoinHandle<()>,
+}
+
+impl LimitOrderTracker {
+    /// Polls every `interval`, at least [`MIN_INTERVAL`].
+    pub fn start<M: Middleware + 'static>(
+        provider: Arc<M>,
+        chain_id: u64,
+        interval: Duration,
+    ) -> (LimitOrderTracker, mpsc::UnboundedReceiver<LimitOrderEvent>) {
+        let orders = TrackedOrders::default();
+        let (events, receiver) = mpsc::unbounded_channel();
+        let task = tokio::spawn(track_loop(
+            provider,
+            chain_id,
+            interval.max(MIN_INTERVAL),
+            orders.clone(),
+            events,
+        ));
+
+        (LimitOrderTracker { orders, task }, receiver)
+    }
+
+    /// Starts tracking a posted order from the next poll.
+    pub fn track(&self, order: LimitOrder, signature: Signature) {
+        self.orders.lock().unwrap().push((order, signature));
+    }
+
+    /// Number of orders still being tracked.
+    pub fn len(&self) -> usize {
+        self.orders.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl Drop for LimitOrderTracker {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+async fn track_loop<M: Middleware + 'static>(
+    provider: Arc<M>,
+    chain_id: u64,
+    interval: Duration,
+    orders: TrackedOrders,
+    events: mpsc::UnboundedSender<LimitOrderEvent>,
+) {
+    let mut ticks = tokio::time::interval(interval);
+    ticks.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    let mut last: HashMap<H256, OrderState> = HashMap::new();
+
+    loop {
+        ticks.tick().await;
+
+        let polled = orders.lock().unwrap().clone();
+        let states = match limit_order_states(provider.as_ref(), chain_id, &polled).await {
+            Ok(states) => states,
+            Err(err) => {
+                debug!("failed to poll limit orders: {}", err);
+                continue;
+            }
+        };
+
+        let mut finished = Vec::new();
+        for (index, state) in states.into_iter().enumerate() {
+            if let Some(event) = LimitOrderEvent::between(last.get(&state.order_hash), state) {
+                if events.send(event).is_err() {
+                    return;
+                }
+            }
+            if matches!(
+                state.status,
+                OrderStatus::Filled
+                    | OrderStatus::Cancelled
+                    | OrderStatus::Expired
+                    | OrderStatus::Invalid
+            ) {
+                last.remove(&state.order_hash);
+                finished.push(index);
+            } else {
+                last.insert(state.order_hash, state);
+            }
+        }
+
+        // Orders are only ever appended, so the polled ones are still first.
+        if !finished.is_empty() {
+            let mut index = 0;
+            orders.lock().unwrap().retain(|_| {
+                index += 1;
+                !finished.contains(&(index - 1))
+            });
+        }
+    }
+}
+
+async fn call<M: Middleware + 'static>(
+    provider: &M,
+    chain_id: u64,
+    signature: &str,
+    args: &[Token],
+) -> Result<Vec<u8>, FillabilityError> {
+    let exchange_proxy =
+        exchange_proxy(chain_id).ok_or(FillabilityError::UnsupportedChain(chain_id))?;
+
+    let mut data = id(signature).to_vec();
+    data.extend(abi::encode(args));
+
+    let tx = TransactionRequest::new().to(exchange_proxy).data(data);
+    let result = provider
+        .call(&tx.into(), None)
+        .await
+        .map_err(|err| FillabilityError::Middleware(Box::new(err)))?;
+
+    Ok(result.to_vec())
+}
+
+fn order_info_type() -> ParamType {
+    ParamType::Tuple(vec![
+        ParamType::FixedBytes(32),
+        ParamType::Uint(8),
+        ParamType::Uint(128),
+    ])
+}
+
+fn order_state(
+    info: Token,
+    fillable_taker_amount: U256,
+    is_signature_valid: bool,
+) -> Result<OrderState, FillabilityError> {
+    let Token::Tuple(info) = info else {
+        return Err(invalid_response("order info is not a tuple"));
+    };
+
+    match <[Token; 3]>::try_from(info) {
+        Ok([Token::FixedBytes(hash), Token::Uint(status), Token::Uint(filled)]) => {
+            let status = u8::try_from(status)
+                .ok()
+                .and_then(OrderStatus::from_u8)
+                .ok_or_else(|| invalid_response(format!("unknown order status {}", status)))?;
+
+            Ok(OrderState {
+                order_hash: H256::from_slice(&hash),
+                status,
+                taker_token_filled_amount: filled,
+                fillable_taker_amount,
+                is_signature_valid,
+            })
+        }
+        _ => Err(invalid_response("unexpected order info fields")),
+    }
+}
+
+fn invalid_response(err: impl ToString) -> FillabilityError {
+    FillabilityError::InvalidResponse(err.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use ethers::{core::types::Bytes, providers::Provider};
+
+    use super::*;
+
+    fn order_info(status: u8, filled: u64) -> Token {
+        Token::Tuple(vec![
+            Token::FixedBytes(H256::repeat_byte(status).as_bytes().to_vec()),
+            Token::Uint(status.into()),
+            Token::Uint(filled.into()),
+        ])
+    }
+
+    #[tokio::test]
+    async fn test_limit_order_states_batch() {
+        let (provider, mock) = Provider::mocked();
+        let response = abi::encode(&[
+            Token::Array(vec![order_info(1, 0), order_info(4, 10)]),
+            Token::Array(vec![Token::Uint(100.into()), Token::Uint(0.into())]),
+            Token::Array(vec![Token::Bool(true), Token::Bool(true)]),
+        ]);
+        mock.push::<Bytes, Bytes>(response.into()).unwrap();
+
+        let orders = vec![(LimitOrder::default(), Signature::default()); 2];
+        let states = limit_order_states(&provider, 1, &orders).await.unwrap();
+
+        assert_eq!(states.len(), 2);
+        assert!(states[0].is_fillable());
+        assert_eq!(states[1].status, OrderStatus::Expired);
+        assert!(!states[1].is_fillable());
+
+        assert!(limit_order_states(&provider, 1, &[])
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_limit_order_state() {
+        let (provider, mock) = Provider::mocked();
+        let response = abi::encode(&[
+            Token::Tuple(vec![
+                Token::FixedBytes(H256::repeat_byte(7).as_bytes().to_vec()),
+                Token::Uint(1.into()),
+                Token::Uint(40.into()),
+            ]),
+            Token::Uint(60.into()),
+            Token::Bool(true),
+        ]);
+        mock.push::<Bytes, Bytes>(response.into()).unwrap();
+
+        let state = limit_order_state(&provider, 1, &LimitOrder::default(), &Signature::default())
+            .await
+            .unwrap();
+
+        assert_eq!(
+            state,
+            OrderState {
+                order_hash: H256::repeat_byte(7),
+                status: OrderStatus::Fillable,
+                taker_token_filled_amount: U256::from(40),
+                fillable_taker_amount: U256::from(60),
+                is_signature_valid: true,
+            }
+        );
+        assert!(state.is_fillable());
+        assert!(LimitOrder::default().typed_data(1).is_ok());
+
+        assert!(matches!(
+            limit_order_state(&provider, 2, &LimitOrder::default(), &Signature::default()).await,
+            Err(FillabilityError::UnsupportedChain(2))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_limit_order_tracker() {
+        let (provider, mock) = Provider::mocked();
+        // Responses are served last pushed first.
+        for (status, filled) in [(2, 100), (1, 40), (1, 40), (1, 0)] {
+            let response = abi::encode(&[
+                Token::Array(vec![order_info(status, filled)]),
+                Token::Array(vec![Token::Uint((100 - filled).into())]),
+                Token::Array(vec![Token::Bool(true)]),
+            ]);
+            mock.push::<Bytes, Bytes>(response.into()).unwrap();
+        }
+
+        let (tracker, mut events) =
+            LimitOrderTracker::start(Arc::new(provider), 1, Duration::from_millis(10));
+        tracker.track(LimitOrder::default(), Signature::default());
+
+        let mut kinds = Vec::new();
+        for _ in 0..3 {
+            kinds.push(match events.recv().await.unwrap() {
+                LimitOrderEvent::Open(state) => ("open", state.taker_token_filled_amount),
+                LimitOrderEvent::PartiallyFilled(state) => {
+                    ("partially filled", state.taker_token_filled_amount)
+                }
+                LimitOrderEvent::Filled(state) => ("filled", state.taker_token_filled_amount),
+                event => panic!("unexpected event {:?}", event),
+            });
+        }
+        assert_eq!(
+            kinds,
+            [
+                ("open", U256::zero()),
+                ("partially filled", U256::from(40)),
+                ("filled", U256::from(100)),
+            ]
+        );
+        assert!(tracker.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_limit_order_tracker_drops_invalid_orders() {
+        let (provider, mock) = Provider::mocked();
+        let response = abi::encode(&[
+            Token::Array(vec![order_info(0, 0)]),
+            Token::Array(vec![Token::Uint(U256::zero())]),
+            Token::Array(vec![Token::Bool(true)]),
+        ]);
+        mock.push::<Bytes, Bytes>(response.into()).unwrap();
+
+        // a zero interval used to panic the polling task, closing the channel
+        let (tracker, mut events) = LimitOrderTracker::start(Arc::new(provider), 1, Duration::ZERO);
+        tracker.track(LimitOrder::default(), Signature::default());
+
+        assert!(matches!(
+            events.recv().await,
+            Some(LimitOrderEvent::Invalid(_))
+        ));
+        assert!(tracker.is_empty());
+    }
+}
@@ -0,0 +1,159 @@
+use std::{collections::HashMap, time::Duration};
+
+use reqwest::{StatusCode, Url};
+use tracing::{debug, info, warn};
+
+use crate::{WithMetadata, ZeroXClientError};
+
+const REDACTED: &str = "[redacted]";
+
+/// Which secrets are masked in request logs. Both are redacted by default.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LogRedaction {
+    pub api_key: bool,
+    pub taker: bool,
+}
+
+impl Default for LogRedaction {
+    fn default() -> Self {
+        LogRedaction {
+            api_key: true,
+            taker: true,
+        }
+    }
+}
+
+impl LogRedaction {
+    pub fn none() -> LogRedaction {
+        LogRedaction {
+            api_key: false,
+            taker: false,
+        }
+    }
+
+    pub(crate) fn api_key<'a>(&self, api_key: &'a str) -> &'a str {
+        if self.api_key {
+            REDACTED
+        } else {
+            api_key
+        }
+    }
+
+    pub(crate) fn taker<'a>(&self, taker: &'a str) -> &'a str {
+        if self.taker {
+            REDACTED
+        } else {
+            taker
+        }
+    }
+}
+
+pub(crate) struct RequestLog<'a> {
+    pub chain_id: u64,
+    pub base_url: &'a str,
+    pub path: &'a str,
+    pub query: &'a HashMap<&'a str, String>,
+    pub api_key: &'a str,
+    pub redaction: LogRedaction,
+}
+
+impl RequestLog<'_> {
+    /// Emits one structured event under the `zerox_client::request` target: `info` for
+    /// successful requests and `warn` for failures.
+    pub(crate) fn emit<T>(
+        &self,
+        result: &Result<WithMetadata<T>, ZeroXClientError>,
+        elapsed: Duration,
+    ) {
+        let field = |name| self.query.get(name).map(String::as_str).unwrap_or_default();
+        let taker = self.redaction.taker(field("takerAddress"));
+        let api_key = self.redaction.api_key(self.api_key);
+
+        match result {
+            Ok(response) => info!(
+                target: "zerox_client::request",
+                chain_id = self.chain_id,
+                base_url = response.metadata.base_url.as_str(),
+                endpoint = self.path,
+                sell_token = field("sellToken"),
+                buy_token = field("buyToken"),
+                sell_amount = field("sellAmount"),
+                taker,
+                api_key,
+                status = response.metadata.status.as_u16(),
+                duration_ms = response.metadata.timings.total.as_millis() as u64,
+                "0x request succeeded"
+            ),
+            Err(err) => warn!(
+                target: "zerox_client::request",
+                chain_id = self.chain_id,
+                base_url = self.base_url,
+                endpoint = self.path,
+                sell_token = field("sellToken"),
+                buy_token = field("buyToken"),
+                sell_amount = field("sellAmount"),
+                taker,
+                api_key,
+                status = status(err),
+                duration_ms = elapsed.as_millis() as u64,
+                error = %err,
+                "0x request failed"
+            ),
+        }
+    }
+}
+
+/// Logs the request URL, with the taker masked per `redaction`, and the API key header.
+pub(crate) fn log_request_url(url: &Url, api_key: &str, redaction: LogRedaction) {
+    let mut url = url.clone();
+    let query = url
+        .query_pairs()
+        .map(|(key, value)| {
+            let value = match key.as_ref() {
+                "takerAddress" => redaction.taker(&value).to_string(),
+                _ => value.into_owned(),
+            };
+            (key.into_owned(), value)
+        })
+        .collect::<Vec<_>>();
+    url.query_pairs_mut().clear().extend_pairs(query);
+
+    debug!(target: "zerox_client::body", %url, api_key, "0x request");
+}
+
+pub(crate) fn log_response_body(status: StatusCode, body: &[u8]) {
+    debug!(
+        target: "zerox_client::body",
+        status = status.as_u16(),
+        body = %String::from_utf8_lossy(body),
+        "0x response"
+    );
+}
+
+pub(crate) fn status(err: &ZeroXClientError) -> Option<u16> {
+    match err {
+        ZeroXClientError::ZeroXInvalidResponseStatusCode(status)
+        | ZeroXClientError::InvalidApiKey(status) => Some(status.as_u16()),
+        ZeroXClientError::ZeroXQuoteError(err) => err.status().map(|status| status.as_u16()),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_redaction() {
+        let redaction = LogRedaction::default();
+        assert_eq!(redaction.api_key("secret"), REDACTED);
+        assert_eq!(redaction.taker("0xabc"), REDACTED);
+
+        let redaction = LogRedaction {
+            taker: false,
+            ..Default::default()
+        };
+        assert_eq!(redaction.api_key("secret"), REDACTED);
+        assert_eq!(redaction.taker("0xabc"), "0xabc");
+    }
+}
@@ -0,0 +1,112 @@
+use hyper::client::connect::dns::Name;
+use reqwest::{
+    dns::{Resolve, Resolving},
+    StatusCode,
+};
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+/// A response from the 0x API along with metadata about the request that produced it.
+#[derive(Debug)]
+pub struct WithMetadata<T> {
+    pub data: T,
+    pub metadata: ResponseMetadata,
+}
+
+#[derive(Debug, Clone)]
+pub struct ResponseMetadata {
+    /// Base URL that served the response (differs from the primary when hedging or falling back).
+    pub base_url: String,
+    pub status: StatusCode,
+    /// Number of attempts made, including retries.
+    pub attempts: u32,
+    pub timings: RequestTimings,
+}
+
+/// Timings of the attempt that produced the response.
+///
+/// `dns`, `connect` and `ttfb` are consecutive, so a slow `ttfb` with a fast `connect` points
+/// at the server rather than the network.
+#[derive(Debug, Clone, Copy)]
+pub struct RequestTimings {
+    /// Time spent resolving the host, or `None` if a pooled connection was reused.
+    pub dns: Option<Duration>,
+    /// Time spent opening the TCP connection and verifying the server's TLS certificate, or
+    /// `None` if a pooled connection was reused, the TLS session was resumed or the URL is
+    /// plain HTTP.
+    pub connect: Option<Duration>,
+    /// Time from the connection being ready until the response headers were received.
+    pub ttfb: Duration,
+    /// Time from sending the request until the body was read.
+    pub total: Duration,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct DnsTiming {
+    finished: Instant,
+    elapsed: Duration,
+}
+
+/// DNS resolver that records how long each lookup takes so it can be attributed to requests.
+#[derive(Debug, Default)]
+pub(crate) struct TimingResolver {
+    timings: Arc<Mutex<HashMap<String, DnsTiming>>>,
+}
+
+impl TimingResolver {
+    /// Returns the duration of the last lookup for `host` if it finished after `since`.
+    pub(crate) fn dns_time(&self, host: &str, since: Instant) -> Option<Duration> {
+        let timings = self.timings.lock().unwrap();
+        timings
+            .get(host)
+            .filter(|timing| timing.finished >= since)
+            .map(|timing| timing.elapsed)
+    }
+}
+
+/// When the TLS handshake with each host verified the server's certificate, recorded by the
+/// client's certificate verifier so connection time can be attributed to requests.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct HandshakeTimings {
+    verified: Arc<Mutex<HashMap<String, Instant>>>,
+}
+
+impl HandshakeTimings {
+    pub(crate) fn record(&self, host: String) {
+        self.verified.lock().unwrap().insert(host, Instant::now());
+    }
+
+    /// Returns how long after `since` the last handshake with `host` verified the server, if
+    /// it did so after `since`.
+    pub(crate) fn connect_time(&self, host: &str, since: Instant) -> Option<Duration> {
+        let verified = self.verified.lock().unwrap();
+        verified
+            .get(host)
+            .filter(|verified| **verified >= since)
+            .map(|verified| *verified - since)
+    }
+}
+
+impl Resolve for TimingResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        let timings = self.timings.clone();
+        Box::pin(async move {
+            let started = Instant::now();
+            let addrs = tokio::net::lookup_host((name.as_str(), 0)).await?;
+            let finished = Instant::now();
+
+            timings.lock().unwrap().insert(
+                name.as_str().to_string(),
+                DnsTiming {
+                    finished,
+                    elapsed: finished - started,
+                },
+            );
+
+            Ok(Box::new(addrs.collect::<Vec<_>>().into_iter()) as _)
+        })
+    }
+}
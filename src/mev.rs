@@ -0,0 +1,145 @@
+//! A heuristic for how exposed a quote is to sandwich attacks, used to decide whether to
+//! submit the swap through a private mempool.
+
+use std::str::FromStr;
+
+use rust_decimal::Decimal;
+
+use crate::{Percentage, SourcePreset, ZeroXQuoteResponse};
+
+/// Price impact or slippage at which that factor counts fully towards the score.
+const SATURATION_PERCENT: Decimal = Decimal::TWO;
+
+/// Scores at or above this are [`RiskLevel::High`].
+const HIGH: u8 = 60;
+const MEDIUM: u8 = 25;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum RiskLevel {
+    Low,
+    Medium,
+    High,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SandwichRisk {
+    /// From 0 to 100.
+    pub score: u8,
+    pub level: RiskLevel,
+    /// Share of the route that can be sandwiched, with pools weighted by how far a trade moves
+    /// them. RFQ and private market maker liquidity counts as zero.
+    pub exposed_share: Decimal,
+    /// Whether to send the swap through a private mempool or relay instead of the public one.
+    pub recommend_private_submission: bool,
+}
+
+/// How much of a source's share is exposed: constant-product pools move the most per trade,
+/// concentrated and stable pools less.
+fn source_exposure(name: &str, private_sources: &[String]) -> Decimal {
+    if private_sources.iter().any(|source| source == name) {
+        return Decimal::ZERO;
+    }
+    let name = name.to_ascii_lowercase();
+    if ["curve", "balancer", "synapse", "saddle", "dodo"]
+        .iter()
+        .any(|stable| name.contains(stable))
+    {
+        Decimal::new(5, 1)
+    } else if name.ends_with("_v3") || name.contains("v3") {
+        Decimal::new(8, 1)
+    } else {
+        Decimal::ONE
+    }
+}
+
+impl ZeroXQuoteResponse {
+    /// Scores the quote's sandwich exposure from its price impact, the pools it routes through
+    /// and the `slippage` it will be submitted with, the most an attacker can extract.
+    ///
+    /// This is a heuristic for choosing a submission path, not a guarantee either way.
+    pub fn sandwich_risk(&self, slippage: Percentage) -> SandwichRisk {
+        let chain_id = self.chain_id.and_then(|id| u64::try_from(id).ok());
+        let private_sources = SourcePreset::AmmOnly.excluded_sources(chain_id.unwrap_or(1));
+
+        // Without a route, assume it is all AMM liquidity.
+        let exposed_share = match &self.sources {
+            Some(sources) => sources
+                .iter()
+                .filter_map(|source| {
+                    let proportion = Decimal::from_str(source.proportion.as_deref()?).ok()?;
+                    let name = source.name.as_deref().unwrap_or_default();
+                    Some(proportion * source_exposure(name, &private_sources))
+                })
+                .sum::<Decimal>()
+                .min(Decimal::ONE),
+            None => Decimal::ONE,
+        };
+
+        let factor =
+            |percent: Decimal| (percent / SATURATION_PERCENT).clamp(Decimal::ZERO, Decimal::ONE);
+        let impact = self
+            .estimated_price_impact
+            .as_deref()
+            .and_then(|impact| Decimal::from_str(impact).ok())
+            .unwrap_or_default();
+        let magnitude = (factor(impact) + factor(slippage.percent())) / Decimal::TWO;
+
+        let score = (exposed_share * magnitude * Decimal::ONE_HUNDRED)
+            .round()
+            .try_into()
+            .unwrap_or(100u8);
+        let level = match score {
+            score if score >= HIGH => RiskLevel::High,
+            score if score >= MEDIUM => RiskLevel::Medium,
+            _ => RiskLevel::Low,
+        };
+
+        SandwichRisk {
+            score,
+            level,
+            exposed_share,
+            recommend_private_submission: level == RiskLevel::High,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn quote(impact: &str, sources: serde_json::Value) -> ZeroXQuoteResponse {
+        serde_json::from_value(serde_json::json!({
+            "chainId": 1,
+            "estimatedPriceImpact": impact,
+            "sources": sources,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_sandwich_risk() {
+        let amm = quote(
+            "1.5",
+            serde_json::json!([
+                { "name": "Uniswap_V2", "proportion": "1" },
+                { "name": "Hashflow", "proportion": "0" },
+            ]),
+        );
+        let risk = amm.sandwich_risk(Percentage::from_percent(Decimal::TWO));
+        assert_eq!(risk.score, 88);
+        assert_eq!(risk.level, RiskLevel::High);
+        assert!(risk.recommend_private_submission);
+
+        let rfq = quote(
+            "1.5",
+            serde_json::json!([
+                { "name": "Uniswap_V3", "proportion": "0.25" },
+                { "name": "0x", "proportion": "0.75" },
+            ]),
+        );
+        let risk = rfq.sandwich_risk(Percentage::from_percent(Decimal::TWO));
+        assert_eq!(risk.exposed_share, Decimal::new(2, 1));
+        assert_eq!(risk.level, RiskLevel::Low);
+        assert!(!risk.recommend_private_submission);
+    }
+}
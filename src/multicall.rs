@@ -0,0 +1,232 @@
+use ethers::{
+    abi::{self, Token},
+    core::types::{Address, Bytes, NameOrAddress, TransactionRequest, U256},
+    utils::id,
+};
+
+use crate::{
+    approval::{approve_calldata, build_approve_tx, sells_native_token},
+    ToTransactionRequest, ZeroXQuoteResponse,
+};
+
+/// Safe's `MultiSendCallOnly` v1.3.0, deployed at the same address on every supported chain.
+pub const SAFE_MULTI_SEND_CALL_ONLY_ADDRESS: &str = "0x40A2aCCbd92BCA938b02010E17A5b8929b49130D";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Call {
+    pub to: Address,
+    pub value: U256,
+    pub data: Bytes,
+}
+
+impl TryFrom<&TransactionRequest> for Call {
+    type Error = Box<dyn std::error::Error>;
+
+    fn try_from(tx: &TransactionRequest) -> Result<Self, Self::Error> {
+        let to = match tx.to.as_ref().ok_or("Missing 'to' field")? {
+            NameOrAddress::Address(address) => *address,
+            NameOrAddress::Name(name) => return Err(format!("Unresolved ENS name {}", name).into()),
+        };
+
+        Ok(Call {
+            to,
+            value: tx.value.unwrap_or_default(),
+            data: tx.data.clone().unwrap_or_default(),
+        })
+    }
+}
+
+/// Returns the calls needed to execute the quote: the `approve` of the sell token (unless
+/// selling the native token) followed by the swap itself.
+///
+/// The calls must be sent from the account that swaps, e.g. as an EIP-5792 `wallet_sendCalls`
+/// batch or by a smart account. Relaying them through a contract such as Multicall3 makes it
+/// the `msg.sender`, so the approval and the bought tokens would be the contract's.
+pub fn approve_and_swap_calls(
+    quote: &ZeroXQuoteResponse,
+) -> Result<Vec<Call>, Box<dyn std::error::Error>> {
+    let mut calls = Vec::with_capacity(2);
+
+    if !sells_native_token(quote) {
+        calls.push(Call::try_from(&build_approve_tx(quote)?)?);
+    }
+
+    calls.push(Call::try_from(&quote.to_transaction_request()?)?);
+
+    Ok(calls)
+}
+
+/// Returns the calls to execute several quotes in one batch: one `approve` per sell token and
+/// spender, for the sum of the amounts sold, followed by the swaps in order.
+///
+/// As with [`approve_and_swap_calls`], the calls must be sent from the account that swaps.
+pub fn swap_bundle_calls<'a>(
+    quotes: impl IntoIterator<Item = &'a ZeroXQuoteResponse>,
+) -> Result<Vec<Call>, Box<dyn std::error::Error>> {
+    let mut approvals: Vec<(Address, Address, U256)> = Vec::new();
+    let mut swaps = Vec::new();
+    let mut chain_id = None;
+
+    for quote in quotes {
+        let quote_chain_id = quote.chain_id.ok_or("Missing 'chain_id' field")?;
+        if *chain_id.get_or_insert(quote_chain_id) != quote_chain_id {
+            return Err("Quotes are for different chains".into());
+        }
+
+        if !sells_native_token(quote) {
+            let token = quote
+                .sell_token_address
+                .as_ref()
+                .ok_or("Missing 'sell_token_address' field")?
+                .parse::<Address>()?;
+            let spender = quote
+                .allowance_target
+                .as_ref()
+                .ok_or("Missing 'allowance_target' field")?
+                .parse::<Address>()?;
+            let amount = quote.sell_amount()?;
+
+            match approvals
+                .iter_mut()
+                .find(|(approved, approved_for, _)| (*approved, *approved_for) == (token, spender))
+            {
+                Some((_, _, total)) => *total += amount,
+                None => approvals.push((token, spender, amount)),
+            }
+        }
+
+        swaps.push(Call::try_from(&quote.to_transaction_request()?)?);
+    }
+
+    Ok(approvals
+        .into_iter()
+        .map(|(token, spender, amount)| Call {
+            to: token,
+            value: U256::zero(),
+            data: approve_calldata(spender, amount),
+        })
+        .chain(swaps)
+        .collect())
+}
+
+/// Calldata for Safe `MultiSend.multiSend`, with every call packed as a plain call.
+pub fn safe_multi_send_calldata(calls: &[Call]) -> Bytes {
+    let mut transactions = Vec::new();
+    for call in calls {
+        let mut value = [0u8; 32];
+        call.value.to_big_endian(&mut value);
+        let mut length = [0u8; 32];
+        U256::from(call.data.len()).to_big_endian(&mut length);
+
+        transactions.push(0u8); // operation: call
+        transactions.extend_from_slice(call.to.as_bytes());
+        transactions.extend_from_slice(&value);
+        transactions.extend_from_slice(&length);
+        transactions.extend_from_slice(&call.data);
+    }
+
+    let mut data = id("multiSend(bytes)").to_vec();
+    data.extend(abi::encode(&[Token::Bytes(transactions)]));
+    data.into()
+}
+
+/// A transaction for a Safe to execute, e.g. through the Safe transaction service.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SafeTransaction {
+    pub to: Address,
+    pub value: U256,
+    pub data: Bytes,
+    /// `0` for a call, `1` for a `delegatecall`.
+    pub operation: u8,
+}
+
+/// Packages several quotes and their approvals into one Safe transaction, a `delegatecall` to
+/// `MultiSendCallOnly`, so the Safe approves and swaps its own tokens atomically.
+pub fn build_swap_bundle_safe_batch<'a>(
+    quotes: impl IntoIterator<Item = &'a ZeroXQuoteResponse>,
+) -> Result<SafeTransaction, Box<dyn std::error::Error>> {
+    let calls = swap_bundle_calls(quotes)?;
+    if calls.is_empty() {
+        return Err("No quotes to bundle".into());
+    }
+
+    Ok(SafeTransaction {
+        to: SAFE_MULTI_SEND_CALL_ONLY_ADDRESS.parse::<Address>()?,
+        // the calls spend the Safe's own balance
+        value: U256::zero(),
+        data: safe_multi_send_calldata(&calls),
+        operation: 1,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn quote(sell_token_address: &str) -> ZeroXQuoteResponse {
+        serde_json::from_value(serde_json::json!({
+            "chainId": 1,
+            "to": "0xdef1c0ded9bec7f1a1670819833240f027b25eff",
+            "data": "0xd9627aa4",
+            "value": "0",
+            "gasPrice": "1000000000",
+            "sellTokenAddress": sell_token_address,
+            "sellAmount": "1000000",
+            "allowanceTarget": "0xdef1c0ded9bec7f1a1670819833240f027b25eff",
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_approve_and_swap_calls() {
+        let usdc = "0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48";
+        let calls = approve_and_swap_calls(&quote(usdc)).unwrap();
+
+        assert_eq!(calls.len(), 2);
+        assert_eq!(calls[0].to, usdc.parse::<Address>().unwrap());
+        assert_eq!(&calls[0].data[..4], &[0x09, 0x5e, 0xa7, 0xb3]);
+        assert_eq!(
+            calls[1].to,
+            "0xdef1c0ded9bec7f1a1670819833240f027b25eff"
+                .parse::<Address>()
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_native_sell_skips_approval() {
+        let calls =
+            approve_and_swap_calls(&quote("0xeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeee")).unwrap();
+
+        assert_eq!(calls.len(), 1);
+    }
+
+    #[test]
+    fn test_swap_bundle_merges_approvals() {
+        let usdc = "0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48";
+        let quotes = [
+            quote(usdc),
+            quote(usdc),
+            quote("0xeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeee"),
+        ];
+
+        let calls = swap_bundle_calls(&quotes).unwrap();
+        assert_eq!(calls.len(), 4);
+        assert_eq!(
+            calls[0].data,
+            approve_calldata(
+                "0xdef1c0ded9bec7f1a1670819833240f027b25eff"
+                    .parse()
+                    .unwrap(),
+                U256::from(2_000_000)
+            )
+        );
+
+        let batch = build_swap_bundle_safe_batch(&quotes).unwrap();
+        assert_eq!(batch.operation, 1);
+        assert_eq!(&batch.data[..4], &id("multiSend(bytes)"));
+        // offset, length, then the first packed call: operation and target
+        assert_eq!(batch.data[68], 0);
+        assert_eq!(&batch.data[69..89], calls[0].to.as_bytes());
+    }
+}
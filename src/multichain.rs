@@ -0,0 +1,144 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use crate::{
+    ZeroXClient, ZeroXClientBuilder, ZeroXClientError, ZeroXQuoteParams, ZeroXQuoteResponse,
+};
+
+/// API keys by chain id, with an optional default for chains without their own key.
+#[derive(Debug, Clone, Default)]
+pub struct ApiKeys {
+    default: Option<String>,
+    per_chain: HashMap<u64, String>,
+}
+
+impl ApiKeys {
+    /// Uses `api_key` for every chain without a chain-specific key.
+    pub fn new(api_key: impl Into<String>) -> ApiKeys {
+        ApiKeys {
+            default: Some(api_key.into()),
+            per_chain: HashMap::new(),
+        }
+    }
+
+    pub fn chain(mut self, chain_id: u64, api_key: impl Into<String>) -> Self {
+        self.per_chain.insert(chain_id, api_key.into());
+        self
+    }
+
+    pub fn get(&self, chain_id: u64) -> Option<&str> {
+        self.per_chain
+            .get(&chain_id)
+            .or(self.default.as_ref())
+            .map(String::as_str)
+    }
+}
+
+type Configure = dyn Fn(ZeroXClientBuilder) -> ZeroXClientBuilder + Send + Sync;
+
+/// Routes requests to a per-chain [`ZeroXClient`], each using the API key configured for its
+/// chain. Clients are created on first use and reused afterwards.
+#[derive(Clone)]
+pub struct MultiChainClient {
+    api_keys: ApiKeys,
+    configure: Arc<Configure>,
+    clients: Arc<Mutex<HashMap<u64, ZeroXClient>>>,
+}
+
+impl MultiChainClient {
+    pub fn new(api_keys: ApiKeys) -> MultiChainClient {
+        MultiChainClient {
+            api_keys,
+            configure: Arc::new(|builder| builder),
+            clients: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Applies `configure` to the builder of every per-chain client, e.g. to set a retry
+    /// policy or rate limit.
+    pub fn configure(
+        mut self,
+        configure: impl Fn(ZeroXClientBuilder) -> ZeroXClientBuilder + Send + Sync + 'static,
+    ) -> Self {
+        self.configure = Arc::new(configure);
+        self
+    }
+
+    /// Returns the client for `chain_id`, creating it with the chain's API key if needed.
+    pub fn client(&self, chain_id: u64) -> Result<ZeroXClient, ZeroXClientError> {
+        let mut clients = self.clients.lock().unwrap();
+        if let Some(client) = clients.get(&chain_id) {
+            return Ok(client.clone());
+        }
+
+        let api_key = self
+            .api_keys
+            .get(chain_id)
+            .ok_or(ZeroXClientError::MissingApiKey(chain_id))?;
+        let client =
+            (self.configure)(ZeroXClient::builder(chain_id, api_key.to_string())).build()?;
+        clients.insert(chain_id, client.clone());
+
+        Ok(client)
+    }
+
+    pub async fn get_quote(
+        &self,
+        chain_id: u64,
+        params: ZeroXQuoteParams,
+    ) -> Result<ZeroXQuoteResponse, ZeroXClientError> {
+        self.client(chain_id)?.get_quote(params).await
+    }
+
+    pub async fn get_price(
+        &self,
+        chain_id: u64,
+        params: ZeroXQuoteParams,
+    ) -> Result<ZeroXQuoteResponse, ZeroXClientError> {
+        self.client(chain_id)?.get_price(params).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::{
+        matchers::{header, method},
+        Mock, MockServer, ResponseTemplate,
+    };
+
+    #[test]
+    fn test_api_keys() {
+        let keys = ApiKeys::new("default").chain(137, "polygon");
+
+        assert_eq!(keys.get(1), Some("default"));
+        assert_eq!(keys.get(137), Some("polygon"));
+        assert_eq!(ApiKeys::default().chain(137, "polygon").get(1), None);
+    }
+
+    #[tokio::test]
+    async fn test_multichain_client_uses_chain_key() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(header("0x-api-key", "polygon"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({})))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let uri = server.uri();
+        let client = MultiChainClient::new(ApiKeys::default().chain(137, "polygon"))
+            .configure(move |builder| builder.base_url(uri.clone()));
+
+        assert!(client
+            .get_quote(137, ZeroXQuoteParams::default())
+            .await
+            .is_ok());
+        assert!(matches!(
+            client.get_quote(1, ZeroXQuoteParams::default()).await,
+            Err(ZeroXClientError::MissingApiKey(1))
+        ));
+    }
+}
@@ -0,0 +1,432 @@
+//! 0x v4 NFT orders (ERC-721 and ERC-1155): order types, EIP-712 signing and the NFT
+//! orderbook.
+
+use std::collections::HashMap;
+
+use ethers::{
+    core::types::{
+        transaction::eip712::{Eip712, Eip712Error, TypedData},
+        U256,
+    },
+    signers::Signer,
+    utils::hex,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use thiserror::Error;
+
+use reqwest::Method;
+
+use crate::{
+    eip712::{exchange_proxy_domain, typed_data, TypedDataError},
+    json_stream::ArrayItems,
+    ApiRequest, ApiVersion, ZeroXClient, ZeroXClientError,
+};
+
+pub const ERC721_ORDER_TYPE: &str = "ERC721Order(uint8 direction,address maker,address taker,uint256 expiry,uint256 nonce,address erc20Token,uint256 erc20TokenAmount,Fee[] fees,address erc721Token,uint256 erc721TokenId,Property[] erc721TokenProperties)Fee(address recipient,uint256 amount,bytes feeData)Property(address propertyValidator,bytes propertyData)";
+pub const ERC1155_ORDER_TYPE: &str = "ERC1155Order(uint8 direction,address maker,address taker,uint256 expiry,uint256 nonce,address erc20Token,uint256 erc20TokenAmount,Fee[] fees,address erc1155Token,uint256 erc1155TokenId,Property[] erc1155TokenProperties,uint128 erc1155TokenAmount)Fee(address recipient,uint256 amount,bytes feeData)Property(address propertyValidator,bytes propertyData)";
+
+/// `signatureType` for EIP-712 signatures.
+const EIP712_SIGNATURE_TYPE: u8 = 2;
+
+#[derive(Error, Debug)]
+pub enum NftOrderError {
+    #[error(transparent)]
+    TypedData(#[from] TypedDataError),
+
+    #[error("Failed to hash NFT order: {0}")]
+    Encode(#[from] Eip712Error),
+
+    #[error("Failed to sign NFT order: {0}")]
+    Signer(Box<dyn std::error::Error + Send + Sync>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "u8", into = "u8")]
+pub enum TradeDirection {
+    /// The maker sells the NFT for ERC-20 tokens.
+    SellNft,
+    /// The maker buys the NFT with ERC-20 tokens.
+    BuyNft,
+}
+
+impl From<TradeDirection> for u8 {
+    fn from(direction: TradeDirection) -> u8 {
+        match direction {
+            TradeDirection::SellNft => 0,
+            TradeDirection::BuyNft => 1,
+        }
+    }
+}
+
+impl TryFrom<u8> for TradeDirection {
+    type Error = String;
+
+    fn try_from(direction: u8) -> Result<Self, Self::Error> {
+        match direction {
+            0 => Ok(TradeDirection::SellNft),
+            1 => Ok(TradeDirection::BuyNft),
+            _ => Err(format!("invalid trade direction {}", direction)),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct NftFee {
+    pub recipient: String,
+    pub amount: String,
+    pub fee_data: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct NftProperty {
+    pub property_validator: String,
+    pub property_data: String,
+}
+
+/// The NFT side of an order.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum NftAsset {
+    #[serde(rename_all = "camelCase")]
+    Erc721 {
+        erc721_token: String,
+        erc721_token_id: String,
+        #[serde(default)]
+        erc721_token_properties: Vec<NftProperty>,
+    },
+    #[serde(rename_all = "camelCase")]
+    Erc1155 {
+        erc1155_token: String,
+        erc1155_token_id: String,
+        #[serde(default)]
+        erc1155_token_properties: Vec<NftProperty>,
+        erc1155_token_amount: String,
+    },
+}
+
+/// An unsigned NFT order. Amounts, ids, `expiry` and `nonce` are decimal strings.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct NftOrder {
+    pub direction: TradeDirection,
+    pub maker: String,
+    pub taker: String,
+    pub expiry: String,
+    pub nonce: String,
+    pub erc20_token: String,
+    pub erc20_token_amount: String,
+    #[serde(default)]
+    pub fees: Vec<NftFee>,
+    #[serde(flatten)]
+    pub nft: NftAsset,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct NftSignature {
+    pub signature_type: u8,
+    pub v: u8,
+    pub r: String,
+    pub s: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct SignedNftOrder {
+    pub order: NftOrder,
+    pub signature: NftSignature,
+}
+
+impl NftOrder {
+    /// EIP-712 typed data for signing this order on `chain_id`.
+    pub fn typed_data(&self, chain_id: u64) -> Result<TypedData, TypedDataError> {
+        let domain =
+            exchange_proxy_domain(chain_id).ok_or(TypedDataError::UnsupportedChain(chain_id))?;
+        let encoded_type = match self.nft {
+            NftAsset::Erc721 { .. } => ERC721_ORDER_TYPE,
+            NftAsset::Erc1155 { .. } => ERC1155_ORDER_TYPE,
+        };
+
+        typed_data(
+            domain,
+            encoded_type,
+            serde_json::to_value(self).expect("NFT orders serialize"),
+        )
+    }
+
+    /// Signs the order with `signer` as an EIP-712 signature.
+    pub async fn sign<S: Signer>(
+        self,
+        chain_id: u64,
+        signer: &S,
+    ) -> Result<SignedNftOrder, NftOrderError>
+    where
+        S::Error: 'static,
+    {
+        let typed_data = self.typed_data(chain_id)?;
+        let signature = signer
+            .sign_typed_data(&typed_data)
+            .await
+            .map_err(|err| NftOrderError::Signer(Box::new(err)))?;
+
+        let word = |value: U256| {
+            let mut bytes = [0u8; 32];
+            value.to_big_endian(&mut bytes);
+            format!("0x{}", hex::encode(bytes))
+        };
+
+        Ok(SignedNftOrder {
+            order: self,
+            signature: NftSignature {
+                signature_type: EIP712_SIGNATURE_TYPE,
+                v: signature.v as u8,
+                r: word(signature.r),
+                s: word(signature.s),
+            },
+        })
+    }
+
+    /// The EIP-712 hash of the order, which the Exchange Proxy uses as the order hash.
+    pub fn order_hash(&self, chain_id: u64) -> Result<[u8; 32], NftOrderError> {
+        Ok(self.typed_data(chain_id)?.encode_eip712()?)
+    }
+}
+
+/// Filters for [`ZeroXClient::get_nft_orders`].
+#[derive(Debug, Clone, Default)]
+pub struct NftOrdersQuery {
+    pub nft_token: Option<String>,
+    pub nft_token_id: Option<String>,
+    pub maker: Option<String>,
+    pub direction: Option<TradeDirection>,
+    pub erc20_token: Option<String>,
+    pub status: Option<String>,
+}
+
+impl NftOrdersQuery {
+    fn into_query(self) -> HashMap<&'static str, String> {
+        let mut query = HashMap::new();
+        let mut insert = |key, value: Option<String>| {
+            if let Some(value) = value {
+                query.insert(key, value);
+            }
+        };
+
+        insert("nft_token", self.nft_token);
+        insert("nft_token_id", self.nft_token_id);
+        insert("maker", self.maker);
+        insert(
+            "sell_or_buy_nft",
+            self.direction.map(|direction| match direction {
+                TradeDirection::SellNft => String::from("sell"),
+                TradeDirection::BuyNft => String::from("buy"),
+            }),
+        );
+        insert("erc20_token", self.erc20_token);
+        insert("status", self.status);
+
+        query
+    }
+}
+
+/// An order as listed by the NFT orderbook.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct NftOrderRecord {
+    pub order: NftOrder,
+    pub signature: NftSignature,
+    #[serde(default)]
+    pub order_status: Option<Value>,
+}
+
+#[derive(Deserialize)]
+struct NftOrdersResponse {
+    orders: Vec<NftOrderRecord>,
+}
+
+/// The orders of an orderbook page, decoded one at a time as the response body arrives.
+pub struct NftOrderStream {
+    resp: Option<reqwest::Response>,
+    items: ArrayItems,
+}
+
+impl NftOrderStream {
+    /// The next order, or `None` once the page is exhausted.
+    pub async fn next(&mut self) -> Option<Result<NftOrderRecord, ZeroXClientError>> {
+        loop {
+            if let Some(item) = self.items.next() {
+                return Some(item.map_err(ZeroXClientError::from));
+            }
+
+            match self.resp.as_mut()?.chunk().await {
+                Ok(Some(chunk)) => self.items.push(&chunk),
+                Ok(None) => {
+                    self.resp = None;
+                    return self.items.finish().err().map(|err| Err(err.into()));
+                }
+                Err(err) => {
+                    self.resp = None;
+                    return Some(Err(err.into()));
+                }
+            }
+        }
+    }
+}
+
+impl ZeroXClient {
+    pub async fn get_nft_orders(
+        &self,
+        query: NftOrdersQuery,
+    ) -> Result<Vec<NftOrderRecord>, ZeroXClientError> {
+        Ok(self
+            .get::<NftOrdersResponse>("/orderbook/v1/nft/orders", &query.into_query())
+            .await?
+            .data
+            .orders)
+    }
+
+    /// Like [`get_nft_orders`](Self::get_nft_orders), but yields orders as they are parsed so
+    /// that memory stays flat however large the page is. The request is sent once, without
+    /// retries, fallback or hedging, and is not subject to the response size limit.
+    pub async fn stream_nft_orders(
+        &self,
+        query: NftOrdersQuery,
+    ) -> Result<NftOrderStream, ZeroXClientError> {
+        let query = query.into_query();
+        let request = ApiRequest {
+            method: Method::GET,
+            version: ApiVersion::V1,
+            path: "/orderbook/v1/nft/orders",
+            query: &query,
+            body: None,
+        };
+        let (resp, _) = self
+            .open(&request, &self.base_url(), &self.api_key())
+            .await?;
+
+        Ok(NftOrderStream {
+            resp: Some(resp),
+            items: ArrayItems::new("orders"),
+        })
+    }
+
+    /// Submits a signed order to the NFT orderbook and returns the stored record.
+    pub async fn post_nft_order(&self, order: &SignedNftOrder) -> Result<Value, ZeroXClientError> {
+        let mut body = serde_json::to_value(order)?;
+        body["chainId"] = Value::from(self.chain_id().to_string());
+
+        Ok(self.post("/orderbook/v1/nft/order", &body).await?.data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ethers::{core::types::Signature, signers::LocalWallet};
+    use wiremock::{
+        matchers::{body_partial_json, method, path, query_param},
+        Mock, MockServer, ResponseTemplate,
+    };
+
+    use super::*;
+
+    fn order() -> NftOrder {
+        NftOrder {
+            direction: TradeDirection::SellNft,
+            maker: String::from("0xd8da6bf26964af9d7eed9e03e53415d37aa96045"),
+            taker: String::from("0x0000000000000000000000000000000000000000"),
+            expiry: String::from("1700000000"),
+            nonce: String::from("1"),
+            erc20_token: String::from("0xeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeee"),
+            erc20_token_amount: String::from("1000000000000000000"),
+            fees: vec![],
+            nft: NftAsset::Erc721 {
+                erc721_token: String::from("0xbc4ca0eda7647a8ab7c2061c2e118a18a936f13d"),
+                erc721_token_id: String::from("1"),
+                erc721_token_properties: vec![],
+            },
+        }
+    }
+
+    #[tokio::test]
+    async fn test_sign_nft_order() {
+        let wallet: LocalWallet =
+            "0x0123456789012345678901234567890123456789012345678901234567890123"
+                .parse()
+                .unwrap();
+
+        let signed = order().sign(1, &wallet).await.unwrap();
+        assert_eq!(signed.signature.signature_type, 2);
+
+        let signature = Signature {
+            r: U256::from_str_radix(&signed.signature.r, 16).unwrap(),
+            s: U256::from_str_radix(&signed.signature.s, 16).unwrap(),
+            v: signed.signature.v.into(),
+        };
+        let hash = signed.order.order_hash(1).unwrap();
+        assert_eq!(signature.recover(hash).unwrap(), wallet.address());
+
+        let json = serde_json::to_value(&signed.order).unwrap();
+        assert_eq!(json["direction"], 0);
+        assert_eq!(json["erc721TokenId"], "1");
+        assert_eq!(
+            serde_json::from_value::<NftOrder>(json).unwrap(),
+            signed.order
+        );
+    }
+
+    #[tokio::test]
+    async fn test_orderbook() {
+        let wallet: LocalWallet =
+            "0x0123456789012345678901234567890123456789012345678901234567890123"
+                .parse()
+                .unwrap();
+        let signed = order().sign(1, &wallet).await.unwrap();
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/orderbook/v1/nft/orders"))
+            .and(query_param("sell_or_buy_nft", "sell"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(serde_json::json!({ "orders": [&signed] })),
+            )
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/orderbook/v1/nft/order"))
+            .and(body_partial_json(serde_json::json!({ "chainId": "1" })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({})))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = ZeroXClient::builder(1, String::from("test"))
+            .base_url(server.uri())
+            .build()
+            .unwrap();
+
+        let orders = client
+            .get_nft_orders(NftOrdersQuery {
+                direction: Some(TradeDirection::SellNft),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(orders[0].order, signed.order);
+
+        let mut stream = client
+            .stream_nft_orders(NftOrdersQuery {
+                direction: Some(TradeDirection::SellNft),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(stream.next().await.unwrap().unwrap().order, signed.order);
+        assert!(stream.next().await.is_none());
+
+        client.post_nft_order(&signed).await.unwrap();
+    }
+}
@@ -0,0 +1,333 @@
+use std::{
+    collections::HashMap,
+    str::FromStr,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use async_trait::async_trait;
+use ethers::core::types::{Address, Chain, U256};
+use rust_decimal::Decimal;
+
+use crate::{tokens, Amount, ZeroXClient, ZeroXQuoteParams};
+
+/// Minimal pricing interface for consumers that only need prices, not the full client.
+#[async_trait]
+pub trait PriceOracle: Send + Sync {
+    /// Price of one whole `base` token denominated in `quote` tokens.
+    async fn price(
+        &self,
+        base: Address,
+        quote: Address,
+    ) -> Result<Decimal, Box<dyn std::error::Error + Send + Sync>>;
+}
+
+/// [`PriceOracle`] backed by the 0x price endpoint, caching each pair for `ttl`.
+///
+/// Prices are quoted for one whole base token, so the base token's decimals must be known:
+/// tokens in the [`tokens`] registry for the client's chain are known, others are added
+/// with [`ZeroXPriceOracle::token`].
+pub struct ZeroXPriceOracle {
+    client: Arc<ZeroXClient>,
+    ttl: Duration,
+    decimals: HashMap<Address, u8>,
+    cache: Mutex<HashMap<(Address, Address), (Decimal, Instant)>>,
+    usd_cache: Mutex<HashMap<(Address, Decimal), (Decimal, Instant)>>,
+}
+
+impl ZeroXPriceOracle {
+    pub fn new(client: Arc<ZeroXClient>, ttl: Duration) -> ZeroXPriceOracle {
+        let decimals = Chain::try_from(client.chain_id())
+            .map(tokens::all)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|token| (token.address, token.decimals))
+            .collect();
+
+        ZeroXPriceOracle {
+            client,
+            ttl,
+            decimals,
+            cache: Mutex::new(HashMap::new()),
+            usd_cache: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Registers the decimals of a token that is not in the built-in registry.
+    pub fn token(mut self, address: Address, decimals: u8) -> Self {
+        self.decimals.insert(address, decimals);
+        self
+    }
+
+    fn cached(&self, pair: (Address, Address)) -> Option<Decimal> {
+        self.cache
+            .lock()
+            .unwrap()
+            .get(&pair)
+            .filter(|(_, fetched_at)| fetched_at.elapsed() < self.ttl)
+            .map(|(price, _)| *price)
+    }
+
+    fn decimals(&self, token: Address) -> Result<u8, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(*self
+            .decimals
+            .get(&token)
+            .ok_or_else(|| format!("Unknown decimals for {:?}", token))?)
+    }
+
+    /// Whole `buy` tokens received for selling `amount` whole `sell` tokens.
+    async fn sell_probe(
+        &self,
+        sell: Address,
+        buy: Address,
+        amount: Decimal,
+    ) -> Result<Decimal, Box<dyn std::error::Error + Send + Sync>> {
+        let sell_amount = Amount::parse(&amount.to_string(), self.decimals(sell)?)?;
+        if sell_amount.as_u256().is_zero() {
+            return Err(format!("Probe size {} rounds to zero", amount).into());
+        }
+
+        let response = self
+            .client
+            .get_price(ZeroXQuoteParams {
+                sell_token: format!("{:?}", sell),
+                buy_token: format!("{:?}", buy),
+                sell_amount: sell_amount.to_string(),
+                ..Default::default()
+            })
+            .await?;
+
+        let mut bought = Decimal::from_str(
+            response
+                .buy_amount
+                .as_deref()
+                .ok_or("Missing 'buy_amount' field")?,
+        )?;
+        bought.set_scale(self.decimals(buy)? as u32)?;
+        Ok(bought)
+    }
+
+    /// `token_b` per whole `token_a`, averaging the rate of selling `probe_size` whole `token_a`
+    /// with the inverse of selling the `token_b` that probe returned. Averaging both directions
+    /// cancels most of the spread, giving a mid rate rather than an execution price.
+    pub async fn exchange_rate(
+        &self,
+        token_a: Address,
+        token_b: Address,
+        probe_size: Decimal,
+    ) -> Result<Decimal, Box<dyn std::error::Error + Send + Sync>> {
+        if token_a == token_b {
+            return Ok(Decimal::ONE);
+        }
+
+        let no_liquidity = || format!("No liquidity between {:?} and {:?}", token_a, token_b);
+        let bought_b = self.sell_probe(token_a, token_b, probe_size).await?;
+        if bought_b.is_zero() {
+            return Err(no_liquidity().into());
+        }
+        let bought_a = self.sell_probe(token_b, token_a, bought_b).await?;
+        if bought_a.is_zero() {
+            return Err(no_liquidity().into());
+        }
+
+        let direct = bought_b / probe_size;
+        let inverse = bought_b / bought_a;
+        Ok((direct + inverse) / Decimal::TWO)
+    }
+
+    /// USD price of one whole `token`, from selling `probe_size` whole tokens for the chain's
+    /// USDC.
+    ///
+    /// The price is derived from the quoted buy amount rather than 0x's `price` field, so it
+    /// reflects the execution price at that size: pick a probe large enough to avoid dust
+    /// rounding and small enough to avoid price impact.
+    pub async fn usd_price(
+        &self,
+        token: Address,
+        probe_size: Decimal,
+    ) -> Result<Decimal, Box<dyn std::error::Error + Send + Sync>> {
+        let chain = Chain::try_from(self.client.chain_id())?;
+        let usdc = tokens::usdc(chain)
+            .ok_or_else(|| format!("No USDC known on chain {}", self.client.chain_id()))?;
+        if token == usdc.address {
+            return Ok(Decimal::ONE);
+        }
+
+        let cached = self
+            .usd_cache
+            .lock()
+            .unwrap()
+            .get(&(token, probe_size))
+            .filter(|(_, fetched_at)| fetched_at.elapsed() < self.ttl)
+            .map(|(price, _)| *price);
+        if let Some(price) = cached {
+            return Ok(price);
+        }
+
+        let price = self.sell_probe(token, usdc.address, probe_size).await? / probe_size;
+
+        self.usd_cache
+            .lock()
+            .unwrap()
+            .insert((token, probe_size), (price, Instant::now()));
+
+        Ok(price)
+    }
+}
+
+#[async_trait]
+impl PriceOracle for ZeroXPriceOracle {
+    async fn price(
+        &self,
+        base: Address,
+        quote: Address,
+    ) -> Result<Decimal, Box<dyn std::error::Error + Send + Sync>> {
+        if let Some(price) = self.cached((base, quote)) {
+            return Ok(price);
+        }
+
+        let decimals = self.decimals(base)?;
+
+        let response = self
+            .client
+            .get_price(ZeroXQuoteParams {
+                sell_token: format!("{:?}", base),
+                buy_token: format!("{:?}", quote),
+                sell_amount: U256::exp10(decimals as usize).to_string(),
+                ..Default::default()
+            })
+            .await?;
+
+        let price = Decimal::from_str(response.price.as_deref().ok_or("Missing 'price' field")?)?;
+
+        self.cache
+            .lock()
+            .unwrap()
+            .insert((base, quote), (price, Instant::now()));
+
+        Ok(price)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::{
+        matchers::{method, path, query_param},
+        Mock, MockServer, ResponseTemplate,
+    };
+
+    #[tokio::test]
+    async fn test_price_oracle_caches_prices() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/swap/v1/price"))
+            .and(query_param("sellAmount", "1000000000000000000"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(serde_json::json!({ "price": "2500.5" })),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = ZeroXClient::builder(1, String::from("test"))
+            .base_url(server.uri())
+            .build()
+            .unwrap();
+        let oracle = ZeroXPriceOracle::new(Arc::new(client), Duration::from_secs(60));
+
+        let weth = tokens::weth(Chain::Mainnet).unwrap().address;
+        let usdc = tokens::usdc(Chain::Mainnet).unwrap().address;
+
+        assert_eq!(
+            oracle.price(weth, usdc).await.unwrap(),
+            Decimal::from_str("2500.5").unwrap()
+        );
+        assert_eq!(
+            oracle.price(weth, usdc).await.unwrap(),
+            Decimal::from_str("2500.5").unwrap()
+        );
+        assert!(oracle.price(Address::random(), usdc).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_usd_price_from_probe() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/swap/v1/price"))
+            .and(query_param("sellAmount", "10000000"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(serde_json::json!({ "buyAmount": "6012345678" })),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = ZeroXClient::builder(1, String::from("test"))
+            .base_url(server.uri())
+            .build()
+            .unwrap();
+        let oracle = ZeroXPriceOracle::new(Arc::new(client), Duration::from_secs(60));
+
+        let wbtc = tokens::wbtc(Chain::Mainnet).unwrap().address;
+        let usdc = tokens::usdc(Chain::Mainnet).unwrap().address;
+        let probe = Decimal::from_str("0.1").unwrap();
+
+        for _ in 0..2 {
+            assert_eq!(
+                oracle.usd_price(wbtc, probe).await.unwrap(),
+                Decimal::from_str("60123.45678").unwrap()
+            );
+        }
+        assert_eq!(oracle.usd_price(usdc, probe).await.unwrap(), Decimal::ONE);
+        assert!(oracle.usd_price(wbtc, Decimal::ZERO).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_exchange_rate_averages_both_directions() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/swap/v1/price"))
+            .and(query_param("sellAmount", "1000000000000000000"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(serde_json::json!({ "buyAmount": "2000000000" })),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/swap/v1/price"))
+            .and(query_param("sellAmount", "2000000000"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(serde_json::json!({ "buyAmount": "800000000000000000" })),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = ZeroXClient::builder(1, String::from("test"))
+            .base_url(server.uri())
+            .build()
+            .unwrap();
+        let oracle = ZeroXPriceOracle::new(Arc::new(client), Duration::from_secs(60));
+
+        let weth = tokens::weth(Chain::Mainnet).unwrap().address;
+        let usdc = tokens::usdc(Chain::Mainnet).unwrap().address;
+
+        // 2000 USDC per WETH one way, 2500 the other.
+        assert_eq!(
+            oracle
+                .exchange_rate(weth, usdc, Decimal::ONE)
+                .await
+                .unwrap(),
+            Decimal::from(2250)
+        );
+        assert!(oracle
+            .exchange_rate(Address::random(), usdc, Decimal::ONE)
+            .await
+            .is_err());
+    }
+}
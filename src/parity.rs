@@ -0,0 +1,202 @@
+//! Fetching the same swap from the v1 and v2 APIs side by side, to check parity while
+//! migrating.
+
+use std::{collections::BTreeSet, str::FromStr};
+
+use ethers::core::types::U256;
+use rust_decimal::Decimal;
+
+use crate::{
+    QuoteFieldError, ZeroXClient, ZeroXClientError, ZeroXQuoteParamsV2, ZeroXQuoteResponse,
+    ZeroXQuoteResponseV2,
+};
+
+/// Returned by [`ZeroXClient::get_quote_both_versions`]. Either request may fail without the
+/// other.
+#[derive(Debug)]
+pub struct VersionComparison {
+    pub v1: Result<ZeroXQuoteResponse, ZeroXClientError>,
+    pub v2: Result<ZeroXQuoteResponseV2, ZeroXClientError>,
+    /// `None` unless both succeeded with buy and sell amounts.
+    pub diff: Option<VersionDiff>,
+}
+
+/// How the v2 quote differs from the v1 quote for the same swap.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VersionDiff {
+    pub v1_buy_amount: U256,
+    pub v2_buy_amount: U256,
+    /// How much more v2 buys per unit sold, in basis points; negative if it buys less.
+    pub price_diff_bps: i64,
+    pub v1_gas: Option<U256>,
+    pub v2_gas: Option<U256>,
+    /// Sources with a non-zero share of the route.
+    pub v1_sources: BTreeSet<String>,
+    pub v2_sources: BTreeSet<String>,
+}
+
+impl VersionDiff {
+    pub fn between(
+        v1: &ZeroXQuoteResponse,
+        v2: &ZeroXQuoteResponseV2,
+    ) -> Result<VersionDiff, QuoteFieldError> {
+        let (v1_buy_amount, v1_sell_amount) = (v1.buy_amount()?, v1.sell_amount()?);
+        let (v2_buy_amount, v2_sell_amount) = (v2.buy_amount()?, v2.sell_amount()?);
+
+        let v1_sources = v1
+            .sources
+            .iter()
+            .flatten()
+            .filter(|source| {
+                source
+                    .proportion
+                    .as_deref()
+                    .and_then(|proportion| Decimal::from_str(proportion).ok())
+                    .is_some_and(|proportion| proportion > Decimal::ZERO)
+            })
+            .filter_map(|source| source.name.clone())
+            .collect();
+        let v2_sources = v2
+            .route
+            .iter()
+            .flat_map(|route| route.fills.iter().flatten())
+            .filter_map(|fill| fill.source.clone())
+            .collect();
+
+        Ok(VersionDiff {
+            v1_buy_amount,
+            v2_buy_amount,
+            price_diff_bps: rate_diff_bps(
+                (v1_buy_amount, v1_sell_amount),
+                (v2_buy_amount, v2_sell_amount),
+            ),
+            v1_gas: v1.gas().ok(),
+            v2_gas: v2.gas().ok(),
+            v1_sources,
+            v2_sources,
+        })
+    }
+
+    /// Whether both versions route through the same sources.
+    pub fn same_route(&self) -> bool {
+        self.v1_sources == self.v2_sources
+    }
+
+    /// v2's gas estimate less v1's, if both have one.
+    pub fn gas_diff(&self) -> Option<i128> {
+        let (v1, v2) = (self.v1_gas?, self.v2_gas?);
+        Some(v2.low_u128() as i128 - v1.low_u128() as i128)
+    }
+}
+
+/// The difference between two `(buy, sell)` rates, in basis points of the first, saturating
+/// at `i64` bounds.
+fn rate_diff_bps((buy_1, sell_1): (U256, U256), (buy_2, sell_2): (U256, U256)) -> i64 {
+    // buy_2 / sell_2 against buy_1 / sell_1, cross-multiplied to stay in integers.
+    let new = buy_2.saturating_mul(sell_1);
+    let old = buy_1.saturating_mul(sell_2);
+    if old.is_zero() {
+        return 0;
+    }
+    let bps = |delta: U256| {
+        let bps = delta.saturating_mul(U256::from(10_000)) / old;
+        bps.min(U256::from(i64::MAX)).as_u64() as i64
+    };
+    if new >= old {
+        bps(new - old)
+    } else {
+        -bps(old - new)
+    }
+}
+
+impl ZeroXClient {
+    /// Fetches the swap from the v1 and v2 quote endpoints concurrently and diffs the results,
+    /// for shadowing v1 traffic with v2 during a migration.
+    ///
+    /// Returns `None` if `params` cannot be expressed in v1, see
+    /// [`ZeroXQuoteParamsV2::to_v1`].
+    pub async fn get_quote_both_versions(
+        &self,
+        params: ZeroXQuoteParamsV2,
+    ) -> Option<VersionComparison> {
+        let v1_params = params.to_v1()?;
+        let (v1, v2) = tokio::join!(self.get_quote(v1_params), self.get_quote_v2(params));
+        let diff = match (&v1, &v2) {
+            (Ok(v1), Ok(v2)) => VersionDiff::between(v1, v2).ok(),
+            _ => None,
+        };
+
+        Some(VersionComparison { v1, v2, diff })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use wiremock::{
+        matchers::{method, path},
+        Mock, MockServer, ResponseTemplate,
+    };
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_get_quote_both_versions() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/swap/v1/quote"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "buyAmount": "2000000000",
+                "sellAmount": "1000000000000000000",
+                "gas": "180000",
+                "sources": [
+                    { "name": "Uniswap_V3", "proportion": "1" },
+                    { "name": "Curve", "proportion": "0" },
+                ],
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/swap/allowance-holder/quote"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "buyAmount": "2002000000",
+                "sellAmount": "1000000000000000000",
+                "route": { "fills": [
+                    { "source": "Uniswap_V3", "proportionBps": "6000" },
+                    { "source": "Curve", "proportionBps": "4000" },
+                ] },
+                "transaction": { "gas": "200000" },
+            })))
+            .mount(&server)
+            .await;
+
+        let client = ZeroXClient::builder(1, String::from("key"))
+            .base_url(server.uri())
+            .v2_base_url(server.uri())
+            .build()
+            .unwrap();
+        let comparison = client
+            .get_quote_both_versions(ZeroXQuoteParamsV2 {
+                sell_token: String::from("WETH"),
+                buy_token: String::from("USDC"),
+                sell_amount: String::from("1000000000000000000"),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+
+        let diff = comparison.diff.unwrap();
+        assert_eq!(diff.price_diff_bps, 10);
+        assert_eq!(diff.gas_diff(), Some(20_000));
+        assert!(!diff.same_route());
+        assert_eq!(
+            diff.v2_sources
+                .difference(&diff.v1_sources)
+                .collect::<Vec<_>>(),
+            ["Curve"]
+        );
+        assert_eq!(
+            rate_diff_bps((2.into(), 1.into()), (1.into(), 1.into())),
+            -5_000
+        );
+    }
+}
@@ -0,0 +1,291 @@
+//! EIP-2612 permits: a signed approval of the quote's `allowance_target`, submitted with
+//! the token's `permit` function instead of a separate `approve` from the taker.
+
+use ethers::{
+    abi::{self, ParamType, Token},
+    core::types::{
+        transaction::eip712::{EIP712Domain, Eip712, Eip712Error, TypedData},
+        Address, Bytes, Signature, TransactionRequest, U256,
+    },
+    providers::Middleware,
+    signers::Signer,
+    utils::id,
+};
+use thiserror::Error;
+
+use crate::{
+    approval::sells_native_token, eip712::typed_data, multicall::Call, ToTransactionRequest,
+    ZeroXQuoteResponse,
+};
+
+pub const PERMIT_TYPE: &str =
+    "Permit(address owner,address spender,uint256 value,uint256 nonce,uint256 deadline)";
+
+#[derive(Error, Debug)]
+pub enum PermitError {
+    #[error("Invalid quote: {0}")]
+    InvalidQuote(String),
+
+    /// Calling the token's `nonces`, `name` or `DOMAIN_SEPARATOR` failed, or its domain is not
+    /// the standard one.
+    #[error("Token {0:?} does not support EIP-2612 permits")]
+    Unsupported(Address),
+
+    #[error("Failed to hash permit: {0}")]
+    Encode(#[from] Eip712Error),
+
+    #[error("Failed to sign permit: {0}")]
+    Signer(Box<dyn std::error::Error + Send + Sync>),
+}
+
+/// The fields of an EIP-2612 `Permit` message.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Permit {
+    pub token: Address,
+    pub owner: Address,
+    pub spender: Address,
+    pub value: U256,
+    pub nonce: U256,
+    /// Unix timestamp in seconds after which the permit is rejected.
+    pub deadline: U256,
+}
+
+impl Permit {
+    /// Typed data for the permit under the token's domain, which is named after the token
+    /// and usually has version `"1"`.
+    pub fn typed_data(&self, chain_id: u64, name: &str, version: &str) -> TypedData {
+        let domain = EIP712Domain {
+            name: Some(name.to_string()),
+            version: Some(version.to_string()),
+            chain_id: Some(chain_id.into()),
+            verifying_contract: Some(self.token),
+            salt: None,
+        };
+        let message = serde_json::json!({
+            "owner": self.owner,
+            "spender": self.spender,
+            "value": self.value.to_string(),
+            "nonce": self.nonce.to_string(),
+            "deadline": self.deadline.to_string(),
+        });
+
+        typed_data(domain, PERMIT_TYPE, message).expect("permit message is an object")
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SignedPermit {
+    pub permit: Permit,
+    pub signature: Signature,
+}
+
+impl SignedPermit {
+    /// Calldata for `permit(owner, spender, value, deadline, v, r, s)`.
+    pub fn calldata(&self) -> Bytes {
+        let word = |value: U256| {
+            let mut bytes = [0u8; 32];
+            value.to_big_endian(&mut bytes);
+            Token::FixedBytes(bytes.to_vec())
+        };
+
+        let mut data = id("permit(address,address,uint256,uint256,uint8,bytes32,bytes32)").to_vec();
+        data.extend(abi::encode(&[
+            Token::Address(self.permit.owner),
+            Token::Address(self.permit.spender),
+            Token::Uint(self.permit.value),
+            Token::Uint(self.permit.deadline),
+            Token::Uint(self.signature.v.into()),
+            word(self.signature.r),
+            word(self.signature.s),
+        ]));
+        data.into()
+    }
+
+    /// The `permit` call. It is authorized by the signature alone, so any account can send it,
+    /// e.g. a relayer, or the taker's smart account ahead of the swap.
+    pub fn to_call(&self) -> Call {
+        Call {
+            to: self.permit.token,
+            value: U256::zero(),
+            data: self.calldata(),
+        }
+    }
+
+    pub fn to_transaction_request(&self, chain_id: u64) -> TransactionRequest {
+        TransactionRequest::new()
+            .to(self.permit.token)
+            .data(self.calldata())
+            .chain_id(chain_id)
+    }
+}
+
+/// Reads the taker's permit nonce and the token's domain, checks the domain matches the token's
+/// `DOMAIN_SEPARATOR`, and signs a permit of the quote's `sell_amount` to its
+/// `allowance_target` with `signer`, which must be the taker.
+pub async fn sign_permit<M, S>(
+    provider: &M,
+    signer: &S,
+    quote: &ZeroXQuoteResponse,
+    deadline: U256,
+) -> Result<SignedPermit, PermitError>
+where
+    M: Middleware + 'static,
+    S: Signer,
+    S::Error: 'static,
+{
+    if sells_native_token(quote) {
+        return Err(PermitError::InvalidQuote(String::from(
+            "the native token needs no approval",
+        )));
+    }
+    let address = |field: Option<&String>, name: &str| {
+        field
+            .ok_or_else(|| PermitError::InvalidQuote(format!("missing '{name}' field")))?
+            .parse::<Address>()
+            .map_err(|err| PermitError::InvalidQuote(format!("invalid '{name}': {err}")))
+    };
+    let token = address(quote.sell_token_address.as_ref(), "sell_token_address")?;
+    let spender = address(quote.allowance_target.as_ref(), "allowance_target")?;
+    let value = quote
+        .sell_amount()
+        .map_err(|err| PermitError::InvalidQuote(err.to_string()))?;
+    let chain_id = quote
+        .chain_id
+        .and_then(|chain_id| u64::try_from(chain_id).ok())
+        .ok_or_else(|| PermitError::InvalidQuote(String::from("missing 'chain_id' field")))?;
+    let owner = signer.address();
+
+    let call = |signature: &str, args: &[Token]| {
+        let mut data = id(signature).to_vec();
+        data.extend(abi::encode(args));
+        let tx = TransactionRequest::new().to(token).data(data).into();
+        async move { provider.call(&tx, None).await }
+    };
+    let unsupported = |_| PermitError::Unsupported(token);
+
+    let nonce = call("nonces(address)", &[Token::Address(owner)])
+        .await
+        .map_err(unsupported)?;
+    let name = call("name()", &[]).await.map_err(unsupported)?;
+    // Tokens without `version()` use "1".
+    let version = call("version()", &[]).await.ok();
+    let separator = call("DOMAIN_SEPARATOR()", &[]).await.map_err(unsupported)?;
+
+    let decode_string = |bytes: &Bytes| match abi::decode(&[ParamType::String], bytes) {
+        Ok(tokens) => tokens.into_iter().next().and_then(Token::into_string),
+        Err(_) => None,
+    };
+    let name = decode_string(&name).ok_or(PermitError::Unsupported(token))?;
+    let version = version
+        .as_ref()
+        .and_then(decode_string)
+        .unwrap_or_else(|| String::from("1"));
+    if nonce.len() < 32 || separator.len() < 32 {
+        return Err(PermitError::Unsupported(token));
+    }
+
+    let permit = Permit {
+        token,
+        owner,
+        spender,
+        value,
+        nonce: U256::from_big_endian(&nonce[..32]),
+        deadline,
+    };
+    let typed_data = permit.typed_data(chain_id, &name, &version);
+    if typed_data.domain_separator()? != separator[..32] {
+        return Err(PermitError::Unsupported(token));
+    }
+
+    let signature = signer
+        .sign_typed_data(&typed_data)
+        .await
+        .map_err(|err| PermitError::Signer(Box::new(err)))?;
+
+    Ok(SignedPermit { permit, signature })
+}
+
+/// The `permit` call followed by the swap, for a smart account to batch in place of
+/// [`approve_and_swap_calls`](crate::multicall::approve_and_swap_calls).
+pub fn permit_and_swap_calls(
+    permit: &SignedPermit,
+    quote: &ZeroXQuoteResponse,
+) -> Result<Vec<Call>, Box<dyn std::error::Error>> {
+    Ok(vec![
+        permit.to_call(),
+        Call::try_from(&quote.to_transaction_request()?)?,
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use ethers::{providers::Provider, signers::LocalWallet};
+
+    use super::*;
+
+    static USDC: &str = "0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48";
+    static EXCHANGE_PROXY: &str = "0xdef1c0ded9bec7f1a1670819833240f027b25eff";
+
+    #[tokio::test]
+    async fn test_sign_permit() {
+        let wallet: LocalWallet =
+            "4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318"
+                .parse()
+                .unwrap();
+        let quote: ZeroXQuoteResponse = serde_json::from_value(serde_json::json!({
+            "chainId": 1,
+            "to": EXCHANGE_PROXY,
+            "data": "0xd9627aa4",
+            "value": "0",
+            "gasPrice": "1000000000",
+            "sellTokenAddress": USDC,
+            "sellAmount": "1000000",
+            "allowanceTarget": EXCHANGE_PROXY,
+        }))
+        .unwrap();
+
+        let token = USDC.parse::<Address>().unwrap();
+        let separator = Permit {
+            token,
+            owner: wallet.address(),
+            spender: Address::zero(),
+            value: U256::zero(),
+            nonce: U256::zero(),
+            deadline: U256::zero(),
+        }
+        .typed_data(1, "USD Coin", "2")
+        .domain_separator()
+        .unwrap();
+
+        // Responses are served last pushed first.
+        let (provider, mock) = Provider::mocked();
+        let encode = |token: Token| Bytes::from(abi::encode(&[token]));
+        mock.push::<Bytes, _>(encode(Token::FixedBytes(separator.to_vec())))
+            .unwrap();
+        mock.push::<Bytes, _>(encode(Token::String(String::from("2"))))
+            .unwrap();
+        mock.push::<Bytes, _>(encode(Token::String(String::from("USD Coin"))))
+            .unwrap();
+        mock.push::<Bytes, _>(encode(Token::Uint(U256::from(3))))
+            .unwrap();
+
+        let signed = sign_permit(&provider, &wallet, &quote, U256::from(1_700_000_000))
+            .await
+            .unwrap();
+        assert_eq!(signed.permit.nonce, U256::from(3));
+        assert_eq!(signed.permit.value, U256::from(1_000_000));
+        let hash = signed
+            .permit
+            .typed_data(1, "USD Coin", "2")
+            .encode_eip712()
+            .unwrap();
+        assert_eq!(signed.signature.recover(hash).unwrap(), wallet.address());
+
+        let calls = permit_and_swap_calls(&signed, &quote).unwrap();
+        assert_eq!(calls[0].to, token);
+        assert_eq!(
+            &calls[0].data[..4],
+            &id("permit(address,address,uint256,uint256,uint8,bytes32,bytes32)")
+        );
+    }
+}
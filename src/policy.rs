@@ -0,0 +1,688 @@
+//! Organizational trading limits, enforced by the client on every quote and by the
+//! [`SwapExecutor`](crate::SwapExecutor) before every swap.
+
+use std::{
+    collections::HashMap,
+    str::FromStr,
+    sync::Mutex,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use ethers::core::types::{Address, Chain};
+use rust_decimal::Decimal;
+use thiserror::Error;
+
+use crate::{
+    approval::NATIVE_TOKEN_ADDRESS, tokens, FeeBps, Percentage, ZeroXQuoteParams,
+    ZeroXQuoteResponse, ZeroXQuoteResponseV2,
+};
+
+/// Slippage 0x applies to requests without one, in both v1 and v2.
+const DEFAULT_SLIPPAGE: Decimal = Decimal::from_parts(1, 0, 0, false, 2);
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum PolicyViolation {
+    #[error("Chain {0} is not allowed")]
+    ChainNotAllowed(u64),
+
+    #[error("Token {0} is denied")]
+    TokenDenied(String),
+
+    #[error("Token {0} is not on the allowlist")]
+    TokenNotAllowed(String),
+
+    #[error("Slippage {slippage} exceeds the {max} limit")]
+    SlippageTooHigh {
+        slippage: Percentage,
+        max: Percentage,
+    },
+
+    #[error("Trade notional {notional} ETH exceeds the {max} ETH limit")]
+    TradeNotionalExceeded { notional: Decimal, max: Decimal },
+
+    #[error(
+        "Trade notional {notional} ETH would take today's {used} ETH past the {max} ETH limit"
+    )]
+    DailyNotionalExceeded {
+        used: Decimal,
+        notional: Decimal,
+        max: Decimal,
+    },
+
+    /// Notional limits are set but the trade cannot be valued, so it is refused.
+    #[error("Cannot value trade: {0}")]
+    UnknownNotional(String),
+}
+
+/// The fields of a v2 or gasless quote the policy checks.
+pub(crate) struct V2Terms<'a> {
+    pub(crate) sell_token: &'a Option<String>,
+    pub(crate) buy_token: &'a Option<String>,
+    pub(crate) sell_amount: &'a Option<String>,
+    pub(crate) buy_amount: &'a Option<String>,
+    pub(crate) min_buy_amount: &'a Option<String>,
+}
+
+#[derive(Debug, Default)]
+struct DailyNotional {
+    /// Days since the Unix epoch, UTC.
+    day: u64,
+    used: Decimal,
+}
+
+/// Trading limits shared by a client and executor, set with
+/// [`ZeroXClientBuilder::policy`](crate::ZeroXClientBuilder::policy) and
+/// [`SwapExecutor::policy`](crate::SwapExecutor::policy).
+///
+/// Notional is the sell side valued in ETH with the quote's `sellTokenToEthRate`. v2 quotes
+/// carry no rate, so with notional limits set only v2 sells of the native token are allowed. Tokens are
+/// matched case-insensitively against both the requested token and the quoted address, so
+/// list addresses and any symbols requests use. The daily total resets at midnight UTC and
+/// grows with each swap reserved by [`reserve_trade`](Self::reserve_trade), which the executor
+/// calls before sending, or counted with [`record_trade`](Self::record_trade).
+#[derive(Debug, Default)]
+pub struct TradingPolicy {
+    allowed_chains: Option<Vec<u64>>,
+    allowed_tokens: Option<Vec<String>>,
+    denied_tokens: Vec<String>,
+    max_slippage: Option<Percentage>,
+    max_trade_notional: Option<Decimal>,
+    max_daily_notional: Option<Decimal>,
+    token_decimals: HashMap<Address, u8>,
+    daily: Mutex<DailyNotional>,
+}
+
+impl TradingPolicy {
+    pub fn new() -> TradingPolicy {
+        TradingPolicy::default()
+    }
+
+    pub fn allowed_chains(mut self, chains: impl IntoIterator<Item = u64>) -> Self {
+        self.allowed_chains = Some(chains.into_iter().collect());
+        self
+    }
+
+    pub fn allowed_tokens<I, S>(mut self, tokens: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.allowed_tokens = Some(lowercase(tokens));
+        self
+    }
+
+    pub fn denied_tokens<I, S>(mut self, tokens: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.denied_tokens = lowercase(tokens);
+        self
+    }
+
+    pub fn max_slippage(mut self, max_slippage: impl Into<Percentage>) -> Self {
+        self.max_slippage = Some(max_slippage.into());
+        self
+    }
+
+    /// Largest single trade, in ETH.
+    pub fn max_trade_notional(mut self, max_eth: Decimal) -> Self {
+        self.max_trade_notional = Some(max_eth);
+        self
+    }
+
+    /// Largest total traded per UTC day, in ETH.
+    pub fn max_daily_notional(mut self, max_eth: Decimal) -> Self {
+        self.max_daily_notional = Some(max_eth);
+        self
+    }
+
+    /// Decimals for valuing a sell token missing from [`tokens`](crate::tokens).
+    pub fn token_decimals(mut self, token: Address, decimals: u8) -> Self {
+        self.token_decimals.insert(token, decimals);
+        self
+    }
+
+    /// Checks the chain, tokens and slippage of a v1 request before it is sent.
+    pub fn check_request(
+        &self,
+        chain_id: u64,
+        params: &ZeroXQuoteParams,
+    ) -> Result<(), PolicyViolation> {
+        self.check_tokens(chain_id, [&params.sell_token, &params.buy_token])?;
+        self.check_v1_slippage(params)
+    }
+
+    pub(crate) fn check_v1_slippage(
+        &self,
+        params: &ZeroXQuoteParams,
+    ) -> Result<(), PolicyViolation> {
+        self.check_slippage(
+            params
+                .slippage_percentage
+                .unwrap_or(Percentage::from_fraction(DEFAULT_SLIPPAGE)),
+        )
+    }
+
+    pub(crate) fn check_v2_slippage(
+        &self,
+        slippage_bps: Option<FeeBps>,
+    ) -> Result<(), PolicyViolation> {
+        self.check_slippage(slippage_bps.map_or(
+            Percentage::from_fraction(DEFAULT_SLIPPAGE),
+            Percentage::from,
+        ))
+    }
+
+    pub fn check_tokens<'a>(
+        &self,
+        chain_id: u64,
+        tokens: impl IntoIterator<Item = &'a String>,
+    ) -> Result<(), PolicyViolation> {
+        if self
+            .allowed_chains
+            .as_ref()
+            .is_some_and(|chains| !chains.contains(&chain_id))
+        {
+            return Err(PolicyViolation::ChainNotAllowed(chain_id));
+        }
+
+        for token in tokens {
+            let lower = token.to_lowercase();
+            if self.denied_tokens.contains(&lower) {
+                return Err(PolicyViolation::TokenDenied(token.clone()));
+            }
+            if self
+                .allowed_tokens
+                .as_ref()
+                .is_some_and(|allowed| !allowed.contains(&lower))
+            {
+                return Err(PolicyViolation::TokenNotAllowed(token.clone()));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn check_slippage(&self, slippage: Percentage) -> Result<(), PolicyViolation> {
+        match self.max_slippage {
+            Some(max) if slippage > max => Err(PolicyViolation::SlippageTooHigh { slippage, max }),
+            _ => Ok(()),
+        }
+    }
+
+    /// Checks a quote's chain, token addresses, implied slippage and notional, including
+    /// whether it fits in what is left of today's limit.
+    pub fn check_quote(
+        &self,
+        chain_id: u64,
+        quote: &ZeroXQuoteResponse,
+    ) -> Result<(), PolicyViolation> {
+        self.check_quote_terms(chain_id, quote)?;
+        if let Some(notional) = self.trade_notional(|| self.notional(quote))? {
+            self.check_daily(&mut self.daily.lock().unwrap(), notional)?;
+        }
+        Ok(())
+    }
+
+    /// Checks a v2 quote or price like [`check_quote`](Self::check_quote), with slippage
+    /// implied by the minimum buy amount.
+    ///
+    /// v2 responses carry no ETH rate, so only native token sells can be valued; with a
+    /// notional limit set, any other v2 trade is refused as
+    /// [`UnknownNotional`](PolicyViolation::UnknownNotional).
+    pub fn check_quote_v2(
+        &self,
+        chain_id: u64,
+        quote: &ZeroXQuoteResponseV2,
+    ) -> Result<(), PolicyViolation> {
+        self.check_v2_terms(
+            chain_id,
+            V2Terms {
+                sell_token: &quote.sell_token,
+                buy_token: &quote.buy_token,
+                sell_amount: &quote.sell_amount,
+                buy_amount: &quote.buy_amount,
+                min_buy_amount: &quote.min_buy_amount,
+            },
+        )
+    }
+
+    /// [`check_quote_v2`](Self::check_quote_v2) over the fields v2 and gasless responses share.
+    pub(crate) fn check_v2_terms(
+        &self,
+        chain_id: u64,
+        terms: V2Terms<'_>,
+    ) -> Result<(), PolicyViolation> {
+        let tokens: Vec<String> = [terms.sell_token, terms.buy_token]
+            .into_iter()
+            .flatten()
+            .cloned()
+            .collect();
+        self.check_tokens(chain_id, &tokens)?;
+
+        let amount = |amount: &Option<String>| {
+            amount
+                .as_deref()
+                .and_then(|amount| Decimal::from_str(amount).ok())
+        };
+        if let (Some(buy), Some(min_buy)) = (amount(terms.buy_amount), amount(terms.min_buy_amount))
+        {
+            if !buy.is_zero() {
+                self.check_slippage(Percentage::from_fraction(((buy - min_buy) / buy).abs()))?;
+            }
+        }
+
+        let notional = || {
+            let unknown = |reason: &str| PolicyViolation::UnknownNotional(reason.to_string());
+            if !terms
+                .sell_token
+                .as_deref()
+                .is_some_and(|token| token.eq_ignore_ascii_case(NATIVE_TOKEN_ADDRESS))
+            {
+                return Err(unknown(
+                    "v2 quotes can only be valued when selling the native token",
+                ));
+            }
+            let sell_amount =
+                amount(terms.sell_amount).ok_or_else(|| unknown("quote has no sell amount"))?;
+            Ok(sell_amount / Decimal::from(10u64.pow(18)))
+        };
+        if let Some(notional) = self.trade_notional(notional)? {
+            self.check_daily(&mut self.daily.lock().unwrap(), notional)?;
+        }
+        Ok(())
+    }
+
+    /// Checks a quote like [`check_quote`](Self::check_quote) and adds its notional to today's
+    /// total in the same step, so concurrent trades sharing the policy cannot together pass
+    /// the daily limit. Returns the notional reserved, to hand back with
+    /// [`release_trade`](Self::release_trade) if the trade is not executed.
+    pub fn reserve_trade(
+        &self,
+        chain_id: u64,
+        quote: &ZeroXQuoteResponse,
+    ) -> Result<Decimal, PolicyViolation> {
+        self.check_quote_terms(chain_id, quote)?;
+        let notional = match self.trade_notional(|| self.notional(quote))? {
+            Some(notional) => notional,
+            // no notional limits, but today's total is still kept when the trade can be valued
+            None => match self.notional(quote) {
+                Ok(notional) => notional,
+                Err(_) => return Ok(Decimal::ZERO),
+            },
+        };
+
+        let mut daily = self.daily.lock().unwrap();
+        self.check_daily(&mut daily, notional)?;
+        daily.used += notional;
+        Ok(notional)
+    }
+
+    /// Returns notional reserved by [`reserve_trade`](Self::reserve_trade) for a trade that
+    /// was not executed.
+    pub fn release_trade(&self, notional: Decimal) {
+        let mut daily = self.daily.lock().unwrap();
+        // a reservation from a previous day was reset with it
+        if daily.day == today() {
+            daily.used = (daily.used - notional).max(Decimal::ZERO);
+        }
+    }
+
+    /// Adds an executed trade to today's notional.
+    pub fn record_trade(&self, quote: &ZeroXQuoteResponse) -> Result<(), PolicyViolation> {
+        let notional = self.notional(quote)?;
+        let mut daily = self.daily.lock().unwrap();
+        roll_over(&mut daily);
+        daily.used += notional;
+        Ok(())
+    }
+
+    fn check_quote_terms(
+        &self,
+        chain_id: u64,
+        quote: &ZeroXQuoteResponse,
+    ) -> Result<(), PolicyViolation> {
+        let tokens: Vec<String> = [&quote.sell_token_address, &quote.buy_token_address]
+            .into_iter()
+            .flatten()
+            .cloned()
+            .collect();
+        self.check_tokens(chain_id, &tokens)?;
+
+        if let (Some(price), Some(guaranteed)) = (
+            quote
+                .price()
+                .and_then(|price| Decimal::from_str(price).ok()),
+            quote
+                .guaranteed_price()
+                .and_then(|price| Decimal::from_str(price).ok()),
+        ) {
+            if !price.is_zero() {
+                self.check_slippage(Percentage::from_fraction(
+                    ((price - guaranteed) / price).abs(),
+                ))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// The quote's notional checked against the per-trade limit, or `None` if no notional
+    /// limit is set.
+    fn trade_notional(
+        &self,
+        notional: impl FnOnce() -> Result<Decimal, PolicyViolation>,
+    ) -> Result<Option<Decimal>, PolicyViolation> {
+        if self.max_trade_notional.is_none() && self.max_daily_notional.is_none() {
+            return Ok(None);
+        }
+        let notional = notional()?;
+        if let Some(max) = self.max_trade_notional {
+            if notional > max {
+                return Err(PolicyViolation::TradeNotionalExceeded { notional, max });
+            }
+        }
+        Ok(Some(notional))
+    }
+
+    fn check_daily(
+        &self,
+        daily: &mut DailyNotional,
+        notional: Decimal,
+    ) -> Result<(), PolicyViolation> {
+        roll_over(daily);
+        match self.max_daily_notional {
+            Some(max) if daily.used + notional > max => {
+                Err(PolicyViolation::DailyNotionalExceeded {
+                    used: daily.used,
+                    notional,
+                    max,
+                })
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// ETH traded so far today.
+    pub fn daily_notional(&self) -> Decimal {
+        let daily = self.daily.lock().unwrap();
+        if daily.day == today() {
+            daily.used
+        } else {
+            Decimal::ZERO
+        }
+    }
+
+    /// The quote's sell amount in ETH.
+    pub fn notional(&self, quote: &ZeroXQuoteResponse) -> Result<Decimal, PolicyViolation> {
+        let unknown = |reason: &str| PolicyViolation::UnknownNotional(reason.to_string());
+
+        let token = quote
+            .sell_token_address
+            .as_deref()
+            .ok_or_else(|| unknown("quote has no sell token address"))?;
+        let decimals = if token.eq_ignore_ascii_case(NATIVE_TOKEN_ADDRESS) {
+            18
+        } else {
+            let address = Address::from_str(token).map_err(|_| unknown("invalid sell token"))?;
+            self.token_decimals
+                .get(&address)
+                .copied()
+                .or_else(|| {
+                    let chain = Chain::try_from(quote.chain_id()?).ok()?;
+                    tokens::all(chain)
+                        .into_iter()
+                        .find(|known| known.address == address)
+                        .map(|known| known.decimals)
+                })
+                .ok_or_else(|| unknown("sell token decimals unknown"))?
+        };
+
+        let amount = quote
+            .sell_amount
+            .as_deref()
+            .and_then(|amount| Decimal::from_str(amount).ok())
+            .ok_or_else(|| unknown("quote has no sell amount"))?;
+        let rate = quote
+            .sell_token_to_eth_rate
+            .as_deref()
+            .and_then(|rate| Decimal::from_str(rate).ok())
+            .filter(|rate| !rate.is_zero())
+            .ok_or_else(|| unknown("quote has no sell token to ETH rate"))?;
+
+        let scale = Decimal::from(10u64.pow(u32::from(decimals).min(19)));
+        Ok(amount / scale / rate)
+    }
+}
+
+fn lowercase<I, S>(tokens: I) -> Vec<String>
+where
+    I: IntoIterator<Item = S>,
+    S: Into<String>,
+{
+    tokens
+        .into_iter()
+        .map(|token| token.into().to_lowercase())
+        .collect()
+}
+
+/// Resets the total at the start of a new day.
+fn roll_over(daily: &mut DailyNotional) {
+    let today = today();
+    if daily.day != today {
+        *daily = DailyNotional {
+            day: today,
+            used: Decimal::ZERO,
+        };
+    }
+}
+
+fn today() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+        / 86_400
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use wiremock::{matchers::method, Mock, MockServer, ResponseTemplate};
+
+    use super::*;
+    use crate::{ZeroXClient, ZeroXClientError};
+
+    const USDC: &str = "0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48";
+
+    fn quote(sell_amount: &str) -> ZeroXQuoteResponse {
+        serde_json::from_value(serde_json::json!({
+            "chainId": 1,
+            "price": "0.0005",
+            "guaranteedPrice": "0.000495",
+            "sellTokenAddress": USDC,
+            "buyTokenAddress": NATIVE_TOKEN_ADDRESS,
+            "sellAmount": sell_amount,
+            "sellTokenToEthRate": "2000",
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_trading_policy() {
+        let policy = TradingPolicy::new()
+            .allowed_chains([1])
+            .denied_tokens(["SHIB"])
+            .max_slippage(Percentage::from_percent(Decimal::ONE))
+            .max_trade_notional(Decimal::from(5))
+            .max_daily_notional(Decimal::from(8));
+
+        let params = ZeroXQuoteParams {
+            sell_token: String::from("USDC"),
+            buy_token: String::from("shib"),
+            ..Default::default()
+        };
+        assert_eq!(
+            policy.check_request(1, &params),
+            Err(PolicyViolation::TokenDenied(String::from("shib")))
+        );
+        let params = ZeroXQuoteParams {
+            buy_token: String::from("ETH"),
+            ..params
+        };
+        assert!(policy.check_request(1, &params).is_ok());
+        assert_eq!(
+            policy.check_request(10, &params),
+            Err(PolicyViolation::ChainNotAllowed(10))
+        );
+        assert!(matches!(
+            policy.check_request(
+                1,
+                &params.clone().slippage(Percentage::from_percent(2.into()))
+            ),
+            Err(PolicyViolation::SlippageTooHigh { .. })
+        ));
+
+        // 10 000 USDC at 2000 USDC per ETH is 5 ETH.
+        let trade = quote("10000000000");
+        assert_eq!(policy.notional(&trade).unwrap(), Decimal::from(5));
+        assert!(policy.check_quote(1, &trade).is_ok());
+        assert!(matches!(
+            policy.check_quote(1, &quote("12000000000")),
+            Err(PolicyViolation::TradeNotionalExceeded { .. })
+        ));
+
+        policy.record_trade(&trade).unwrap();
+        assert_eq!(
+            policy.check_quote(1, &trade),
+            Err(PolicyViolation::DailyNotionalExceeded {
+                used: Decimal::from(5),
+                notional: Decimal::from(5),
+                max: Decimal::from(8),
+            })
+        );
+    }
+
+    #[test]
+    fn test_check_quote_v2() {
+        let policy = TradingPolicy::new()
+            .max_slippage(Percentage::from_percent(Decimal::ONE))
+            .max_trade_notional(Decimal::from(5));
+        let quote = |sell_token: &str, min_buy_amount: &str| -> ZeroXQuoteResponseV2 {
+            serde_json::from_value(serde_json::json!({
+                "sellToken": sell_token,
+                "buyToken": USDC,
+                "sellAmount": "2000000000000000000",
+                "buyAmount": "4000000000",
+                "minBuyAmount": min_buy_amount,
+            }))
+            .unwrap()
+        };
+
+        assert!(policy
+            .check_quote_v2(1, &quote(NATIVE_TOKEN_ADDRESS, "3980000000"))
+            .is_ok());
+        assert!(matches!(
+            policy.check_quote_v2(1, &quote(NATIVE_TOKEN_ADDRESS, "3900000000")),
+            Err(PolicyViolation::SlippageTooHigh { .. })
+        ));
+        // an ERC-20 sell cannot be valued without an ETH rate
+        assert!(matches!(
+            policy.check_quote_v2(1, &quote(USDC, "3980000000")),
+            Err(PolicyViolation::UnknownNotional(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_policy_applies_to_every_quote_path() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "buyTokenAddress": USDC,
+                "buyToken": USDC,
+            })))
+            .mount(&server)
+            .await;
+
+        let client = ZeroXClient::builder(1, String::from("key"))
+            .base_url(server.uri())
+            .v2_base_url(server.uri())
+            .policy(Arc::new(TradingPolicy::new().denied_tokens([USDC])))
+            .build()
+            .unwrap();
+        let denied = |err: ZeroXClientError| {
+            matches!(
+                err,
+                ZeroXClientError::PolicyViolation(PolicyViolation::TokenDenied(_))
+            )
+        };
+
+        assert!(denied(
+            client
+                .get_quote_raw(ZeroXQuoteParams::default())
+                .await
+                .unwrap_err()
+        ));
+        assert!(denied(
+            client
+                .get_price_body(ZeroXQuoteParams::default())
+                .await
+                .unwrap_err()
+        ));
+        assert!(denied(
+            client
+                .get_quote_snapshot(ZeroXQuoteParams::default())
+                .await
+                .unwrap_err()
+        ));
+        assert!(denied(
+            client.get_quote_v2(Default::default()).await.unwrap_err()
+        ));
+        assert!(denied(
+            client
+                .get_gasless_price(Default::default())
+                .await
+                .unwrap_err()
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_client_enforces_policy() {
+        let policy = Arc::new(
+            TradingPolicy::new()
+                .allowed_chains([1])
+                .denied_tokens(["SHIB"]),
+        );
+        let params = ZeroXQuoteParams {
+            sell_token: String::from("ETH"),
+            buy_token: String::from("SHIB"),
+            sell_amount: String::from("1"),
+            ..Default::default()
+        };
+
+        // Rejected before anything is sent to the unroutable base URL.
+        let client = ZeroXClient::builder(1, String::from("key"))
+            .base_url("http://0.0.0.0:1")
+            .policy(policy.clone())
+            .build()
+            .unwrap();
+        assert!(matches!(
+            client.get_price(params.clone()).await,
+            Err(ZeroXClientError::PolicyViolation(
+                PolicyViolation::TokenDenied(_)
+            ))
+        ));
+
+        let client = ZeroXClient::builder(137, String::from("key"))
+            .base_url("http://0.0.0.0:1")
+            .policy(policy)
+            .build()
+            .unwrap();
+        assert!(matches!(
+            client.get_quote(params).await,
+            Err(ZeroXClientError::PolicyViolation(
+                PolicyViolation::ChainNotAllowed(137)
+            ))
+        ));
+    }
+}
@@ -0,0 +1,132 @@
+//! Named liquidity source exclusions, expanded per chain so only sources that exist on the
+//! chain are sent, and quoting modes that set related parameters together.
+
+use crate::ZeroXQuoteParams;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SourcePreset {
+    /// No request-for-quote liquidity: 0x native orders and Hashflow.
+    NoRfq,
+    /// No liquidity from private market makers: PLP pools and Clipper.
+    NoPmm,
+    /// On-chain AMMs only; the union of `NoRfq` and `NoPmm`.
+    AmmOnly,
+}
+
+fn rfq_sources(chain_id: u64) -> &'static [&'static str] {
+    match chain_id {
+        1 | 10 | 56 | 137 | 42161 | 43114 => &["0x", "Hashflow"],
+        _ => &["0x"],
+    }
+}
+
+fn pmm_sources(chain_id: u64) -> &'static [&'static str] {
+    match chain_id {
+        1 | 137 => &["LiquidityProvider", "Clipper"],
+        10 | 42161 => &["Clipper"],
+        56 => &["LiquidityProvider"],
+        _ => &[],
+    }
+}
+
+impl SourcePreset {
+    /// The sources to exclude on `chain_id`.
+    pub fn excluded_sources(self, chain_id: u64) -> Vec<String> {
+        let sources: Vec<&str> = match self {
+            SourcePreset::NoRfq => rfq_sources(chain_id).to_vec(),
+            SourcePreset::NoPmm => pmm_sources(chain_id).to_vec(),
+            SourcePreset::AmmOnly => [rfq_sources(chain_id), pmm_sources(chain_id)].concat(),
+        };
+        sources.into_iter().map(String::from).collect()
+    }
+}
+
+impl ZeroXQuoteParams {
+    /// Adds the exclusions of `preset` on `chain_id` to `excluded_sources`.
+    pub fn exclude_preset(mut self, preset: SourcePreset, chain_id: u64) -> Self {
+        let excluded = self.excluded_sources.get_or_insert_with(Vec::new);
+        for source in preset.excluded_sources(chain_id) {
+            if !excluded.contains(&source) {
+                excluded.push(source);
+            }
+        }
+        self
+    }
+}
+
+/// Presets for the parameters that trade quote latency for detail.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum QuoteMode {
+    /// Lowest latency, e.g. for bots: skips transaction validation and price comparisons.
+    /// Quotes may fail on-chain and come without a validated `gas`.
+    Fast,
+    /// Most informative, e.g. for UIs: validates the transaction against the taker and
+    /// includes per-source price comparisons. Validation needs `taker_address`.
+    Accurate,
+}
+
+impl QuoteMode {
+    pub fn skip_validation(self) -> bool {
+        self == QuoteMode::Fast
+    }
+
+    pub fn include_price_comparisons(self) -> bool {
+        self == QuoteMode::Accurate
+    }
+}
+
+impl ZeroXQuoteParams {
+    /// Sets every parameter `mode` covers, replacing values already set.
+    pub fn mode(mut self, mode: QuoteMode) -> Self {
+        self.skip_validation = Some(mode.skip_validation());
+        self.include_price_comparisons = Some(mode.include_price_comparisons());
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_presets_per_chain() {
+        assert_eq!(
+            SourcePreset::AmmOnly.excluded_sources(1),
+            vec!["0x", "Hashflow", "LiquidityProvider", "Clipper"]
+        );
+        assert_eq!(SourcePreset::NoRfq.excluded_sources(42220), vec!["0x"]);
+        assert!(SourcePreset::NoPmm.excluded_sources(42220).is_empty());
+
+        let params = ZeroXQuoteParams {
+            excluded_sources: Some(vec![String::from("Clipper")]),
+            ..Default::default()
+        }
+        .exclude_preset(SourcePreset::NoPmm, 137)
+        .exclude_preset(SourcePreset::NoRfq, 137);
+        assert_eq!(
+            params.excluded_sources.unwrap(),
+            vec!["Clipper", "LiquidityProvider", "0x", "Hashflow"]
+        );
+    }
+
+    #[test]
+    fn test_quote_mode() {
+        let params = ZeroXQuoteParams {
+            skip_validation: Some(false),
+            ..Default::default()
+        }
+        .mode(QuoteMode::Fast);
+        assert_eq!(params.skip_validation, Some(true));
+        assert_eq!(params.include_price_comparisons, Some(false));
+
+        let params = ZeroXQuoteParams::default().mode(QuoteMode::Accurate);
+        assert_eq!(params.skip_validation, Some(false));
+        assert_eq!(params.include_price_comparisons, Some(true));
+
+        let query = crate::quote_query(params);
+        assert_eq!(
+            query.get("includePriceComparisons").map(String::as_str),
+            Some("true")
+        );
+    }
+}
@@ -0,0 +1,114 @@
+//! A client that can only fetch indicative prices, for services that must never hold an
+//! executable swap.
+
+use crate::{
+    gasless::{GaslessParams, GaslessPrice},
+    WithMetadata, ZeroXClient, ZeroXClientBuilder, ZeroXClientError, ZeroXQuoteParams,
+    ZeroXQuoteParamsV2, ZeroXQuoteResponse, ZeroXQuoteResponseV2,
+};
+
+/// Built with [`ZeroXClientBuilder::build_price_only`].
+///
+/// Only the price endpoints are exposed and the wrapped client cannot be reached, so no quote
+/// endpoint can be called through it. Transaction fields are also cleared from every
+/// response, in case a price ever carries them.
+#[derive(Clone)]
+pub struct PriceOnlyClient {
+    client: ZeroXClient,
+}
+
+impl ZeroXClientBuilder {
+    pub fn build_price_only(self) -> Result<PriceOnlyClient, ZeroXClientError> {
+        Ok(PriceOnlyClient {
+            client: self.build()?,
+        })
+    }
+}
+
+impl PriceOnlyClient {
+    pub fn new(chain_id: u64, api_key: String) -> Result<PriceOnlyClient, ZeroXClientError> {
+        ZeroXClient::builder(chain_id, api_key).build_price_only()
+    }
+
+    pub fn chain_id(&self) -> u64 {
+        self.client.chain_id()
+    }
+
+    pub async fn get_price(
+        &self,
+        params: ZeroXQuoteParams,
+    ) -> Result<ZeroXQuoteResponse, ZeroXClientError> {
+        Ok(self.get_price_with_metadata(params).await?.data)
+    }
+
+    pub async fn get_price_with_metadata(
+        &self,
+        params: ZeroXQuoteParams,
+    ) -> Result<WithMetadata<ZeroXQuoteResponse>, ZeroXClientError> {
+        let mut price = self.client.get_price_with_metadata(params).await?;
+        price.data.to = None;
+        price.data.data = None;
+        price.data.value = None;
+        Ok(price)
+    }
+
+    pub async fn get_price_v2(
+        &self,
+        params: ZeroXQuoteParamsV2,
+    ) -> Result<ZeroXQuoteResponseV2, ZeroXClientError> {
+        let mut price = self.client.get_price_v2(params).await?;
+        price.transaction = None;
+        price.permit2 = None;
+        Ok(price)
+    }
+
+    pub async fn get_gasless_price(
+        &self,
+        params: GaslessParams,
+    ) -> Result<GaslessPrice, ZeroXClientError> {
+        self.client.get_gasless_price(params).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use wiremock::{
+        matchers::{method, path},
+        Mock, MockServer, ResponseTemplate,
+    };
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_price_only_strips_calldata() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/swap/v1/price"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "price": "2000",
+                "to": "0xdef1c0ded9bec7f1a1670819833240f027b25eff",
+                "data": "0xd9627aa4",
+                "value": "0",
+            })))
+            .mount(&server)
+            .await;
+
+        let client = ZeroXClient::builder(1, String::from("key"))
+            .base_url(server.uri())
+            .build_price_only()
+            .unwrap();
+        let price = client
+            .get_price(ZeroXQuoteParams {
+                sell_token: String::from("ETH"),
+                buy_token: String::from("DAI"),
+                sell_amount: String::from("1"),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(price.price(), Some("2000"));
+        assert!(price.data.is_none());
+        assert!(price.to.is_none());
+    }
+}
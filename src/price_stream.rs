@@ -0,0 +1,315 @@
+//! Price subscriptions and adapters that compose over them, e.g.
+//! `watchlist.prices(pair).dedupe_unchanged().throttle(interval)`.
+
+use std::{
+    str::FromStr,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use async_trait::async_trait;
+use rust_decimal::Decimal;
+use tokio::{
+    sync::broadcast::{self, error::RecvError},
+    time,
+};
+
+use crate::{CachedQuote, WatchedPair, ZeroXQuoteResponse};
+
+/// A refreshed price for a pair.
+#[derive(Debug, Clone)]
+pub struct PriceTick {
+    pub pair: WatchedPair,
+    pub price: Decimal,
+    pub quote: Arc<ZeroXQuoteResponse>,
+    pub fetched_at: Instant,
+}
+
+/// A change in a pair's price, emitted by [`PriceStreamExt::diff`].
+#[derive(Debug, Clone)]
+pub struct PriceDelta {
+    pub tick: PriceTick,
+    pub previous: Decimal,
+    /// `tick.price - previous`.
+    pub change: Decimal,
+    /// `change / previous`, or `None` if `previous` is zero.
+    pub change_fraction: Option<Decimal>,
+}
+
+/// No item arrived within the timeout of [`PriceStreamExt::with_staleness_timeout`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Stale {
+    /// Time since the last item, or since the adapter was created if there was none.
+    pub since_last: Duration,
+}
+
+/// An async sequence of price updates. `next` returns `None` once the stream has ended.
+///
+/// Adapters are cancel-safe: dropping a pending `next` loses no state, so they can be used
+/// in `tokio::select!`.
+#[async_trait]
+pub trait PriceStream: Send {
+    type Item: Send;
+
+    async fn next(&mut self) -> Option<Self::Item>;
+}
+
+/// Adapters for [`PriceStream`]s.
+pub trait PriceStreamExt: PriceStream + Sized {
+    /// Skips ticks whose price equals the last emitted one.
+    fn dedupe_unchanged(self) -> DedupeUnchanged<Self>
+    where
+        Self: PriceStream<Item = PriceTick>,
+    {
+        DedupeUnchanged {
+            inner: self,
+            last: None,
+        }
+    }
+
+    /// Emits at most one tick per `interval`, dropping the ticks that arrive in between.
+    fn throttle(self, interval: Duration) -> Throttle<Self>
+    where
+        Self: PriceStream<Item = PriceTick>,
+    {
+        Throttle {
+            inner: self,
+            interval,
+            last_emitted: None,
+        }
+    }
+
+    /// Emits only price changes, each relative to the previous price. The first tick sets the
+    /// baseline and is not emitted.
+    fn diff(self) -> Diff<Self>
+    where
+        Self: PriceStream<Item = PriceTick>,
+    {
+        Diff {
+            inner: self,
+            previous: None,
+        }
+    }
+
+    /// Yields `Err(Stale)` each time `timeout` passes without an item, and keeps waiting.
+    fn with_staleness_timeout(self, timeout: Duration) -> StalenessTimeout<Self> {
+        let now = time::Instant::now();
+        StalenessTimeout {
+            inner: self,
+            timeout,
+            last_item: now,
+            deadline: now + timeout,
+        }
+    }
+}
+
+impl<S: PriceStream> PriceStreamExt for S {}
+
+/// Prices of one pair as a [`Watchlist`](crate::Watchlist) refreshes them, from
+/// [`Watchlist::prices`](crate::Watchlist::prices). Quotes without a parseable price are
+/// skipped, as are updates missed by a subscriber that fell behind.
+pub struct WatchlistPrices {
+    pub(crate) pair: WatchedPair,
+    pub(crate) updates: broadcast::Receiver<(WatchedPair, CachedQuote)>,
+}
+
+#[async_trait]
+impl PriceStream for WatchlistPrices {
+    type Item = PriceTick;
+
+    async fn next(&mut self) -> Option<PriceTick> {
+        loop {
+            let (pair, cached) = match self.updates.recv().await {
+                Ok(update) => update,
+                Err(RecvError::Lagged(_)) => continue,
+                Err(RecvError::Closed) => return None,
+            };
+            if pair != self.pair {
+                continue;
+            }
+            let Some(price) = cached
+                .quote
+                .price
+                .as_deref()
+                .and_then(|price| Decimal::from_str(price).ok())
+            else {
+                continue;
+            };
+
+            return Some(PriceTick {
+                pair,
+                price,
+                quote: cached.quote,
+                fetched_at: cached.fetched_at,
+            });
+        }
+    }
+}
+
+pub struct DedupeUnchanged<S> {
+    inner: S,
+    last: Option<Decimal>,
+}
+
+#[async_trait]
+impl<S: PriceStream<Item = PriceTick>> PriceStream for DedupeUnchanged<S> {
+    type Item = PriceTick;
+
+    async fn next(&mut self) -> Option<PriceTick> {
+        loop {
+            let tick = self.inner.next().await?;
+            if self.last != Some(tick.price) {
+                self.last = Some(tick.price);
+                return Some(tick);
+            }
+        }
+    }
+}
+
+pub struct Throttle<S> {
+    inner: S,
+    interval: Duration,
+    last_emitted: Option<time::Instant>,
+}
+
+#[async_trait]
+impl<S: PriceStream<Item = PriceTick>> PriceStream for Throttle<S> {
+    type Item = PriceTick;
+
+    async fn next(&mut self) -> Option<PriceTick> {
+        loop {
+            let tick = self.inner.next().await?;
+            if self
+                .last_emitted
+                .is_none_or(|last| last.elapsed() >= self.interval)
+            {
+                self.last_emitted = Some(time::Instant::now());
+                return Some(tick);
+            }
+        }
+    }
+}
+
+pub struct Diff<S> {
+    inner: S,
+    previous: Option<Decimal>,
+}
+
+#[async_trait]
+impl<S: PriceStream<Item = PriceTick>> PriceStream for Diff<S> {
+    type Item = PriceDelta;
+
+    async fn next(&mut self) -> Option<PriceDelta> {
+        loop {
+            let tick = self.inner.next().await?;
+            let Some(previous) = self.previous.replace(tick.price) else {
+                continue;
+            };
+            if tick.price == previous {
+                continue;
+            }
+
+            let change = tick.price - previous;
+            return Some(PriceDelta {
+                change_fraction: (!previous.is_zero()).then(|| change / previous),
+                change,
+                previous,
+                tick,
+            });
+        }
+    }
+}
+
+pub struct StalenessTimeout<S> {
+    inner: S,
+    timeout: Duration,
+    last_item: time::Instant,
+    deadline: time::Instant,
+}
+
+#[async_trait]
+impl<S: PriceStream> PriceStream for StalenessTimeout<S> {
+    type Item = Result<S::Item, Stale>;
+
+    async fn next(&mut self) -> Option<Result<S::Item, Stale>> {
+        match time::timeout_at(self.deadline, self.inner.next()).await {
+            Ok(item) => {
+                self.last_item = time::Instant::now();
+                self.deadline = self.last_item + self.timeout;
+                Some(Ok(item?))
+            }
+            Err(_) => {
+                self.deadline += self.timeout;
+                Some(Err(Stale {
+                    since_last: self.last_item.elapsed(),
+                }))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::VecDeque;
+
+    use super::*;
+
+    struct Scripted(VecDeque<Option<i64>>);
+
+    #[async_trait]
+    impl PriceStream for Scripted {
+        type Item = PriceTick;
+
+        async fn next(&mut self) -> Option<PriceTick> {
+            let Some(price) = *self.0.front()? else {
+                // A `None` entry never yields, like a feed that went quiet.
+                return std::future::pending().await;
+            };
+            self.0.pop_front();
+            Some(PriceTick {
+                pair: WatchedPair::new("ETH", "DAI", "1"),
+                price: Decimal::from(price),
+                quote: Arc::new(serde_json::from_value(serde_json::json!({})).unwrap()),
+                fetched_at: Instant::now(),
+            })
+        }
+    }
+
+    fn scripted(prices: &[i64]) -> Scripted {
+        Scripted(prices.iter().copied().map(Some).collect())
+    }
+
+    #[tokio::test]
+    async fn test_dedupe_and_diff() {
+        let mut deduped = scripted(&[1, 1, 2, 2, 1]).dedupe_unchanged();
+        let mut prices = Vec::new();
+        while let Some(tick) = deduped.next().await {
+            prices.push(tick.price);
+        }
+        assert_eq!(prices, [1, 2, 1].map(Decimal::from));
+
+        let mut diff = scripted(&[100, 100, 110, 99]).diff();
+        let delta = diff.next().await.unwrap();
+        assert_eq!(delta.previous, Decimal::from(100));
+        assert_eq!(delta.change, Decimal::from(10));
+        assert_eq!(delta.change_fraction, Some(Decimal::new(1, 1)));
+        assert_eq!(diff.next().await.unwrap().change, Decimal::from(-11));
+        assert!(diff.next().await.is_none());
+
+        let mut throttled = scripted(&[1, 2, 3]).throttle(Duration::from_secs(60));
+        assert_eq!(throttled.next().await.unwrap().price, Decimal::ONE);
+        assert!(throttled.next().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_staleness_timeout() {
+        let timeout = Duration::from_millis(50);
+        let mut stream = Scripted(VecDeque::from([Some(1), None])).with_staleness_timeout(timeout);
+
+        assert!(stream.next().await.unwrap().is_ok());
+        let stale = stream.next().await.unwrap().unwrap_err();
+        assert!(stale.since_last >= timeout);
+        let stale = stream.next().await.unwrap().unwrap_err();
+        assert!(stale.since_last >= timeout * 2);
+    }
+}
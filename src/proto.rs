@@ -0,0 +1,344 @@
+//! Protobuf messages for quotes, prices and execution reports, and conversions from and to
+//! the client's types. The messages are generated by prost-build from
+//! `proto/zerox/v1/quote.proto`; regenerate `src/proto/zerox.v1.rs` after changing it. The
+//! `PriceStream` service stubs generated by tonic-build are kept in `src/proto/zerox.v1.tonic.rs`
+//! so the messages build without tonic.
+//!
+//! Fields the schema does not carry, such as `orders` and `fees`, are dropped.
+
+use ethers::{
+    core::types::{Address, Bytes, TxHash},
+    utils::hex,
+};
+
+use crate::{
+    parse_field,
+    sink::{QuoteEvent, QuoteEventKind},
+    AuditDecision, QuoteFieldError, Source, ZeroXQuoteResponse,
+};
+
+/// The generated `zerox.v1` messages.
+pub mod pb {
+    include!("proto/zerox.v1.rs");
+    #[cfg(feature = "grpc")]
+    include!("proto/zerox.v1.tonic.rs");
+}
+
+use pb::{execution_report::Outcome, quote_update::Payload};
+
+fn address_bytes(value: &Option<String>, field: &'static str) -> Result<Vec<u8>, QuoteFieldError> {
+    value.as_deref().map_or(Ok(Vec::new()), |value| {
+        Ok(parse_field::<Address>(value, field)?.as_bytes().to_vec())
+    })
+}
+
+fn address_string(bytes: Vec<u8>, field: &'static str) -> Result<Option<String>, QuoteFieldError> {
+    match bytes.len() {
+        0 => Ok(None),
+        20 => Ok(Some(format!("{:?}", Address::from_slice(&bytes)))),
+        _ => Err(QuoteFieldError::Invalid {
+            field,
+            value: format!("0x{}", hex::encode(bytes)),
+        }),
+    }
+}
+
+fn chain_id_to_pb(chain_id: Option<i32>) -> Result<Option<u64>, QuoteFieldError> {
+    chain_id
+        .map(|chain_id| {
+            u64::try_from(chain_id).map_err(|_| QuoteFieldError::Invalid {
+                field: "chain_id",
+                value: chain_id.to_string(),
+            })
+        })
+        .transpose()
+}
+
+fn chain_id_from_pb(chain_id: Option<u64>) -> Result<Option<i32>, QuoteFieldError> {
+    chain_id
+        .map(|chain_id| {
+            i32::try_from(chain_id).map_err(|_| QuoteFieldError::Invalid {
+                field: "chain_id",
+                value: chain_id.to_string(),
+            })
+        })
+        .transpose()
+}
+
+fn sources_to_pb(sources: &Option<Vec<Source>>) -> Vec<pb::Source> {
+    sources
+        .iter()
+        .flatten()
+        .map(|source| pb::Source {
+            name: source.name.clone().unwrap_or_default(),
+            proportion: source.proportion.clone().unwrap_or_default(),
+        })
+        .collect()
+}
+
+fn sources_from_pb(sources: Vec<pb::Source>) -> Option<Vec<Source>> {
+    let non_empty = |value: String| (!value.is_empty()).then_some(value);
+    (!sources.is_empty()).then(|| {
+        sources
+            .into_iter()
+            .map(|source| Source {
+                name: non_empty(source.name),
+                proportion: non_empty(source.proportion),
+            })
+            .collect()
+    })
+}
+
+fn empty_response() -> ZeroXQuoteResponse {
+    ZeroXQuoteResponse {
+        chain_id: None,
+        price: None,
+        guaranteed_price: None,
+        estimated_price_impact: None,
+        to: None,
+        data: None,
+        value: None,
+        gas: None,
+        estimated_gas: None,
+        gas_price: None,
+        protocol_fee: None,
+        minimum_protocol_fee: None,
+        buy_token_address: None,
+        sell_token_address: None,
+        buy_amount: None,
+        sell_amount: None,
+        sources: None,
+        orders: None,
+        allowance_target: None,
+        sell_token_to_eth_rate: None,
+        buy_token_to_eth_rate: None,
+        fees: None,
+        gross_price: None,
+        gross_buy_amount: None,
+        gross_sell_amount: None,
+        price_comparisons: None,
+    }
+}
+
+impl TryFrom<&ZeroXQuoteResponse> for pb::Quote {
+    type Error = QuoteFieldError;
+
+    fn try_from(quote: &ZeroXQuoteResponse) -> Result<pb::Quote, QuoteFieldError> {
+        Ok(pb::Quote {
+            chain_id: chain_id_to_pb(quote.chain_id)?,
+            price: quote.price.clone(),
+            guaranteed_price: quote.guaranteed_price.clone(),
+            estimated_price_impact: quote.estimated_price_impact.clone(),
+            to: address_bytes(&quote.to, "to")?,
+            data: match &quote.data {
+                Some(data) => parse_field::<Bytes>(data, "data")?.to_vec(),
+                None => Vec::new(),
+            },
+            value: quote.value.clone(),
+            gas: quote.gas.clone(),
+            estimated_gas: quote.estimated_gas.clone(),
+            gas_price: quote.gas_price.clone(),
+            protocol_fee: quote.protocol_fee.clone(),
+            buy_token_address: address_bytes(&quote.buy_token_address, "buy_token_address")?,
+            sell_token_address: address_bytes(&quote.sell_token_address, "sell_token_address")?,
+            buy_amount: quote.buy_amount.clone(),
+            sell_amount: quote.sell_amount.clone(),
+            allowance_target: address_bytes(&quote.allowance_target, "allowance_target")?,
+            sources: sources_to_pb(&quote.sources),
+        })
+    }
+}
+
+impl TryFrom<pb::Quote> for ZeroXQuoteResponse {
+    type Error = QuoteFieldError;
+
+    fn try_from(quote: pb::Quote) -> Result<ZeroXQuoteResponse, QuoteFieldError> {
+        Ok(ZeroXQuoteResponse {
+            chain_id: chain_id_from_pb(quote.chain_id)?,
+            price: quote.price,
+            guaranteed_price: quote.guaranteed_price,
+            estimated_price_impact: quote.estimated_price_impact,
+            to: address_string(quote.to, "to")?,
+            data: (!quote.data.is_empty()).then(|| Bytes::from(quote.data).to_string()),
+            value: quote.value,
+            gas: quote.gas,
+            estimated_gas: quote.estimated_gas,
+            gas_price: quote.gas_price,
+            protocol_fee: quote.protocol_fee,
+            buy_token_address: address_string(quote.buy_token_address, "buy_token_address")?,
+            sell_token_address: address_string(quote.sell_token_address, "sell_token_address")?,
+            buy_amount: quote.buy_amount,
+            sell_amount: quote.sell_amount,
+            allowance_target: address_string(quote.allowance_target, "allowance_target")?,
+            sources: sources_from_pb(quote.sources),
+            ..empty_response()
+        })
+    }
+}
+
+impl TryFrom<&ZeroXQuoteResponse> for pb::Price {
+    type Error = QuoteFieldError;
+
+    fn try_from(price: &ZeroXQuoteResponse) -> Result<pb::Price, QuoteFieldError> {
+        Ok(pb::Price {
+            chain_id: chain_id_to_pb(price.chain_id)?,
+            price: price.price.clone(),
+            estimated_price_impact: price.estimated_price_impact.clone(),
+            estimated_gas: price.estimated_gas.clone(),
+            gas_price: price.gas_price.clone(),
+            buy_token_address: address_bytes(&price.buy_token_address, "buy_token_address")?,
+            sell_token_address: address_bytes(&price.sell_token_address, "sell_token_address")?,
+            buy_amount: price.buy_amount.clone(),
+            sell_amount: price.sell_amount.clone(),
+            allowance_target: address_bytes(&price.allowance_target, "allowance_target")?,
+            sources: sources_to_pb(&price.sources),
+        })
+    }
+}
+
+impl TryFrom<pb::Price> for ZeroXQuoteResponse {
+    type Error = QuoteFieldError;
+
+    fn try_from(price: pb::Price) -> Result<ZeroXQuoteResponse, QuoteFieldError> {
+        Ok(ZeroXQuoteResponse {
+            chain_id: chain_id_from_pb(price.chain_id)?,
+            price: price.price,
+            estimated_price_impact: price.estimated_price_impact,
+            estimated_gas: price.estimated_gas,
+            gas_price: price.gas_price,
+            buy_token_address: address_string(price.buy_token_address, "buy_token_address")?,
+            sell_token_address: address_string(price.sell_token_address, "sell_token_address")?,
+            buy_amount: price.buy_amount,
+            sell_amount: price.sell_amount,
+            allowance_target: address_string(price.allowance_target, "allowance_target")?,
+            sources: sources_from_pb(price.sources),
+            ..empty_response()
+        })
+    }
+}
+
+impl TryFrom<&QuoteEvent> for pb::QuoteUpdate {
+    type Error = QuoteFieldError;
+
+    fn try_from(event: &QuoteEvent) -> Result<pb::QuoteUpdate, QuoteFieldError> {
+        let quote = event.quote.as_ref();
+        Ok(pb::QuoteUpdate {
+            chain_id: event.chain_id,
+            sell_token: event.sell_token.clone(),
+            buy_token: event.buy_token.clone(),
+            sell_amount: event.sell_amount.clone(),
+            fetched_at: event.fetched_at,
+            payload: Some(match event.kind {
+                QuoteEventKind::Quote => Payload::Quote(quote.try_into()?),
+                QuoteEventKind::Price => Payload::Price(quote.try_into()?),
+            }),
+        })
+    }
+}
+
+impl From<&AuditDecision> for Outcome {
+    fn from(decision: &AuditDecision) -> Outcome {
+        match decision {
+            AuditDecision::Executed { tx_hash } => Outcome::Executed(tx_hash.as_bytes().to_vec()),
+            AuditDecision::Skipped { reason } => Outcome::Skipped(reason.clone()),
+            AuditDecision::Expired => Outcome::Expired(true),
+        }
+    }
+}
+
+impl TryFrom<Outcome> for AuditDecision {
+    type Error = QuoteFieldError;
+
+    fn try_from(outcome: Outcome) -> Result<AuditDecision, QuoteFieldError> {
+        match outcome {
+            Outcome::Executed(tx_hash) if tx_hash.len() == 32 => Ok(AuditDecision::Executed {
+                tx_hash: TxHash::from_slice(&tx_hash),
+            }),
+            Outcome::Executed(tx_hash) => Err(QuoteFieldError::Invalid {
+                field: "executed",
+                value: format!("0x{}", hex::encode(tx_hash)),
+            }),
+            Outcome::Skipped(reason) => Ok(AuditDecision::Skipped { reason }),
+            Outcome::Expired(_) => Ok(AuditDecision::Expired),
+        }
+    }
+}
+
+impl pb::ExecutionReport {
+    /// `reported_at` is a Unix timestamp in milliseconds.
+    pub fn new(
+        quote: &ZeroXQuoteResponse,
+        decision: &AuditDecision,
+        reported_at: u64,
+    ) -> Result<pb::ExecutionReport, QuoteFieldError> {
+        Ok(pb::ExecutionReport {
+            quote: Some(quote.try_into()?),
+            reported_at,
+            outcome: Some(decision.into()),
+        })
+    }
+
+    pub fn decision(&self) -> Result<AuditDecision, QuoteFieldError> {
+        self.outcome
+            .clone()
+            .ok_or(QuoteFieldError::Missing("outcome"))?
+            .try_into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use prost::Message;
+
+    use super::*;
+
+    fn quote() -> ZeroXQuoteResponse {
+        serde_json::from_value(serde_json::json!({
+            "chainId": 1,
+            "price": "2000.5",
+            "to": "0xdef1c0ded9bec7f1a1670819833240f027b25eff",
+            "data": "0xd9627aa4",
+            "value": "0",
+            "buyTokenAddress": "0x6b175474e89094c44da98b954eedeac495271d0f",
+            "sellTokenAddress": "0xeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeee",
+            "buyAmount": "2000500000000000000000",
+            "sellAmount": "1000000000000000000",
+            "sources": [{ "name": "Uniswap_V3", "proportion": "1" }],
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_quote_round_trip() {
+        let encoded = pb::Quote::try_from(&quote()).unwrap().encode_to_vec();
+        let decoded = ZeroXQuoteResponse::try_from(pb::Quote::decode(&encoded[..]).unwrap());
+
+        assert_eq!(
+            serde_json::to_value(decoded.unwrap()).unwrap(),
+            serde_json::to_value(quote()).unwrap()
+        );
+
+        let price = pb::Price::try_from(&quote()).unwrap();
+        assert_eq!(price.buy_token_address.len(), 20);
+        let price = ZeroXQuoteResponse::try_from(price).unwrap();
+        assert_eq!(price.sell_amount.as_deref(), Some("1000000000000000000"));
+        assert!(price.to.is_none());
+
+        let mut invalid = quote();
+        invalid.to = Some(String::from("0xdef1"));
+        assert!(pb::Quote::try_from(&invalid).is_err());
+    }
+
+    #[test]
+    fn test_execution_report() {
+        let decision = AuditDecision::Executed {
+            tx_hash: TxHash::repeat_byte(7),
+        };
+        let report = pb::ExecutionReport::new(&quote(), &decision, 1_700_000_000_000).unwrap();
+        let report = pb::ExecutionReport::decode(&report.encode_to_vec()[..]).unwrap();
+
+        assert_eq!(report.decision().unwrap(), decision);
+        assert_eq!(report.reported_at, 1_700_000_000_000);
+        assert!(AuditDecision::try_from(Outcome::Executed(vec![1, 2])).is_err());
+    }
+}
@@ -0,0 +1,150 @@
+// This file is @generated by prost-build.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Source {
+    #[prost(string, tag = "1")]
+    pub name: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub proportion: ::prost::alloc::string::String,
+}
+/// A firm quote from /swap/v1/quote.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Quote {
+    #[prost(uint64, optional, tag = "1")]
+    pub chain_id: ::core::option::Option<u64>,
+    #[prost(string, optional, tag = "2")]
+    pub price: ::core::option::Option<::prost::alloc::string::String>,
+    #[prost(string, optional, tag = "3")]
+    pub guaranteed_price: ::core::option::Option<::prost::alloc::string::String>,
+    #[prost(string, optional, tag = "4")]
+    pub estimated_price_impact: ::core::option::Option<::prost::alloc::string::String>,
+    #[prost(bytes = "vec", tag = "5")]
+    pub to: ::prost::alloc::vec::Vec<u8>,
+    #[prost(bytes = "vec", tag = "6")]
+    pub data: ::prost::alloc::vec::Vec<u8>,
+    #[prost(string, optional, tag = "7")]
+    pub value: ::core::option::Option<::prost::alloc::string::String>,
+    #[prost(string, optional, tag = "8")]
+    pub gas: ::core::option::Option<::prost::alloc::string::String>,
+    #[prost(string, optional, tag = "9")]
+    pub estimated_gas: ::core::option::Option<::prost::alloc::string::String>,
+    #[prost(string, optional, tag = "10")]
+    pub gas_price: ::core::option::Option<::prost::alloc::string::String>,
+    #[prost(string, optional, tag = "11")]
+    pub protocol_fee: ::core::option::Option<::prost::alloc::string::String>,
+    #[prost(bytes = "vec", tag = "12")]
+    pub buy_token_address: ::prost::alloc::vec::Vec<u8>,
+    #[prost(bytes = "vec", tag = "13")]
+    pub sell_token_address: ::prost::alloc::vec::Vec<u8>,
+    #[prost(string, optional, tag = "14")]
+    pub buy_amount: ::core::option::Option<::prost::alloc::string::String>,
+    #[prost(string, optional, tag = "15")]
+    pub sell_amount: ::core::option::Option<::prost::alloc::string::String>,
+    #[prost(bytes = "vec", tag = "16")]
+    pub allowance_target: ::prost::alloc::vec::Vec<u8>,
+    #[prost(message, repeated, tag = "17")]
+    pub sources: ::prost::alloc::vec::Vec<Source>,
+}
+/// An indicative price from /swap/v1/price.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Price {
+    #[prost(uint64, optional, tag = "1")]
+    pub chain_id: ::core::option::Option<u64>,
+    #[prost(string, optional, tag = "2")]
+    pub price: ::core::option::Option<::prost::alloc::string::String>,
+    #[prost(string, optional, tag = "3")]
+    pub estimated_price_impact: ::core::option::Option<::prost::alloc::string::String>,
+    #[prost(string, optional, tag = "4")]
+    pub estimated_gas: ::core::option::Option<::prost::alloc::string::String>,
+    #[prost(string, optional, tag = "5")]
+    pub gas_price: ::core::option::Option<::prost::alloc::string::String>,
+    #[prost(bytes = "vec", tag = "6")]
+    pub buy_token_address: ::prost::alloc::vec::Vec<u8>,
+    #[prost(bytes = "vec", tag = "7")]
+    pub sell_token_address: ::prost::alloc::vec::Vec<u8>,
+    #[prost(string, optional, tag = "8")]
+    pub buy_amount: ::core::option::Option<::prost::alloc::string::String>,
+    #[prost(string, optional, tag = "9")]
+    pub sell_amount: ::core::option::Option<::prost::alloc::string::String>,
+    #[prost(bytes = "vec", tag = "10")]
+    pub allowance_target: ::prost::alloc::vec::Vec<u8>,
+    #[prost(message, repeated, tag = "11")]
+    pub sources: ::prost::alloc::vec::Vec<Source>,
+}
+/// What was done with a quote.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ExecutionReport {
+    #[prost(message, optional, tag = "1")]
+    pub quote: ::core::option::Option<Quote>,
+    /// Unix timestamp in milliseconds.
+    #[prost(uint64, tag = "2")]
+    pub reported_at: u64,
+    #[prost(oneof = "execution_report::Outcome", tags = "3, 4, 5")]
+    pub outcome: ::core::option::Option<execution_report::Outcome>,
+}
+/// Nested message and enum types in `ExecutionReport`.
+pub mod execution_report {
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Oneof)]
+    pub enum Outcome {
+        /// Hash of the submitted transaction.
+        #[prost(bytes, tag = "3")]
+        Executed(::prost::alloc::vec::Vec<u8>),
+        /// Why the quote was not executed.
+        #[prost(string, tag = "4")]
+        Skipped(::prost::alloc::string::String),
+        /// The quote expired before it could be executed.
+        #[prost(bool, tag = "5")]
+        Expired(bool),
+    }
+}
+/// A pair to receive updates for. Empty fields match any value.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PairFilter {
+    #[prost(string, tag = "1")]
+    pub sell_token: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub buy_token: ::prost::alloc::string::String,
+    #[prost(string, tag = "3")]
+    pub sell_amount: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SubscribeRequest {
+    /// Pairs to stream; all registered pairs when empty.
+    #[prost(message, repeated, tag = "1")]
+    pub pairs: ::prost::alloc::vec::Vec<PairFilter>,
+}
+/// A refreshed quote or price for a registered pair. Tokens and amounts are as registered.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct QuoteUpdate {
+    #[prost(uint64, tag = "1")]
+    pub chain_id: u64,
+    #[prost(string, tag = "2")]
+    pub sell_token: ::prost::alloc::string::String,
+    #[prost(string, tag = "3")]
+    pub buy_token: ::prost::alloc::string::String,
+    #[prost(string, tag = "4")]
+    pub sell_amount: ::prost::alloc::string::String,
+    /// Unix timestamp in milliseconds.
+    #[prost(uint64, tag = "5")]
+    pub fetched_at: u64,
+    #[prost(oneof = "quote_update::Payload", tags = "6, 7")]
+    pub payload: ::core::option::Option<quote_update::Payload>,
+}
+/// Nested message and enum types in `QuoteUpdate`.
+pub mod quote_update {
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Oneof)]
+    pub enum Payload {
+        #[prost(message, tag = "6")]
+        Quote(super::Quote),
+        #[prost(message, tag = "7")]
+        Price(super::Price),
+    }
+}
@@ -0,0 +1,244 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use ethers::{core::types::TxHash, providers::Middleware};
+use thiserror::Error;
+
+use crate::{
+    SwapExecutor, SwapExecutorError, ZeroXClient, ZeroXClientBuilder, ZeroXClientError,
+    ZeroXQuoteParams, ZeroXQuoteResponse,
+};
+
+#[derive(Error, Debug)]
+pub enum SwapProviderError {
+    #[error(transparent)]
+    Client(#[from] ZeroXClientError),
+
+    #[error(transparent)]
+    Executor(#[from] SwapExecutorError),
+
+    #[error("Middleware error: {0}")]
+    Middleware(Box<dyn std::error::Error + Send + Sync>),
+
+    #[error("Middleware has no default sender; use a signer middleware")]
+    NoSender,
+
+    #[error("Chain id mismatch: provider is on {provider}, client is for {client}")]
+    ChainIdMismatch { provider: u64, client: u64 },
+}
+
+/// Quotes and executes swaps for the account and chain of an ethers middleware stack.
+///
+/// The taker is the middleware's default sender, and nonces are managed locally so
+/// concurrent swaps don't collide.
+pub struct SwapProvider<M> {
+    client: Arc<ZeroXClient>,
+    executor: SwapExecutor<M>,
+}
+
+impl<M: Middleware + 'static> SwapProvider<M> {
+    pub async fn new(
+        provider: Arc<M>,
+        client: Arc<ZeroXClient>,
+    ) -> Result<SwapProvider<M>, SwapProviderError> {
+        let from = provider
+            .default_sender()
+            .ok_or(SwapProviderError::NoSender)?;
+
+        let chain_id = provider
+            .get_chainid()
+            .await
+            .map_err(|err| SwapProviderError::Middleware(Box::new(err)))?
+            .as_u64();
+        if chain_id != client.chain_id() {
+            return Err(SwapProviderError::ChainIdMismatch {
+                provider: chain_id,
+                client: client.chain_id(),
+            });
+        }
+
+        Ok(SwapProvider {
+            client,
+            executor: SwapExecutor::new(provider, from).nonce_management(true),
+        })
+    }
+
+    /// Adjusts the underlying executor, e.g. to enable balance checks or set a fee estimator.
+    pub fn configure(mut self, f: impl FnOnce(SwapExecutor<M>) -> SwapExecutor<M>) -> Self {
+        self.executor = f(self.executor);
+        self
+    }
+
+    pub fn client(&self) -> &Arc<ZeroXClient> {
+        &self.client
+    }
+
+    pub fn executor(&self) -> &SwapExecutor<M> {
+        &self.executor
+    }
+
+    /// Fetches a quote with the sender as taker.
+    pub async fn quote(
+        &self,
+        params: ZeroXQuoteParams,
+    ) -> Result<ZeroXQuoteResponse, SwapProviderError> {
+        let params = ZeroXQuoteParams {
+            taker_address: Some(format!("{:?}", self.executor.from())),
+            ..params
+        };
+
+        Ok(self.client.get_quote(params).await?)
+    }
+
+    /// Fetches a quote with the sender as taker and executes it.
+    pub async fn swap(&self, params: ZeroXQuoteParams) -> Result<TxHash, SwapProviderError> {
+        let quote = self.quote(params).await?;
+
+        Ok(self.executor.execute_swap(&quote).await?)
+    }
+}
+
+impl ZeroXClient {
+    /// Creates a client for the chain `provider` is connected to, as reported by
+    /// `eth_chainId`.
+    ///
+    /// The provider is only queried for the chain, not kept: pass it to the RPC-backed helpers,
+    /// or use [`ZeroXMiddlewareExt::zerox`] to get a [`SwapProvider`] that holds both.
+    pub async fn from_provider<M: Middleware + 'static>(
+        provider: Arc<M>,
+        api_key: String,
+    ) -> Result<ZeroXClient, ZeroXClientError> {
+        ZeroXClient::builder_from_provider(provider, api_key)
+            .await?
+            .build()
+    }
+
+    /// Like [`ZeroXClient::from_provider`], returning the builder for further configuration.
+    pub async fn builder_from_provider<M: Middleware + 'static>(
+        provider: Arc<M>,
+        api_key: String,
+    ) -> Result<ZeroXClientBuilder, ZeroXClientError> {
+        let chain_id = provider
+            .get_chainid()
+            .await
+            .map_err(|err| ZeroXClientError::Rpc(Box::new(err)))?;
+
+        Ok(ZeroXClient::builder(chain_id.as_u64(), api_key))
+    }
+}
+
+/// Adds `provider.zerox(api_key)` to every ethers middleware.
+#[async_trait]
+pub trait ZeroXMiddlewareExt: Middleware + Sized + 'static {
+    async fn zerox(
+        self: Arc<Self>,
+        api_key: String,
+    ) -> Result<SwapProvider<Self>, SwapProviderError>;
+}
+
+#[async_trait]
+impl<M: Middleware + 'static> ZeroXMiddlewareExt for M {
+    async fn zerox(
+        self: Arc<Self>,
+        api_key: String,
+    ) -> Result<SwapProvider<Self>, SwapProviderError> {
+        let client = ZeroXClient::from_provider(self.clone(), api_key).await?;
+
+        SwapProvider::new(self, Arc::new(client)).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ethers::{
+        core::types::{Address, U256},
+        providers::Provider,
+    };
+    use wiremock::{
+        matchers::{method, path, query_param},
+        Mock, MockServer, ResponseTemplate,
+    };
+
+    use super::*;
+    use crate::fees::Eip1559Fees;
+
+    static VITALIK: &str = "0xd8dA6BF26964aF9D7eEd9e03E53415D37aA96045";
+
+    #[tokio::test]
+    async fn test_swap_uses_provider_sender() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/swap/v1/quote"))
+            .and(query_param("takerAddress", VITALIK.to_lowercase()))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "chainId": 1,
+                "to": "0xdef1c0ded9bec7f1a1670819833240f027b25eff",
+                "data": "0xd9627aa4",
+                "value": "0",
+                "gasPrice": "1000000000",
+            })))
+            .mount(&server)
+            .await;
+
+        let (provider, mock) = Provider::mocked();
+        let provider = provider.with_sender(VITALIK.parse::<Address>().unwrap());
+        // responses are popped from the back
+        mock.push(TxHash::repeat_byte(1)).unwrap();
+        mock.push(U256::from(21000)).unwrap();
+        mock.push(U256::from(7)).unwrap();
+        mock.push(U256::from(1)).unwrap();
+
+        let client = ZeroXClient::builder(1, String::from("test"))
+            .base_url(server.uri())
+            .build()
+            .unwrap();
+
+        let swap_provider = SwapProvider::new(Arc::new(provider), Arc::new(client))
+            .await
+            .unwrap()
+            .configure(|executor| {
+                executor.fee_estimator(Eip1559Fees {
+                    max_fee_per_gas: U256::from(30_000_000_000u64),
+                    max_priority_fee_per_gas: U256::from(1_000_000_000),
+                })
+            });
+
+        assert_eq!(
+            swap_provider
+                .swap(ZeroXQuoteParams::default())
+                .await
+                .unwrap(),
+            TxHash::repeat_byte(1)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_rejects_chain_id_mismatch() {
+        let (provider, mock) = Provider::mocked();
+        let provider = provider.with_sender(VITALIK.parse::<Address>().unwrap());
+        mock.push(U256::from(137)).unwrap();
+
+        let client = ZeroXClient::new(1, String::from("test")).unwrap();
+
+        assert!(matches!(
+            SwapProvider::new(Arc::new(provider), Arc::new(client)).await,
+            Err(SwapProviderError::ChainIdMismatch {
+                provider: 137,
+                client: 1
+            })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_from_provider_detects_chain() {
+        let (provider, mock) = Provider::mocked();
+        mock.push(U256::from(137)).unwrap();
+
+        let client = ZeroXClient::from_provider(Arc::new(provider), String::from("test"))
+            .await
+            .unwrap();
+
+        assert_eq!(client.chain_id(), 137);
+        assert_eq!(client.base_url(), "https://polygon.api.0x.org");
+    }
+}
@@ -0,0 +1,108 @@
+//! One-call quotes for the common case: a sell amount given in whole tokens and otherwise
+//! default parameters.
+
+use crate::{
+    approval::NATIVE_TOKEN_ADDRESS, Amount, ZeroXClient, ZeroXClientError, ZeroXQuoteParams,
+    ZeroXQuoteResponse,
+};
+
+fn sell_params(
+    sell_token: &str,
+    buy_token: &str,
+    amount: &str,
+    decimals: u8,
+) -> Result<ZeroXQuoteParams, ZeroXClientError> {
+    Ok(ZeroXQuoteParams {
+        sell_token: sell_token.to_string(),
+        buy_token: buy_token.to_string(),
+        sell_amount: Amount::parse(amount, decimals)?.to_string(),
+        ..Default::default()
+    })
+}
+
+impl ZeroXClient {
+    /// Quotes selling `amount_eth` of the chain's native token, e.g. `"0.5"`, for `buy_token`.
+    pub async fn quote_sell_native(
+        &self,
+        buy_token: &str,
+        amount_eth: &str,
+    ) -> Result<ZeroXQuoteResponse, ZeroXClientError> {
+        self.get_quote(sell_params(
+            NATIVE_TOKEN_ADDRESS,
+            buy_token,
+            amount_eth,
+            18,
+        )?)
+        .await
+    }
+
+    /// Quotes selling `amount` of `sell_token`, given in whole tokens with `decimals` decimals.
+    pub async fn quote_token_for_token(
+        &self,
+        sell_token: &str,
+        buy_token: &str,
+        amount: &str,
+        decimals: u8,
+    ) -> Result<ZeroXQuoteResponse, ZeroXClientError> {
+        self.get_quote(sell_params(sell_token, buy_token, amount, decimals)?)
+            .await
+    }
+
+    /// Like [`ZeroXClient::quote_token_for_token`], for an indicative price.
+    pub async fn price_token_for_token(
+        &self,
+        sell_token: &str,
+        buy_token: &str,
+        amount: &str,
+        decimals: u8,
+    ) -> Result<ZeroXQuoteResponse, ZeroXClientError> {
+        self.get_price(sell_params(sell_token, buy_token, amount, decimals)?)
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use wiremock::{
+        matchers::{method, path, query_param},
+        Mock, MockServer, ResponseTemplate,
+    };
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_quote_helpers_scale_amounts() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/swap/v1/quote"))
+            .and(query_param("sellToken", NATIVE_TOKEN_ADDRESS))
+            .and(query_param("sellAmount", "500000000000000000"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({})))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/swap/v1/price"))
+            .and(query_param("sellToken", "USDC"))
+            .and(query_param("sellAmount", "1500000"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({})))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = ZeroXClient::builder(1, String::from("test"))
+            .base_url(server.uri())
+            .build()
+            .unwrap();
+
+        client.quote_sell_native("DAI", "0.5").await.unwrap();
+        client
+            .price_token_for_token("USDC", "DAI", "1.5", 6)
+            .await
+            .unwrap();
+        assert!(matches!(
+            client.quote_token_for_token("USDC", "DAI", "1,5", 6).await,
+            Err(ZeroXClientError::InvalidAmount(_))
+        ));
+    }
+}
@@ -0,0 +1,136 @@
+use async_trait::async_trait;
+use ethers::core::types::{Address, TransactionRequest, U256};
+
+use crate::{ToTransactionRequest, ZeroXClient, ZeroXClientError, ZeroXQuoteParams};
+
+/// An aggregator-independent swap request.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SwapRequest {
+    pub chain_id: u64,
+    pub sell_token: Address,
+    pub buy_token: Address,
+    pub sell_amount: U256,
+    pub taker: Option<Address>,
+}
+
+/// An aggregator-independent quote.
+#[derive(Debug, Clone)]
+pub struct NormalizedQuote {
+    /// Name of the aggregator that produced the quote.
+    pub quoter: String,
+    pub sell_token: Address,
+    pub buy_token: Address,
+    pub sell_amount: U256,
+    pub buy_amount: U256,
+    pub estimated_gas: Option<U256>,
+    /// Executable transaction, if the aggregator returned one.
+    pub transaction: Option<TransactionRequest>,
+}
+
+/// Common interface for swap aggregators, so 0x can be compared against alternatives.
+#[async_trait]
+pub trait SwapQuoter: Send + Sync {
+    fn name(&self) -> &str;
+
+    async fn quote_swap(
+        &self,
+        request: &SwapRequest,
+    ) -> Result<NormalizedQuote, Box<dyn std::error::Error + Send + Sync>>;
+}
+
+#[async_trait]
+impl SwapQuoter for ZeroXClient {
+    fn name(&self) -> &str {
+        "0x"
+    }
+
+    async fn quote_swap(
+        &self,
+        request: &SwapRequest,
+    ) -> Result<NormalizedQuote, Box<dyn std::error::Error + Send + Sync>> {
+        if request.chain_id != self.chain_id() {
+            return Err(ZeroXClientError::InvalidChainId(request.chain_id).into());
+        }
+
+        let quote = self
+            .get_quote(ZeroXQuoteParams {
+                sell_token: format!("{:?}", request.sell_token),
+                buy_token: format!("{:?}", request.buy_token),
+                sell_amount: request.sell_amount.to_string(),
+                taker_address: request.taker.map(|taker| format!("{:?}", taker)),
+                ..Default::default()
+            })
+            .await?;
+
+        let buy_amount = U256::from_dec_str(
+            quote
+                .buy_amount
+                .as_deref()
+                .ok_or("Missing 'buy_amount' field")?,
+        )?;
+        let estimated_gas = quote
+            .estimated_gas
+            .as_deref()
+            .map(U256::from_dec_str)
+            .transpose()?;
+
+        Ok(NormalizedQuote {
+            quoter: self.name().to_string(),
+            sell_token: request.sell_token,
+            buy_token: request.buy_token,
+            sell_amount: request.sell_amount,
+            buy_amount,
+            estimated_gas,
+            transaction: quote.to_transaction_request().ok(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::{
+        matchers::{method, path, query_param},
+        Mock, MockServer, ResponseTemplate,
+    };
+
+    #[tokio::test]
+    async fn test_zerox_client_as_swap_quoter() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/swap/v1/quote"))
+            .and(query_param("sellAmount", "1000000"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "buyAmount": "999000000000000000",
+                "estimatedGas": "150000",
+            })))
+            .mount(&server)
+            .await;
+
+        let client = ZeroXClient::builder(1, String::from("test"))
+            .base_url(server.uri())
+            .build()
+            .unwrap();
+        let quoter: &dyn SwapQuoter = &client;
+
+        let quote = quoter
+            .quote_swap(&SwapRequest {
+                chain_id: 1,
+                sell_token: "0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48"
+                    .parse()
+                    .unwrap(),
+                buy_token: "0x6b175474e89094c44da98b954eedeac495271d0f"
+                    .parse()
+                    .unwrap(),
+                sell_amount: U256::from(1_000_000),
+                taker: None,
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(quote.quoter, "0x");
+        assert_eq!(quote.buy_amount, U256::from(999_000_000_000_000_000u64));
+        assert_eq!(quote.estimated_gas, Some(U256::from(150_000)));
+        assert!(quote.transaction.is_none());
+    }
+}
@@ -0,0 +1,310 @@
+use std::{
+    cmp::Reverse,
+    collections::BTreeSet,
+    sync::Mutex,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use reqwest::{header::HeaderMap, StatusCode};
+use tokio::sync::Notify;
+
+/// Order in which requests waiting on a saturated rate limiter are sent. Requests of equal
+/// priority are sent in arrival order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub enum RequestPriority {
+    /// Background work such as price refreshes for display.
+    Low,
+    #[default]
+    Normal,
+    /// Requests on a trading path, e.g. the quote about to be executed.
+    High,
+}
+
+/// Rate-limit state, as last reported by the API and as seen by the local limiter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct RateLimitStatus {
+    /// `None` until a response carrying rate-limit headers has been received.
+    pub server: Option<ServerRateLimit>,
+    /// `None` unless the client was built with a rate limit.
+    pub local: Option<LocalRateLimit>,
+}
+
+/// Parsed from the `x-ratelimit-*` headers of the latest response, or `retry-after` on a 429.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ServerRateLimit {
+    pub limit: Option<u32>,
+    pub remaining: Option<u32>,
+    /// When the window resets and `remaining` is replenished.
+    pub reset_at: Option<Instant>,
+    pub observed_at: Instant,
+}
+
+impl ServerRateLimit {
+    pub(crate) fn from_response(
+        status: StatusCode,
+        headers: &HeaderMap,
+        now: Instant,
+    ) -> Option<ServerRateLimit> {
+        let number =
+            |name: &str| -> Option<u64> { headers.get(name)?.to_str().ok()?.trim().parse().ok() };
+
+        let limit = number("x-ratelimit-limit").and_then(|n| u32::try_from(n).ok());
+        let mut remaining = number("x-ratelimit-remaining").and_then(|n| u32::try_from(n).ok());
+        let mut reset = number("x-ratelimit-reset");
+        if status == StatusCode::TOO_MANY_REQUESTS {
+            remaining = Some(0);
+            reset = number("retry-after").or(reset);
+        }
+
+        if limit.is_none() && remaining.is_none() && reset.is_none() {
+            return None;
+        }
+
+        Some(ServerRateLimit {
+            limit,
+            remaining,
+            reset_at: reset.map(|reset| now + reset_delay(reset)),
+            observed_at: now,
+        })
+    }
+}
+
+/// Reset values are either seconds from now or, if large enough, a Unix timestamp.
+fn reset_delay(reset: u64) -> Duration {
+    const UNIX_TIMESTAMP_MIN: u64 = 1_000_000_000;
+
+    if reset < UNIX_TIMESTAMP_MIN {
+        return Duration::from_secs(reset);
+    }
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default();
+    Duration::from_secs(reset).saturating_sub(now)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LocalRateLimit {
+    /// The configured burst size.
+    pub capacity: u32,
+    /// Requests that may be sent right now without waiting.
+    pub available: u32,
+    /// How long the next request would wait; zero if `available` is non-zero.
+    pub next_slot_in: Duration,
+}
+
+type QueueKey = (Reverse<RequestPriority>, u64);
+
+#[derive(Debug)]
+struct LimiterState {
+    // Theoretical arrival time of the next request (GCRA).
+    tat: Instant,
+    // Requests waiting for a slot; the first one is served next.
+    queue: BTreeSet<QueueKey>,
+    next_seq: u64,
+}
+
+/// Client-side limiter allowing bursts of up to `requests` and an average of `requests` per `per`.
+///
+/// When saturated, waiting requests are served by [`RequestPriority`], then in arrival order.
+#[derive(Debug)]
+pub(crate) struct RateLimiter {
+    interval: Duration,
+    burst: u32,
+    state: Mutex<LimiterState>,
+    queue_changed: Notify,
+}
+
+/// Removes a waiter from the queue when it is served or its request is dropped.
+struct QueueGuard<'a> {
+    limiter: &'a RateLimiter,
+    key: QueueKey,
+}
+
+impl Drop for QueueGuard<'_> {
+    fn drop(&mut self) {
+        self.limiter.state.lock().unwrap().queue.remove(&self.key);
+        self.limiter.queue_changed.notify_waiters();
+    }
+}
+
+impl RateLimiter {
+    pub(crate) fn new(requests: u32, per: Duration) -> RateLimiter {
+        let requests = requests.max(1);
+        RateLimiter {
+            interval: per / requests,
+            burst: requests,
+            state: Mutex::new(LimiterState {
+                tat: Instant::now(),
+                queue: BTreeSet::new(),
+                next_seq: 0,
+            }),
+            queue_changed: Notify::new(),
+        }
+    }
+
+    /// Takes a slot if one is free at `now`, or returns how long until one is.
+    fn take_slot(&self, state: &mut LimiterState, now: Instant) -> Result<(), Duration> {
+        let start = state.tat.max(now);
+        let tolerance = self.interval * (self.burst - 1);
+        match start.checked_sub(tolerance) {
+            Some(allowed_at) if allowed_at > now => Err(allowed_at - now),
+            _ => {
+                state.tat = start + self.interval;
+                Ok(())
+            }
+        }
+    }
+
+    /// Waits until a request may be sent.
+    pub(crate) async fn acquire(&self, priority: RequestPriority) {
+        let key = {
+            let mut state = self.state.lock().unwrap();
+            if state.queue.is_empty() && self.take_slot(&mut state, Instant::now()).is_ok() {
+                return;
+            }
+            let key = (Reverse(priority), state.next_seq);
+            state.next_seq += 1;
+            state.queue.insert(key);
+            key
+        };
+        // The new waiter may have displaced the head of the queue.
+        self.queue_changed.notify_waiters();
+        let _guard = QueueGuard { limiter: self, key };
+
+        loop {
+            let notified = self.queue_changed.notified();
+            tokio::pin!(notified);
+            notified.as_mut().enable();
+
+            let wait = {
+                let mut state = self.state.lock().unwrap();
+                if state.queue.first() != Some(&key) {
+                    None
+                } else {
+                    match self.take_slot(&mut state, Instant::now()) {
+                        Ok(()) => return,
+                        Err(wait) => Some(wait),
+                    }
+                }
+            };
+
+            match wait {
+                Some(wait) => {
+                    tokio::select! {
+                        _ = tokio::time::sleep(wait) => {}
+                        _ = &mut notified => {}
+                    }
+                }
+                None => notified.await,
+            }
+        }
+    }
+
+    pub(crate) fn status(&self) -> LocalRateLimit {
+        let tat = self.state.lock().unwrap().tat;
+        let backlog = tat.saturating_duration_since(Instant::now());
+        let used = backlog.as_nanos().div_ceil(self.interval.as_nanos().max(1));
+        let tolerance = self.interval * (self.burst - 1);
+
+        LocalRateLimit {
+            capacity: self.burst,
+            available: self
+                .burst
+                .saturating_sub(used.try_into().unwrap_or(u32::MAX)),
+            next_slot_in: backlog.saturating_sub(tolerance),
+        }
+    }
+
+    /// Takes a slot if one is available right now, without waiting.
+    #[cfg(feature = "server")]
+    pub(crate) fn try_acquire(&self) -> bool {
+        let mut state = self.state.lock().unwrap();
+        state.queue.is_empty() && self.take_slot(&mut state, Instant::now()).is_ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_rate_limiter_allows_burst_then_paces() {
+        let limiter = RateLimiter::new(2, Duration::from_millis(200));
+        let started = Instant::now();
+
+        limiter.acquire(RequestPriority::Normal).await;
+        limiter.acquire(RequestPriority::Normal).await;
+        assert!(started.elapsed() < Duration::from_millis(50));
+
+        limiter.acquire(RequestPriority::Normal).await;
+        assert!(started.elapsed() >= Duration::from_millis(100));
+    }
+
+    #[tokio::test]
+    async fn test_higher_priority_is_served_first() {
+        let limiter = std::sync::Arc::new(RateLimiter::new(1, Duration::from_millis(100)));
+        limiter.acquire(RequestPriority::Normal).await;
+
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        for priority in [
+            RequestPriority::Low,
+            RequestPriority::Normal,
+            RequestPriority::High,
+        ] {
+            let (limiter, tx) = (limiter.clone(), tx.clone());
+            tokio::spawn(async move {
+                limiter.acquire(priority).await;
+                tx.send(priority).unwrap();
+            });
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+
+        let mut served = Vec::new();
+        for _ in 0..3 {
+            served.push(rx.recv().await.unwrap());
+        }
+        assert_eq!(
+            served,
+            [
+                RequestPriority::High,
+                RequestPriority::Normal,
+                RequestPriority::Low
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_status() {
+        let limiter = RateLimiter::new(3, Duration::from_secs(60));
+        assert_eq!(limiter.status().available, 3);
+
+        for _ in 0..3 {
+            limiter.acquire(RequestPriority::Normal).await;
+        }
+        let status = limiter.status();
+        assert_eq!((status.capacity, status.available), (3, 0));
+        assert!(status.next_slot_in > Duration::from_secs(19));
+
+        let mut headers = HeaderMap::new();
+        headers.insert("x-ratelimit-remaining", "7".parse().unwrap());
+        headers.insert("retry-after", "30".parse().unwrap());
+        let now = Instant::now();
+        let ok = ServerRateLimit::from_response(StatusCode::OK, &headers, now).unwrap();
+        assert_eq!((ok.remaining, ok.reset_at), (Some(7), None));
+        let limited =
+            ServerRateLimit::from_response(StatusCode::TOO_MANY_REQUESTS, &headers, now).unwrap();
+        assert_eq!(limited.remaining, Some(0));
+        assert_eq!(limited.reset_at, Some(now + Duration::from_secs(30)));
+        assert!(ServerRateLimit::from_response(StatusCode::OK, &HeaderMap::new(), now).is_none());
+    }
+
+    #[cfg(feature = "server")]
+    #[test]
+    fn test_rate_limiter_try_acquire() {
+        let limiter = RateLimiter::new(2, Duration::from_secs(60));
+
+        assert!(limiter.try_acquire());
+        assert!(limiter.try_acquire());
+        assert!(!limiter.try_acquire());
+    }
+}
@@ -0,0 +1,378 @@
+//! Rebalancing a portfolio towards target weights: which swaps to make, what they are quoted
+//! at, and running them in order.
+
+use std::{cmp::Reverse, str::FromStr};
+
+use ethers::core::types::{Address, U256};
+use rust_decimal::Decimal;
+use thiserror::Error;
+
+use crate::{
+    Amount, QuoteFieldError, QuoteHandler, ZeroXClient, ZeroXClientError, ZeroXQuoteParams,
+    ZeroXQuoteResponse,
+};
+
+#[derive(Error, Debug)]
+pub enum RebalanceError {
+    #[error("Target weights sum to {0}, not 1")]
+    InvalidWeights(Decimal),
+
+    #[error("No position for target token {0:?}; add it with a zero balance")]
+    UnknownToken(Address),
+
+    #[error("Value of {0:?} does not fit in a decimal")]
+    Overflow(Address),
+
+    #[error("Failed to quote swap {index}: {source}")]
+    Quote {
+        index: usize,
+        source: ZeroXClientError,
+    },
+
+    #[error("Invalid quote for swap {index}: {source}")]
+    InvalidQuote {
+        index: usize,
+        source: QuoteFieldError,
+    },
+
+    #[error("Failed to execute swap {index}: {source}")]
+    Execution {
+        index: usize,
+        source: Box<dyn std::error::Error + Send + Sync>,
+    },
+}
+
+/// A token held, valued in a common unit such as USD.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Position {
+    pub token: Address,
+    pub decimals: u8,
+    /// In base units.
+    pub balance: U256,
+    /// Value of one whole token.
+    pub price: Decimal,
+}
+
+impl Position {
+    fn whole(&self, base_units: U256) -> Option<Decimal> {
+        let mut whole = Decimal::from_str(&base_units.to_string()).ok()?;
+        whole.set_scale(self.decimals as u32).ok()?;
+        Some(whole)
+    }
+
+    fn value(&self) -> Option<Decimal> {
+        self.whole(self.balance)?.checked_mul(self.price)
+    }
+
+    /// Base units worth `value`, rounded down.
+    fn amount_for(&self, value: Decimal) -> U256 {
+        let whole = (value / self.price)
+            .round_dp_with_strategy(self.decimals as u32, rust_decimal::RoundingStrategy::ToZero);
+        Amount::parse(&whole.to_string(), self.decimals)
+            .map(|amount| amount.as_u256())
+            .unwrap_or_default()
+    }
+}
+
+/// One swap of a rebalance, before quoting.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RebalanceTrade {
+    pub sell_token: Address,
+    pub buy_token: Address,
+    /// In base units of `sell_token`.
+    pub sell_amount: U256,
+    /// Value moved, at the positions' prices.
+    pub value: Decimal,
+}
+
+/// A trade with its quote and what it is expected to cost.
+#[derive(Debug)]
+pub struct PlannedSwap {
+    pub trade: RebalanceTrade,
+    pub quote: ZeroXQuoteResponse,
+    /// Value lost to price impact, slippage and fees: `trade.value` minus the value of the
+    /// quoted buy amount.
+    pub expected_cost: Decimal,
+    /// `gas * gas_price` in wei, if the quote has both.
+    pub network_fee: Option<U256>,
+}
+
+/// Quoted swaps in execution order, largest first.
+#[derive(Debug)]
+pub struct RebalancePlan {
+    pub swaps: Vec<PlannedSwap>,
+}
+
+impl RebalancePlan {
+    pub fn expected_cost(&self) -> Decimal {
+        self.swaps.iter().map(|swap| swap.expected_cost).sum()
+    }
+
+    pub fn network_fee(&self) -> U256 {
+        self.swaps
+            .iter()
+            .filter_map(|swap| swap.network_fee)
+            .fold(U256::zero(), |total, fee| total + fee)
+    }
+
+    /// Hands each quote to `handler` in order, e.g. a [`SwapExecutor`](crate::SwapExecutor),
+    /// and stops at the first failure. Quotes age while earlier swaps land, so plan shortly
+    /// before executing.
+    pub async fn execute(self, handler: &dyn QuoteHandler) -> Result<(), RebalanceError> {
+        for (index, swap) in self.swaps.into_iter().enumerate() {
+            handler
+                .handle(swap.quote)
+                .await
+                .map_err(|source| RebalanceError::Execution { index, source })?;
+        }
+        Ok(())
+    }
+}
+
+/// Computes the swaps that move a portfolio to target weights.
+///
+/// Each token over its target is sold directly for a token under its target, largest
+/// differences first, so `n` tokens need at most `n - 1` swaps. Differences smaller than
+/// [`RebalancePlanner::min_trade_value`] are left alone.
+#[derive(Debug, Clone)]
+pub struct RebalancePlanner {
+    positions: Vec<Position>,
+    targets: Vec<(Address, Decimal)>,
+    min_trade_value: Decimal,
+    params: ZeroXQuoteParams,
+}
+
+impl RebalancePlanner {
+    /// `targets` are fractions of the portfolio's value summing to 1. Held tokens without a
+    /// target are sold off.
+    pub fn new(positions: Vec<Position>, targets: Vec<(Address, Decimal)>) -> RebalancePlanner {
+        RebalancePlanner {
+            positions,
+            targets,
+            min_trade_value: Decimal::ZERO,
+            params: ZeroXQuoteParams::default(),
+        }
+    }
+
+    pub fn min_trade_value(mut self, min_trade_value: Decimal) -> Self {
+        self.min_trade_value = min_trade_value;
+        self
+    }
+
+    /// Template for each swap's quote parameters, e.g. the taker and slippage. Tokens and
+    /// amount are overwritten.
+    pub fn params(mut self, params: ZeroXQuoteParams) -> Self {
+        self.params = params;
+        self
+    }
+
+    pub fn trades(&self) -> Result<Vec<RebalanceTrade>, RebalanceError> {
+        let weights: Decimal = self.targets.iter().map(|(_, weight)| weight).sum();
+        if (weights - Decimal::ONE).abs() > Decimal::new(1, 6) {
+            return Err(RebalanceError::InvalidWeights(weights));
+        }
+        if let Some((token, _)) = self
+            .targets
+            .iter()
+            .find(|(token, _)| !self.positions.iter().any(|p| p.token == *token))
+        {
+            return Err(RebalanceError::UnknownToken(*token));
+        }
+
+        let values = self
+            .positions
+            .iter()
+            .map(|position| {
+                position
+                    .value()
+                    .ok_or(RebalanceError::Overflow(position.token))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let total: Decimal = values.iter().sum();
+
+        // (position, value to sell or buy)
+        let mut surpluses = Vec::new();
+        let mut deficits = Vec::new();
+        for (position, value) in self.positions.iter().zip(values) {
+            let weight = self
+                .targets
+                .iter()
+                .find(|(token, _)| *token == position.token)
+                .map_or(Decimal::ZERO, |(_, weight)| *weight);
+            let difference = value - total * weight;
+            if difference > self.min_trade_value {
+                surpluses.push((position, difference));
+            } else if -difference > self.min_trade_value {
+                deficits.push((position, -difference));
+            }
+        }
+        surpluses.sort_by_key(|(_, value)| Reverse(*value));
+        deficits.sort_by_key(|(_, value)| Reverse(*value));
+
+        let mut trades = Vec::new();
+        let (mut surpluses, mut deficits) = (surpluses.into_iter(), deficits.into_iter());
+        let (mut surplus, mut deficit) = (surpluses.next(), deficits.next());
+        while let (Some((seller, available)), Some((buyer, wanted))) = (&mut surplus, &mut deficit)
+        {
+            let value = (*available).min(*wanted);
+            if value > self.min_trade_value {
+                trades.push(RebalanceTrade {
+                    sell_token: seller.token,
+                    buy_token: buyer.token,
+                    sell_amount: seller.amount_for(value),
+                    value,
+                });
+            }
+            *available -= value;
+            *wanted -= value;
+            if available.is_zero() {
+                surplus = surpluses.next();
+            }
+            if wanted.is_zero() {
+                deficit = deficits.next();
+            }
+        }
+
+        trades.sort_by_key(|trade| Reverse(trade.value));
+        Ok(trades)
+    }
+
+    /// Quotes every trade and estimates its cost.
+    pub async fn plan(&self, client: &ZeroXClient) -> Result<RebalancePlan, RebalanceError> {
+        let mut swaps = Vec::new();
+        for (index, trade) in self.trades()?.into_iter().enumerate() {
+            let quote = client
+                .get_quote(ZeroXQuoteParams {
+                    sell_token: format!("{:?}", trade.sell_token),
+                    buy_token: format!("{:?}", trade.buy_token),
+                    sell_amount: trade.sell_amount.to_string(),
+                    ..self.params.clone()
+                })
+                .await
+                .map_err(|source| RebalanceError::Quote { index, source })?;
+
+            let bought = quote
+                .buy_amount()
+                .map_err(|source| RebalanceError::InvalidQuote { index, source })?;
+            let buyer = self
+                .positions
+                .iter()
+                .find(|position| position.token == trade.buy_token)
+                .expect("trades only buy held tokens");
+            let received = buyer
+                .whole(bought)
+                .and_then(|whole| whole.checked_mul(buyer.price))
+                .ok_or(RebalanceError::Overflow(buyer.token))?;
+            let network_fee = match (quote.gas(), quote.gas_price()) {
+                (Ok(gas), Ok(gas_price)) => Some(gas * gas_price),
+                _ => None,
+            };
+
+            swaps.push(PlannedSwap {
+                trade,
+                expected_cost: trade.value - received,
+                network_fee,
+                quote,
+            });
+        }
+
+        Ok(RebalancePlan { swaps })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use wiremock::{
+        matchers::{method, path, query_param},
+        Mock, MockServer, ResponseTemplate,
+    };
+
+    use super::*;
+
+    fn position(byte: u8, decimals: u8, whole: u64, price: i64) -> Position {
+        Position {
+            token: Address::repeat_byte(byte),
+            decimals,
+            balance: U256::from(whole) * U256::exp10(decimals as usize),
+            price: Decimal::from(price),
+        }
+    }
+
+    #[test]
+    fn test_trades_match_surpluses_to_deficits() {
+        // 10 A at 100, 1000 B at 1, 0 C at 2: worth 2000, targeting 25% / 25% / 50%
+        let positions = vec![
+            position(1, 18, 10, 100),
+            position(2, 6, 1000, 1),
+            position(3, 18, 0, 2),
+        ];
+        let targets = vec![
+            (Address::repeat_byte(1), Decimal::new(25, 2)),
+            (Address::repeat_byte(2), Decimal::new(25, 2)),
+            (Address::repeat_byte(3), Decimal::new(50, 2)),
+        ];
+
+        let trades = RebalancePlanner::new(positions.clone(), targets.clone())
+            .trades()
+            .unwrap();
+        assert_eq!(
+            trades,
+            vec![
+                RebalanceTrade {
+                    sell_token: Address::repeat_byte(1),
+                    buy_token: Address::repeat_byte(3),
+                    sell_amount: U256::from(5) * U256::exp10(18),
+                    value: Decimal::from(500),
+                },
+                RebalanceTrade {
+                    sell_token: Address::repeat_byte(2),
+                    buy_token: Address::repeat_byte(3),
+                    sell_amount: U256::from(500_000_000),
+                    value: Decimal::from(500),
+                },
+            ]
+        );
+
+        let mut targets = targets;
+        targets.pop();
+        assert!(matches!(
+            RebalancePlanner::new(positions, targets).trades(),
+            Err(RebalanceError::InvalidWeights(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_plan_quotes_trades() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/swap/v1/quote"))
+            .and(query_param("sellAmount", "1600000000000000000"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "buyAmount": "157000000",
+                "gas": "100000",
+                "gasPrice": "10",
+            })))
+            .mount(&server)
+            .await;
+        let client = ZeroXClient::builder(1, String::from("test"))
+            .base_url(server.uri())
+            .build()
+            .unwrap();
+
+        // 10 A at 100 and 400 B at 1, targeting 60% / 40% of 1400
+        let planner = RebalancePlanner::new(
+            vec![position(1, 18, 10, 100), position(2, 6, 400, 1)],
+            vec![
+                (Address::repeat_byte(1), Decimal::new(6, 1)),
+                (Address::repeat_byte(2), Decimal::new(4, 1)),
+            ],
+        )
+        .min_trade_value(Decimal::ONE);
+
+        let plan = planner.plan(&client).await.unwrap();
+        assert_eq!(plan.swaps.len(), 1);
+        assert_eq!(plan.swaps[0].trade.value, Decimal::from(160));
+        assert_eq!(plan.expected_cost(), Decimal::from(3));
+        assert_eq!(plan.network_fee(), U256::from(1_000_000));
+    }
+}
@@ -0,0 +1,383 @@
+//! Human-readable trade reports in Markdown or HTML, for pasting into chat or runbooks.
+
+use std::fmt::Write;
+
+use ethers::{
+    core::types::{TxHash, U256},
+    utils::format_units,
+};
+use rust_decimal::Decimal;
+
+use crate::{tokens::Token, ZeroXQuoteResponse};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReportFormat {
+    Markdown,
+    Html,
+}
+
+/// What a swap actually did on-chain, to compare with its quote.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Execution {
+    pub tx_hash: TxHash,
+    /// Buy token received, in base units.
+    pub bought: U256,
+    pub gas_used: U256,
+    pub effective_gas_price: U256,
+}
+
+struct Table {
+    heading: &'static str,
+    columns: &'static [&'static str],
+    rows: Vec<Vec<String>>,
+}
+
+/// A report of a quote, and of its execution if one is attached.
+///
+/// Amounts are shown in whole tokens for tokens set with [`TradeReport::sell_token`] and
+/// [`TradeReport::buy_token`], and in base units otherwise.
+pub struct TradeReport<'a> {
+    quote: &'a ZeroXQuoteResponse,
+    title: String,
+    sell_token: Option<Token>,
+    buy_token: Option<Token>,
+    execution: Option<Execution>,
+}
+
+impl<'a> TradeReport<'a> {
+    pub fn new(quote: &'a ZeroXQuoteResponse) -> TradeReport<'a> {
+        TradeReport {
+            quote,
+            title: String::from("Trade report"),
+            sell_token: None,
+            buy_token: None,
+            execution: None,
+        }
+    }
+
+    pub fn title(mut self, title: impl Into<String>) -> Self {
+        self.title = title.into();
+        self
+    }
+
+    pub fn sell_token(mut self, token: Token) -> Self {
+        self.sell_token = Some(token);
+        self
+    }
+
+    pub fn buy_token(mut self, token: Token) -> Self {
+        self.buy_token = Some(token);
+        self
+    }
+
+    pub fn execution(mut self, execution: Execution) -> Self {
+        self.execution = Some(execution);
+        self
+    }
+
+    pub fn render(&self, format: ReportFormat) -> String {
+        match format {
+            ReportFormat::Markdown => self.to_markdown(),
+            ReportFormat::Html => self.to_html(),
+        }
+    }
+
+    pub fn to_markdown(&self) -> String {
+        let mut out = format!("## {}\n\n**Pair:** {}\n", self.title, self.pair());
+        for table in self.tables() {
+            let _ = write!(
+                out,
+                "\n### {}\n\n| {} |\n|{}\n",
+                table.heading,
+                table.columns.join(" | "),
+                " --- |".repeat(table.columns.len())
+            );
+            for row in table.rows {
+                let cells: Vec<_> = row.iter().map(|cell| cell.replace('|', "\\|")).collect();
+                let _ = writeln!(out, "| {} |", cells.join(" | "));
+            }
+        }
+        out
+    }
+
+    pub fn to_html(&self) -> String {
+        let mut out = format!(
+            "<h2>{}</h2>\n<p><strong>Pair:</strong> {}</p>\n",
+            escape(&self.title),
+            escape(&self.pair())
+        );
+        for table in self.tables() {
+            let _ = write!(out, "<h3>{}</h3>\n<table>\n<tr>", table.heading);
+            for column in table.columns {
+                let _ = write!(out, "<th>{column}</th>");
+            }
+            out.push_str("</tr>\n");
+            for row in table.rows {
+                out.push_str("<tr>");
+                for cell in row {
+                    let _ = write!(out, "<td>{}</td>", escape(&cell));
+                }
+                out.push_str("</tr>\n");
+            }
+            out.push_str("</table>\n");
+        }
+        out
+    }
+
+    fn token_name(token: Option<Token>, address: Option<&str>) -> String {
+        match (token, address) {
+            (Some(token), _) => token.symbol.to_string(),
+            (None, Some(address)) => address.to_string(),
+            (None, None) => String::from("?"),
+        }
+    }
+
+    fn pair(&self) -> String {
+        format!(
+            "{} → {}",
+            TradeReport::token_name(self.sell_token, self.quote.sell_token_address.as_deref()),
+            TradeReport::token_name(self.buy_token, self.quote.buy_token_address.as_deref())
+        )
+    }
+
+    fn amount(token: Option<Token>, base_units: U256) -> String {
+        match token {
+            Some(token) => format!(
+                "{} {}",
+                whole_units(base_units, token.decimals),
+                token.symbol
+            ),
+            None => base_units.to_string(),
+        }
+    }
+
+    fn tables(&self) -> Vec<Table> {
+        let quote = self.quote;
+        let or_dash = |value: &Option<String>| value.clone().unwrap_or_else(|| String::from("-"));
+        let sold = quote.sell_amount().ok();
+        let quoted = quote.buy_amount().ok();
+        let gas = quote.gas().ok();
+        let gas_price = quote.gas_price().ok();
+
+        let mut summary = vec![
+            vec![
+                String::from("Sell amount"),
+                sold.map_or_else(
+                    || String::from("-"),
+                    |sold| TradeReport::amount(self.sell_token, sold),
+                ),
+            ],
+            vec![
+                String::from("Buy amount"),
+                quoted.map_or_else(
+                    || String::from("-"),
+                    |quoted| TradeReport::amount(self.buy_token, quoted),
+                ),
+            ],
+            vec![String::from("Price"), or_dash(&quote.price)],
+            vec![
+                String::from("Guaranteed price"),
+                or_dash(&quote.guaranteed_price),
+            ],
+            vec![
+                String::from("Price impact"),
+                or_dash(&quote.estimated_price_impact),
+            ],
+        ];
+
+        let mut fees = vec![
+            vec![String::from("Gas"), or_dash(&quote.gas)],
+            vec![
+                String::from("Gas price"),
+                gas_price.map_or_else(|| String::from("-"), gwei),
+            ],
+        ];
+        if let (Some(gas), Some(gas_price)) = (gas, gas_price) {
+            fees.push(vec![
+                String::from("Network fee (native)"),
+                whole_units(gas * gas_price, 18),
+            ]);
+        }
+        if let Some(protocol_fee) = quote.protocol_fee().ok().filter(|fee| !fee.is_zero()) {
+            fees.push(vec![
+                String::from("Protocol fee (native)"),
+                whole_units(protocol_fee, 18),
+            ]);
+        }
+        if let Some(fee) = quote.zero_ex_fee() {
+            fees.push(vec![
+                String::from("0x fee"),
+                format!(
+                    "{} {}",
+                    or_dash(&fee.fee_amount),
+                    fee.fee_token.as_deref().unwrap_or_default()
+                )
+                .trim_end()
+                .to_string(),
+            ]);
+        }
+
+        let route = quote
+            .sources()
+            .iter()
+            .filter_map(|source| {
+                let proportion = Decimal::from_str_exact(source.proportion.as_deref()?).ok()?;
+                (!proportion.is_zero()).then(|| {
+                    vec![
+                        source.name.clone().unwrap_or_default(),
+                        format!("{}%", (proportion * Decimal::ONE_HUNDRED).normalize()),
+                    ]
+                })
+            })
+            .collect();
+
+        let realized = self.execution.map(|execution| {
+            summary.push(vec![
+                String::from("Transaction"),
+                format!("{:?}", execution.tx_hash),
+            ]);
+            let difference = |quoted: Option<U256>, realized: U256| {
+                quoted
+                    .and_then(|quoted| difference_bps(quoted, realized))
+                    .unwrap_or_else(|| String::from("-"))
+            };
+            vec![
+                vec![
+                    String::from("Bought"),
+                    TradeReport::amount(self.buy_token, quoted.unwrap_or_default()),
+                    TradeReport::amount(self.buy_token, execution.bought),
+                    difference(quoted, execution.bought),
+                ],
+                vec![
+                    String::from("Gas"),
+                    gas.map_or_else(|| String::from("-"), |gas| gas.to_string()),
+                    execution.gas_used.to_string(),
+                    difference(gas, execution.gas_used),
+                ],
+                vec![
+                    String::from("Gas price"),
+                    gas_price.map_or_else(|| String::from("-"), gwei),
+                    gwei(execution.effective_gas_price),
+                    difference(gas_price, execution.effective_gas_price),
+                ],
+            ]
+        });
+
+        let mut tables = vec![Table {
+            heading: "Summary",
+            columns: &["", "Value"],
+            rows: summary,
+        }];
+        if let Some(realized) = realized {
+            tables.push(Table {
+                heading: "Realized vs quoted",
+                columns: &["", "Quoted", "Realized", "Difference"],
+                rows: realized,
+            });
+        }
+        tables.push(Table {
+            heading: "Route",
+            columns: &["Source", "Share"],
+            rows: route,
+        });
+        tables.push(Table {
+            heading: "Fees and gas",
+            columns: &["", "Value"],
+            rows: fees,
+        });
+        tables
+    }
+}
+
+/// `base_units` in whole tokens, without trailing zeros.
+fn whole_units(base_units: U256, decimals: u8) -> String {
+    let formatted =
+        format_units(base_units, decimals as u32).unwrap_or_else(|_| base_units.to_string());
+    match formatted.split_once('.') {
+        Some((whole, fraction)) if fraction.trim_end_matches('0').is_empty() => whole.to_string(),
+        Some(_) => formatted.trim_end_matches('0').to_string(),
+        None => formatted,
+    }
+}
+
+fn gwei(wei: U256) -> String {
+    format!("{} gwei", whole_units(wei, 9))
+}
+
+/// `(realized - quoted) / quoted` in basis points, e.g. `-12 bps`.
+fn difference_bps(quoted: U256, realized: U256) -> Option<String> {
+    let quoted = Decimal::from_str_exact(&quoted.to_string()).ok()?;
+    let realized = Decimal::from_str_exact(&realized.to_string()).ok()?;
+    if quoted.is_zero() {
+        return None;
+    }
+    let bps = ((realized - quoted) / quoted * Decimal::from(10_000)).round_dp(1);
+    Some(format!("{} bps", bps.normalize()))
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use ethers::core::types::Chain;
+
+    use super::*;
+    use crate::tokens;
+
+    fn quote() -> ZeroXQuoteResponse {
+        serde_json::from_value(serde_json::json!({
+            "price": "2000",
+            "guaranteedPrice": "1980",
+            "gas": "150000",
+            "gasPrice": "20000000000",
+            "buyAmount": "2000000000",
+            "sellAmount": "1000000000000000000",
+            "sources": [
+                { "name": "Uniswap_V3", "proportion": "0.75" },
+                { "name": "Curve", "proportion": "0.25" },
+                { "name": "Balancer", "proportion": "0" },
+            ],
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_markdown_report() {
+        let quote = quote();
+        let report = TradeReport::new(&quote)
+            .sell_token(tokens::weth(Chain::Mainnet).unwrap())
+            .buy_token(tokens::usdc(Chain::Mainnet).unwrap())
+            .execution(Execution {
+                tx_hash: TxHash::zero(),
+                bought: U256::from(1_998_000_000u64),
+                gas_used: U256::from(120_000),
+                effective_gas_price: U256::from(20_000_000_000u64),
+            })
+            .to_markdown();
+
+        assert!(report.starts_with("## Trade report\n\n**Pair:** WETH → USDC\n"));
+        assert!(report.contains("| Sell amount | 1 WETH |"));
+        assert!(report.contains("| Uniswap_V3 | 75% |"));
+        assert!(!report.contains("Balancer"));
+        assert!(report.contains("| Network fee (native) | 0.003 |"));
+        assert!(report.contains("| Bought | 2000 USDC | 1998 USDC | -10 bps |"));
+        assert!(report.contains("| Gas | 150000 | 120000 | -2000 bps |"));
+    }
+
+    #[test]
+    fn test_html_report_escapes() {
+        let quote = quote();
+        let html = TradeReport::new(&quote)
+            .title("<script>")
+            .render(ReportFormat::Html);
+
+        assert!(html.starts_with("<h2>&lt;script&gt;</h2>"));
+        assert!(html.contains("<tr><td>Curve</td><td>25%</td></tr>"));
+        assert!(!html.contains("Realized"));
+    }
+}
@@ -0,0 +1,220 @@
+//! A durable, `tracing`-independent request log: one JSON line per API request, written to a
+//! file that is rotated by size.
+
+use std::{
+    collections::BTreeMap,
+    fs::{self, File, OpenOptions},
+    io::{self, Write},
+    path::{Path, PathBuf},
+    sync::Mutex,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use reqwest::Method;
+use serde::Serialize;
+use tracing::debug;
+
+use crate::{logging::RequestLog, WithMetadata, ZeroXClientError};
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct RequestRecord<'a> {
+    /// Unix timestamp in milliseconds.
+    timestamp: u64,
+    chain_id: u64,
+    method: &'a str,
+    base_url: &'a str,
+    endpoint: &'a str,
+    query: BTreeMap<&'a str, &'a str>,
+    status: Option<u16>,
+    duration_ms: u64,
+    error: Option<String>,
+}
+
+struct LogFile {
+    file: File,
+    len: u64,
+}
+
+/// Appends one sanitized JSON line per request, set with
+/// [`ZeroXClientBuilder::request_log`](crate::ZeroXClientBuilder::request_log).
+///
+/// Lines hold the endpoint, query, status, duration and error. The API key is never written
+/// and the taker is masked per the client's [`LogRedaction`](crate::LogRedaction). When the
+/// file would exceed `max_bytes` it is renamed to `<path>.1`, older files shift up, and only
+/// `max_files` rotated files are kept.
+pub struct JsonlRequestLog {
+    path: PathBuf,
+    max_bytes: u64,
+    max_files: usize,
+    file: Mutex<LogFile>,
+}
+
+impl JsonlRequestLog {
+    /// Rotates at 100 MiB and keeps 5 rotated files by default.
+    pub fn open(path: impl Into<PathBuf>) -> io::Result<JsonlRequestLog> {
+        let path = path.into();
+        let file = open_append(&path)?;
+
+        Ok(JsonlRequestLog {
+            path,
+            max_bytes: 100 * 1024 * 1024,
+            max_files: 5,
+            file: Mutex::new(file),
+        })
+    }
+
+    pub fn max_bytes(mut self, max_bytes: u64) -> Self {
+        self.max_bytes = max_bytes;
+        self
+    }
+
+    pub fn max_files(mut self, max_files: usize) -> Self {
+        self.max_files = max_files;
+        self
+    }
+
+    pub(crate) fn write<T>(
+        &self,
+        method: &Method,
+        log: &RequestLog<'_>,
+        result: &Result<WithMetadata<T>, ZeroXClientError>,
+        elapsed: Duration,
+    ) {
+        let query = log
+            .query
+            .iter()
+            .map(|(name, value)| match *name {
+                "takerAddress" => (*name, log.redaction.taker(value)),
+                _ => (*name, value.as_str()),
+            })
+            .collect();
+        let (status, duration, error) = match result {
+            Ok(response) => (
+                Some(response.metadata.status.as_u16()),
+                response.metadata.timings.total,
+                None,
+            ),
+            Err(err) => (crate::logging::status(err), elapsed, Some(err.to_string())),
+        };
+        let record = RequestRecord {
+            timestamp: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_millis() as u64,
+            chain_id: log.chain_id,
+            method: method.as_str(),
+            base_url: log.base_url,
+            endpoint: log.path,
+            query,
+            status,
+            duration_ms: duration.as_millis() as u64,
+            error,
+        };
+
+        if let Err(err) = self.append(&record) {
+            debug!(
+                "failed to write request log {}: {}",
+                self.path.display(),
+                err
+            );
+        }
+    }
+
+    fn append(
+        &self,
+        record: &RequestRecord<'_>,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let mut line = serde_json::to_vec(record)?;
+        line.push(b'\n');
+
+        let mut file = self.file.lock().unwrap();
+        if file.len > 0 && file.len + line.len() as u64 > self.max_bytes {
+            self.rotate()?;
+            *file = open_append(&self.path)?;
+        }
+        file.file.write_all(&line)?;
+        file.len += line.len() as u64;
+        Ok(())
+    }
+
+    fn rotate(&self) -> io::Result<()> {
+        let rotated = |n: usize| PathBuf::from(format!("{}.{}", self.path.display(), n));
+
+        if self.max_files == 0 {
+            return fs::remove_file(&self.path);
+        }
+        let _ = fs::remove_file(rotated(self.max_files));
+        for n in (1..self.max_files).rev() {
+            if rotated(n).exists() {
+                fs::rename(rotated(n), rotated(n + 1))?;
+            }
+        }
+        fs::rename(&self.path, rotated(1))
+    }
+}
+
+fn open_append(path: &Path) -> io::Result<LogFile> {
+    let file = OpenOptions::new().create(true).append(true).open(path)?;
+    let len = file.metadata()?.len();
+    Ok(LogFile { file, len })
+}
+
+#[cfg(test)]
+mod tests {
+    use wiremock::{
+        matchers::{method, path},
+        Mock, MockServer, ResponseTemplate,
+    };
+
+    use super::*;
+    use crate::{ZeroXClient, ZeroXQuoteParams};
+
+    #[tokio::test]
+    async fn test_request_log_rotates() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/swap/v1/price"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({})))
+            .mount(&server)
+            .await;
+
+        let dir = std::env::temp_dir().join(format!("zerox-request-log-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let log_path = dir.join("requests.jsonl");
+
+        let client = ZeroXClient::builder(1, String::from("secret-key"))
+            .base_url(server.uri())
+            .request_log(
+                JsonlRequestLog::open(&log_path)
+                    .unwrap()
+                    .max_bytes(1)
+                    .max_files(1),
+            )
+            .build()
+            .unwrap();
+        let params = ZeroXQuoteParams {
+            sell_token: String::from("ETH"),
+            taker_address: Some(String::from("0xd8dA6BF26964aF9D7eEd9e03E53415D37aA96045")),
+            ..Default::default()
+        };
+        for _ in 0..3 {
+            client.get_price(params.clone()).await.unwrap();
+        }
+
+        let line = fs::read_to_string(&log_path).unwrap();
+        let record: serde_json::Value = serde_json::from_str(line.trim()).unwrap();
+        assert_eq!(record["endpoint"], "/swap/v1/price");
+        assert_eq!(record["status"], 200);
+        assert_eq!(record["query"]["sellToken"], "ETH");
+        assert!(!line.contains("secret-key"));
+        assert!(!line.contains("0xd8dA6BF26964aF9D7eEd9e03E53415D37aA96045"));
+
+        // One line per file: the current one and a single rotated one.
+        assert!(dir.join("requests.jsonl.1").exists());
+        assert!(!dir.join("requests.jsonl.2").exists());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
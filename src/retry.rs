@@ -0,0 +1,106 @@
+use reqwest::Method;
+use std::time::Duration;
+
+use crate::ZeroXClientError;
+
+/// Information about the request being retried.
+#[derive(Debug, Clone, Copy)]
+pub struct RetryContext<'a> {
+    pub method: &'a Method,
+    pub path: &'a str,
+    /// Number of attempts already made, starting at 1 after the first failure.
+    pub attempt: u32,
+}
+
+/// Decides whether a failed request should be retried and how long to wait first.
+pub trait RetryPolicy: Send + Sync {
+    /// Returns the delay before the next attempt, or `None` to give up and return `error`.
+    fn retry_after(&self, ctx: RetryContext<'_>, error: &ZeroXClientError) -> Option<Duration>;
+}
+
+/// Never retries.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoRetry;
+
+impl RetryPolicy for NoRetry {
+    fn retry_after(&self, _ctx: RetryContext<'_>, _error: &ZeroXClientError) -> Option<Duration> {
+        None
+    }
+}
+
+/// Retries transient failures (timeouts, connection errors, 429 and 5xx) with exponential backoff.
+///
+/// Only idempotent `GET` requests are retried.
+#[derive(Debug, Clone)]
+pub struct ExponentialBackoff {
+    pub max_retries: u32,
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+}
+
+impl Default for ExponentialBackoff {
+    fn default() -> Self {
+        ExponentialBackoff {
+            max_retries: 3,
+            base_delay: Duration::from_millis(250),
+            max_delay: Duration::from_secs(5),
+        }
+    }
+}
+
+impl RetryPolicy for ExponentialBackoff {
+    fn retry_after(&self, ctx: RetryContext<'_>, error: &ZeroXClientError) -> Option<Duration> {
+        if ctx.attempt > self.max_retries || ctx.method != Method::GET || !error.is_retryable() {
+            return None;
+        }
+
+        let factor = 2u32.saturating_pow(ctx.attempt - 1);
+        Some(self.base_delay.saturating_mul(factor).min(self.max_delay))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::StatusCode;
+
+    fn ctx(method: &Method, attempt: u32) -> RetryContext<'_> {
+        RetryContext {
+            method,
+            path: "/swap/v1/quote",
+            attempt,
+        }
+    }
+
+    #[test]
+    fn test_exponential_backoff_delays() {
+        let policy = ExponentialBackoff::default();
+        let error = ZeroXClientError::ZeroXInvalidResponseStatusCode(StatusCode::BAD_GATEWAY);
+
+        assert_eq!(
+            policy.retry_after(ctx(&Method::GET, 1), &error),
+            Some(Duration::from_millis(250))
+        );
+        assert_eq!(
+            policy.retry_after(ctx(&Method::GET, 3), &error),
+            Some(Duration::from_millis(1000))
+        );
+        assert_eq!(policy.retry_after(ctx(&Method::GET, 4), &error), None);
+    }
+
+    #[test]
+    fn test_exponential_backoff_skips_permanent_errors() {
+        let policy = ExponentialBackoff::default();
+        let error = ZeroXClientError::ZeroXInvalidResponseStatusCode(StatusCode::BAD_REQUEST);
+
+        assert_eq!(policy.retry_after(ctx(&Method::GET, 1), &error), None);
+    }
+
+    #[test]
+    fn test_exponential_backoff_skips_post() {
+        let policy = ExponentialBackoff::default();
+        let error = ZeroXClientError::ZeroXInvalidResponseStatusCode(StatusCode::TOO_MANY_REQUESTS);
+
+        assert_eq!(policy.retry_after(ctx(&Method::POST, 1), &error), None);
+    }
+}
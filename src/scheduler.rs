@@ -0,0 +1,240 @@
+use std::{
+    sync::{
+        atomic::{AtomicU32, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use async_trait::async_trait;
+use ethers::providers::Middleware;
+use tokio::{task::JoinHandle, time::MissedTickBehavior};
+use tracing::{debug, warn};
+
+use crate::{SwapExecutor, ZeroXClient, ZeroXQuoteParams, ZeroXQuoteResponse};
+
+/// Shortest interval a [`Schedule`] runs at; shorter ones, including zero, are raised to it.
+pub const MIN_INTERVAL: Duration = Duration::from_millis(1);
+
+/// When a recurring quote fires: every `interval`, starting after `start_after`, optionally
+/// stopping after `max_runs` handled quotes. Missed ticks are skipped rather than bunched up.
+///
+/// Only fixed intervals are supported, not calendar or cron expressions; to run at a time of
+/// day, set `start_after` to the time until then and `interval` to a day.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Schedule {
+    pub interval: Duration,
+    pub start_after: Duration,
+    pub max_runs: Option<u32>,
+}
+
+impl Schedule {
+    /// Runs every `interval`, at least [`MIN_INTERVAL`].
+    pub fn every(interval: Duration) -> Schedule {
+        Schedule {
+            interval: interval.max(MIN_INTERVAL),
+            start_after: Duration::ZERO,
+            max_runs: None,
+        }
+    }
+
+    pub fn start_after(mut self, delay: Duration) -> Self {
+        self.start_after = delay;
+        self
+    }
+
+    pub fn max_runs(mut self, runs: u32) -> Self {
+        self.max_runs = Some(runs);
+        self
+    }
+}
+
+/// Receives each quote produced by a [`QuoteScheduler`].
+#[async_trait]
+pub trait QuoteHandler: Send + Sync {
+    async fn handle(
+        &self,
+        quote: ZeroXQuoteResponse,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>>;
+}
+
+#[async_trait]
+impl<F> QuoteHandler for F
+where
+    F: Fn(ZeroXQuoteResponse) -> Result<(), Box<dyn std::error::Error + Send + Sync>> + Send + Sync,
+{
+    async fn handle(
+        &self,
+        quote: ZeroXQuoteResponse,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self(quote)
+    }
+}
+
+/// Executes every scheduled quote.
+#[async_trait]
+impl<M: Middleware + 'static> QuoteHandler for SwapExecutor<M> {
+    async fn handle(
+        &self,
+        quote: ZeroXQuoteResponse,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let tx_hash = self.execute_swap(&quote).await?;
+        debug!("scheduled swap sent: {:?}", tx_hash);
+        Ok(())
+    }
+}
+
+/// Fetches a fresh quote for `params` on a [`Schedule`] and hands it to a [`QuoteHandler`],
+/// e.g. for dollar-cost averaging. Set `taker_address` in `params` to get executable quotes.
+///
+/// A failed quote or handler call is logged and does not count as a run. The background
+/// task stops when the scheduler is dropped.
+pub struct QuoteScheduler {
+    runs: Arc<AtomicU32>,
+    task: JoinHandle<()>,
+}
+
+impl QuoteScheduler {
+    pub fn start(
+        client: Arc<ZeroXClient>,
+        params: ZeroXQuoteParams,
+        schedule: Schedule,
+        handler: Arc<dyn QuoteHandler>,
+    ) -> QuoteScheduler {
+        let runs = Arc::new(AtomicU32::new(0));
+        let task = tokio::spawn(run(client, params, schedule, handler, runs.clone()));
+
+        QuoteScheduler { runs, task }
+    }
+
+    /// Number of quotes handled successfully so far.
+    pub fn runs(&self) -> u32 {
+        self.runs.load(Ordering::Relaxed)
+    }
+
+    /// Whether the schedule has completed its `max_runs`.
+    pub fn is_finished(&self) -> bool {
+        self.task.is_finished()
+    }
+}
+
+impl Drop for QuoteScheduler {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+async fn run(
+    client: Arc<ZeroXClient>,
+    params: ZeroXQuoteParams,
+    schedule: Schedule,
+    handler: Arc<dyn QuoteHandler>,
+    runs: Arc<AtomicU32>,
+) {
+    let start = tokio::time::Instant::now() + schedule.start_after;
+    // the fields are public, so a zero interval can still get here and would panic
+    let mut ticks = tokio::time::interval_at(start, schedule.interval.max(MIN_INTERVAL));
+    ticks.set_missed_tick_behavior(MissedTickBehavior::Skip);
+
+    while schedule
+        .max_runs
+        .is_none_or(|max_runs| runs.load(Ordering::Relaxed) < max_runs)
+    {
+        ticks.tick().await;
+
+        let quote = match client.get_quote(params.clone()).await {
+            Ok(quote) => quote,
+            Err(err) => {
+                warn!("scheduled quote failed: {}", err);
+                continue;
+            }
+        };
+
+        match handler.handle(quote).await {
+            Ok(()) => {
+                runs.fetch_add(1, Ordering::Relaxed);
+            }
+            Err(err) => warn!("scheduled quote handler failed: {}", err),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+    use wiremock::{
+        matchers::{method, path},
+        Mock, MockServer, ResponseTemplate,
+    };
+
+    #[tokio::test]
+    async fn test_scheduler_hands_quotes_to_handler() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/swap/v1/quote"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(serde_json::json!({ "price": "2000" })),
+            )
+            .mount(&server)
+            .await;
+
+        let client = Arc::new(
+            ZeroXClient::builder(1, String::from("test"))
+                .base_url(server.uri())
+                .build()
+                .unwrap(),
+        );
+
+        let prices = Arc::new(Mutex::new(Vec::new()));
+        let handler = {
+            let prices = prices.clone();
+            move |quote: ZeroXQuoteResponse| -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+                prices.lock().unwrap().push(quote.price);
+                Ok(())
+            }
+        };
+
+        let scheduler = QuoteScheduler::start(
+            client.clone(),
+            ZeroXQuoteParams::default(),
+            Schedule::every(Duration::from_millis(20)).max_runs(3),
+            Arc::new(handler),
+        );
+
+        for _ in 0..100 {
+            if scheduler.is_finished() {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+
+        assert!(scheduler.is_finished());
+        assert_eq!(scheduler.runs(), 3);
+        assert_eq!(prices.lock().unwrap().len(), 3);
+
+        // a zero interval used to panic the task, which then never finished
+        assert_eq!(Schedule::every(Duration::ZERO).interval, MIN_INTERVAL);
+        let scheduler = QuoteScheduler::start(
+            client,
+            ZeroXQuoteParams::default(),
+            Schedule {
+                interval: Duration::ZERO,
+                start_after: Duration::ZERO,
+                max_runs: Some(2),
+            },
+            Arc::new(
+                |_: ZeroXQuoteResponse| -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+                    Ok(())
+                },
+            ),
+        );
+        for _ in 0..100 {
+            if scheduler.is_finished() {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        assert_eq!(scheduler.runs(), 2);
+    }
+}
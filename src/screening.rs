@@ -0,0 +1,84 @@
+use async_trait::async_trait;
+
+/// Addresses involved in a quote or swap that a [`TokenScreener`] can inspect.
+#[derive(Debug, Clone, Copy)]
+pub struct ScreeningRequest<'a> {
+    pub chain_id: u64,
+    pub sell_token: &'a str,
+    pub buy_token: &'a str,
+    pub taker: Option<&'a str>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScreeningRejection {
+    /// The token or account address that was blocked.
+    pub address: String,
+    pub reason: String,
+}
+
+impl std::fmt::Display for ScreeningRejection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} rejected: {}", self.address, self.reason)
+    }
+}
+
+/// Screens tokens and accounts before quoting or executing, e.g. to block scam tokens,
+/// honeypots or sanctioned addresses.
+#[async_trait]
+pub trait TokenScreener: Send + Sync {
+    async fn screen(&self, request: ScreeningRequest<'_>) -> Result<(), ScreeningRejection>;
+}
+
+/// Allows everything.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoScreening;
+
+#[async_trait]
+impl TokenScreener for NoScreening {
+    async fn screen(&self, _request: ScreeningRequest<'_>) -> Result<(), ScreeningRejection> {
+        Ok(())
+    }
+}
+
+/// Rejects a fixed set of addresses, compared case-insensitively.
+#[derive(Debug, Clone, Default)]
+pub struct Blocklist {
+    addresses: Vec<String>,
+}
+
+impl Blocklist {
+    pub fn new<I, S>(addresses: I) -> Blocklist
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Blocklist {
+            addresses: addresses
+                .into_iter()
+                .map(|address| address.into().to_lowercase())
+                .collect(),
+        }
+    }
+}
+
+#[async_trait]
+impl TokenScreener for Blocklist {
+    async fn screen(&self, request: ScreeningRequest<'_>) -> Result<(), ScreeningRejection> {
+        let candidates = [
+            Some(request.sell_token),
+            Some(request.buy_token),
+            request.taker,
+        ];
+
+        for address in candidates.into_iter().flatten() {
+            if self.addresses.contains(&address.to_lowercase()) {
+                return Err(ScreeningRejection {
+                    address: address.to_string(),
+                    reason: String::from("blocklisted"),
+                });
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,515 @@
+//! A small caching proxy exposing `/quote` and `/price`, so frontends can get 0x quotes
+//! without embedding an API key.
+
+use std::{
+    collections::HashMap,
+    net::SocketAddr,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use async_trait::async_trait;
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::get,
+    Json, Router,
+};
+use serde::Deserialize;
+use serde_json::Value;
+use tracing::warn;
+
+use crate::{
+    quote_query, rate_limit::RateLimiter, Percentage, ZeroXClient, ZeroXClientError,
+    ZeroXQuoteParams,
+};
+
+#[derive(Debug, Clone, Copy)]
+pub struct ServerConfig {
+    /// How long identical requests are served from cache.
+    pub cache_ttl: Duration,
+    /// Requests accepted per `(requests, per)` across all callers. Excess requests get a 429.
+    pub rate_limit: Option<(u32, Duration)>,
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        ServerConfig {
+            cache_ttl: Duration::from_secs(2),
+            rate_limit: Some((10, Duration::from_secs(1))),
+        }
+    }
+}
+
+/// Query parameters, named as in the 0x API.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ProxyQuery {
+    sell_token: String,
+    buy_token: String,
+    sell_amount: String,
+    taker_address: Option<String>,
+    slippage_percentage: Option<Percentage>,
+    excluded_sources: Option<String>,
+    included_sources: Option<String>,
+}
+
+impl From<ProxyQuery> for ZeroXQuoteParams {
+    fn from(query: ProxyQuery) -> ZeroXQuoteParams {
+        let split = |sources: String| sources.split(',').map(String::from).collect();
+
+        ZeroXQuoteParams {
+            sell_token: query.sell_token,
+            buy_token: query.buy_token,
+            sell_amount: query.sell_amount,
+            taker_address: query.taker_address,
+            slippage_percentage: query.slippage_percentage,
+            excluded_sources: query.excluded_sources.map(split),
+            included_sources: query.included_sources.map(split),
+            ..Default::default()
+        }
+    }
+}
+
+/// Storage for proxied responses, keyed by chain, path and query. Implement it to serve the
+/// cache from an existing layer, see [`router_with_cache`].
+#[async_trait]
+pub trait CacheBackend: Send + Sync {
+    async fn get(
+        &self,
+        key: &str,
+    ) -> Result<Option<Value>, Box<dyn std::error::Error + Send + Sync>>;
+
+    /// Stores `value` for `ttl`, after which `get` must no longer return it.
+    async fn set(
+        &self,
+        key: &str,
+        value: &Value,
+        ttl: Duration,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>>;
+}
+
+/// The in-process cache used by [`router`].
+#[derive(Debug, Default)]
+pub struct MemoryCache {
+    entries: Mutex<HashMap<String, (Value, Instant)>>,
+}
+
+#[async_trait]
+impl CacheBackend for MemoryCache {
+    async fn get(
+        &self,
+        key: &str,
+    ) -> Result<Option<Value>, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(self
+            .entries
+            .lock()
+            .unwrap()
+            .get(key)
+            .filter(|(_, expires_at)| Instant::now() < *expires_at)
+            .map(|(value, _)| value.clone()))
+    }
+
+    async fn set(
+        &self,
+        key: &str,
+        value: &Value,
+        ttl: Duration,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let now = Instant::now();
+        let mut entries = self.entries.lock().unwrap();
+        entries.retain(|_, (_, expires_at)| now < *expires_at);
+        entries.insert(key.to_string(), (value.clone(), now + ttl));
+        Ok(())
+    }
+}
+
+/// A cache and rate limit shared through Redis, so a fleet of proxies serves each price
+/// once per TTL and together stays within one API key's limit. See [`router_with_redis`].
+#[cfg(feature = "redis")]
+#[derive(Clone)]
+pub struct RedisCache {
+    conn: redis::aio::ConnectionManager,
+    prefix: String,
+}
+
+#[cfg(feature = "redis")]
+impl RedisCache {
+    /// Connects to `url`, e.g. `redis://127.0.0.1/`. Keys are namespaced under `prefix`.
+    pub async fn connect(url: &str, prefix: impl Into<String>) -> redis::RedisResult<RedisCache> {
+        let client = redis::Client::open(url)?;
+        Ok(RedisCache {
+            conn: redis::aio::ConnectionManager::new(client).await?,
+            prefix: prefix.into(),
+        })
+    }
+
+    /// Counts a request against a fixed window of `per` shared by all instances.
+    async fn try_acquire(&self, requests: u32, per: Duration) -> redis::RedisResult<bool> {
+        let per = (per.as_millis() as u64).max(1);
+        let window = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis() as u64
+            / per;
+        let key = format!("{}:rate:{}", self.prefix, window);
+
+        let (count,): (u64,) = redis::pipe()
+            .atomic()
+            .cmd("INCR")
+            .arg(&key)
+            .cmd("PEXPIRE")
+            .arg(&key)
+            .arg(per)
+            .ignore()
+            .query_async(&mut self.conn.clone())
+            .await?;
+        Ok(count <= u64::from(requests))
+    }
+}
+
+#[cfg(feature = "redis")]
+#[async_trait]
+impl CacheBackend for RedisCache {
+    async fn get(
+        &self,
+        key: &str,
+    ) -> Result<Option<Value>, Box<dyn std::error::Error + Send + Sync>> {
+        let value: Option<String> = redis::cmd("GET")
+            .arg(format!("{}:cache:{}", self.prefix, key))
+            .query_async(&mut self.conn.clone())
+            .await?;
+        Ok(value.and_then(|value| serde_json::from_str(&value).ok()))
+    }
+
+    async fn set(
+        &self,
+        key: &str,
+        value: &Value,
+        ttl: Duration,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        redis::cmd("SET")
+            .arg(format!("{}:cache:{}", self.prefix, key))
+            .arg(value.to_string())
+            .arg("PX")
+            .arg(ttl.as_millis() as u64)
+            .query_async::<_, ()>(&mut self.conn.clone())
+            .await?;
+        Ok(())
+    }
+}
+
+struct ProxyState {
+    client: ZeroXClient,
+    cache_ttl: Duration,
+    cache: Arc<dyn CacheBackend>,
+    #[cfg(feature = "redis")]
+    rate_limit: Option<(u32, Duration)>,
+    rate_limiter: Option<RateLimiter>,
+    #[cfg(feature = "redis")]
+    redis: Option<RedisCache>,
+}
+
+impl ProxyState {
+    fn cache_key(&self, path: &str, params: &ZeroXQuoteParams) -> String {
+        let mut query = quote_query(params.clone()).into_iter().collect::<Vec<_>>();
+        query.sort();
+        format!("{}:{}?{:?}", self.client.chain_id(), path, query)
+    }
+
+    async fn cached(&self, key: &str) -> Option<Value> {
+        self.cache
+            .get(key)
+            .await
+            .map_err(|err| warn!("cache lookup failed: {}", err))
+            .ok()
+            .flatten()
+    }
+
+    async fn store(&self, key: String, value: Value) {
+        if self.cache_ttl.is_zero() {
+            return;
+        }
+        if let Err(err) = self.cache.set(&key, &value, self.cache_ttl).await {
+            warn!("cache store failed: {}", err);
+        }
+    }
+
+    async fn try_acquire(&self) -> bool {
+        #[cfg(feature = "redis")]
+        if let (Some(redis), Some((requests, per))) = (&self.redis, self.rate_limit) {
+            // fail open: an unreachable Redis should not take the proxy down with it
+            return redis
+                .try_acquire(requests, per)
+                .await
+                .map_err(|err| warn!("redis rate limit check failed: {}", err))
+                .unwrap_or(true);
+        }
+
+        self.rate_limiter
+            .as_ref()
+            .is_none_or(|rate_limiter| rate_limiter.try_acquire())
+    }
+}
+
+/// Builds the proxy service. Mount it under any prefix or serve it with [`serve`].
+pub fn router(client: ZeroXClient, config: ServerConfig) -> Router {
+    router_with_cache(client, config, Arc::new(MemoryCache::default()))
+}
+
+/// Like [`router`], but caches responses in `cache` instead of in process.
+pub fn router_with_cache(
+    client: ZeroXClient,
+    config: ServerConfig,
+    cache: Arc<dyn CacheBackend>,
+) -> Router {
+    routes(ProxyState {
+        client,
+        cache_ttl: config.cache_ttl,
+        cache,
+        #[cfg(feature = "redis")]
+        rate_limit: config.rate_limit,
+        rate_limiter: config
+            .rate_limit
+            .map(|(requests, per)| RateLimiter::new(requests, per)),
+        #[cfg(feature = "redis")]
+        redis: None,
+    })
+}
+
+/// Like [`router`], but caches responses and enforces `config.rate_limit` in Redis, across
+/// every instance sharing `redis`.
+#[cfg(feature = "redis")]
+pub fn router_with_redis(client: ZeroXClient, config: ServerConfig, redis: RedisCache) -> Router {
+    routes(ProxyState {
+        client,
+        cache_ttl: config.cache_ttl,
+        cache: Arc::new(redis.clone()),
+        rate_limit: config.rate_limit,
+        rate_limiter: None,
+        redis: Some(redis),
+    })
+}
+
+fn routes(state: ProxyState) -> Router {
+    Router::new()
+        .route("/quote", get(quote))
+        .route("/price", get(price))
+        .with_state(Arc::new(state))
+}
+
+pub async fn serve(
+    addr: SocketAddr,
+    client: ZeroXClient,
+    config: ServerConfig,
+) -> Result<(), axum::Error> {
+    axum::Server::bind(&addr)
+        .serve(router(client, config).into_make_service())
+        .await
+        .map_err(axum::Error::new)
+}
+
+async fn quote(State(state): State<Arc<ProxyState>>, Query(query): Query<ProxyQuery>) -> Response {
+    handle(&state, "/quote", query.into()).await
+}
+
+async fn price(State(state): State<Arc<ProxyState>>, Query(query): Query<ProxyQuery>) -> Response {
+    handle(&state, "/price", query.into()).await
+}
+
+async fn handle(state: &ProxyState, path: &str, params: ZeroXQuoteParams) -> Response {
+    let key = state.cache_key(path, &params);
+    if let Some(value) = state.cached(&key).await {
+        return Json(value).into_response();
+    }
+
+    if !state.try_acquire().await {
+        return error_response(StatusCode::TOO_MANY_REQUESTS, "rate limit exceeded");
+    }
+
+    let result = match path {
+        "/quote" => state.client.get_quote(params).await,
+        _ => state.client.get_price(params).await,
+    };
+
+    let value = match result.map(serde_json::to_value) {
+        Ok(Ok(value)) => value,
+        Ok(Err(err)) => return error_response(StatusCode::INTERNAL_SERVER_ERROR, err),
+        Err(err) => return error_response(status_for(&err), err),
+    };
+
+    state.store(key, value.clone()).await;
+    Json(value).into_response()
+}
+
+fn status_for(err: &ZeroXClientError) -> StatusCode {
+    match err {
+        ZeroXClientError::ZeroXInvalidResponseStatusCode(status) if status.is_client_error() => {
+            StatusCode::from_u16(status.as_u16()).unwrap_or(StatusCode::BAD_GATEWAY)
+        }
+        ZeroXClientError::ScreeningRejected(_) | ZeroXClientError::PolicyViolation(_) => {
+            StatusCode::FORBIDDEN
+        }
+        _ => StatusCode::BAD_GATEWAY,
+    }
+}
+
+fn error_response(status: StatusCode, err: impl ToString) -> Response {
+    (
+        status,
+        Json(serde_json::json!({ "error": err.to_string() })),
+    )
+        .into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::{
+        matchers::{method, path},
+        Mock, MockServer, ResponseTemplate,
+    };
+
+    async fn spawn(config: ServerConfig) -> String {
+        spawn_with(|client| router(client, config)).await
+    }
+
+    async fn spawn_with(router: impl FnOnce(ZeroXClient) -> Router) -> String {
+        let upstream = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/swap/v1/price"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(serde_json::json!({ "price": "2000" })),
+            )
+            .expect(1)
+            .mount(&upstream)
+            .await;
+
+        let client = ZeroXClient::builder(1, String::from("test"))
+            .base_url(upstream.uri())
+            .build()
+            .unwrap();
+
+        let server = axum::Server::bind(&"127.0.0.1:0".parse().unwrap())
+            .serve(router(client).into_make_service());
+        let addr = server.local_addr();
+        tokio::spawn(async move {
+            // keep the upstream mock alive for the lifetime of the server
+            let _upstream = upstream;
+            server.await.unwrap();
+        });
+
+        format!("http://{}", addr)
+    }
+
+    #[tokio::test]
+    async fn test_proxy_caches_prices() {
+        let base = spawn(ServerConfig::default()).await;
+        let url = format!("{}/price?sellToken=ETH&buyToken=DAI&sellAmount=1", base);
+
+        for _ in 0..2 {
+            let body: Value = reqwest::get(&url).await.unwrap().json().await.unwrap();
+            assert_eq!(body["price"], "2000");
+        }
+
+        let resp = reqwest::get(format!("{}/price?sellToken=ETH", base))
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), reqwest::StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn test_proxy_rate_limits() {
+        let base = spawn(ServerConfig {
+            cache_ttl: Duration::ZERO,
+            rate_limit: Some((1, Duration::from_secs(60))),
+        })
+        .await;
+        let url = format!("{}/price?sellToken=ETH&buyToken=DAI&sellAmount=1", base);
+
+        assert!(reqwest::get(&url).await.unwrap().status().is_success());
+        assert_eq!(
+            reqwest::get(&url).await.unwrap().status(),
+            reqwest::StatusCode::TOO_MANY_REQUESTS
+        );
+    }
+
+    #[derive(Default)]
+    struct RecordingCache {
+        inner: MemoryCache,
+        stored: Mutex<Vec<(String, Duration)>>,
+    }
+
+    #[async_trait]
+    impl CacheBackend for RecordingCache {
+        async fn get(
+            &self,
+            key: &str,
+        ) -> Result<Option<Value>, Box<dyn std::error::Error + Send + Sync>> {
+            self.inner.get(key).await
+        }
+
+        async fn set(
+            &self,
+            key: &str,
+            value: &Value,
+            ttl: Duration,
+        ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+            self.stored.lock().unwrap().push((key.to_string(), ttl));
+            self.inner.set(key, value, ttl).await
+        }
+    }
+
+    #[tokio::test]
+    async fn test_custom_cache_backend() {
+        let cache = Arc::new(RecordingCache::default());
+        let config = ServerConfig {
+            cache_ttl: Duration::from_secs(30),
+            rate_limit: None,
+        };
+        let base = spawn_with(|client| router_with_cache(client, config, cache.clone())).await;
+        let url = format!("{}/price?sellToken=ETH&buyToken=DAI&sellAmount=1", base);
+
+        // the upstream mock expects a single request, so the second is served from `cache`
+        for _ in 0..2 {
+            assert!(reqwest::get(&url).await.unwrap().status().is_success());
+        }
+
+        let stored = cache.stored.lock().unwrap();
+        assert_eq!(stored.len(), 1);
+        assert!(stored[0].0.starts_with("1:/price?"));
+        assert_eq!(stored[0].1, Duration::from_secs(30));
+    }
+
+    #[cfg(feature = "redis")]
+    #[tokio::test]
+    async fn test_redis_cache_is_shared() {
+        dotenv::dotenv().ok();
+
+        let prefix = format!("zerox-test-{}", std::process::id());
+        let redis = RedisCache::connect(&std::env::var("REDIS_URL").unwrap(), prefix)
+            .await
+            .unwrap();
+        let config = ServerConfig {
+            cache_ttl: Duration::from_secs(60),
+            rate_limit: Some((1, Duration::from_secs(60))),
+        };
+
+        let first = spawn_with(|client| router_with_redis(client, config, redis.clone())).await;
+        let second = spawn_with(|client| router_with_redis(client, config, redis)).await;
+        let price = "/price?sellToken=ETH&buyToken=DAI&sellAmount=1";
+
+        // the second instance is served from the first one's cache entry...
+        for base in [&first, &second] {
+            let resp = reqwest::get(format!("{}{}", base, price)).await.unwrap();
+            assert!(resp.status().is_success());
+        }
+
+        // ...and shares its rate limit
+        let resp = reqwest::get(format!("{}{}&takerAddress=0x01", second, price))
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), reqwest::StatusCode::TOO_MANY_REQUESTS);
+    }
+}
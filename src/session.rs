@@ -0,0 +1,230 @@
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use tokio::{sync::watch, task::JoinHandle};
+use tracing::debug;
+
+use crate::{CachedQuote, ZeroXClient, ZeroXClientError, ZeroXQuoteParams};
+
+#[derive(Debug, Clone)]
+pub struct QuoteSessionConfig {
+    /// How often to re-quote.
+    pub interval: Duration,
+    /// Quotes older than this are not handed out.
+    pub max_age: Duration,
+    /// If set, a new quote only replaces the current one when its price moved by more than
+    /// this fraction (e.g. `0.005` for 0.5%) or the current one would go stale before the
+    /// next re-quote. Otherwise every new quote replaces the current one.
+    pub price_drift: Option<f64>,
+}
+
+impl Default for QuoteSessionConfig {
+    fn default() -> Self {
+        QuoteSessionConfig {
+            interval: Duration::from_secs(2),
+            max_age: Duration::from_secs(15),
+            price_drift: None,
+        }
+    }
+}
+
+/// Keeps an executable quote fresh between quoting and sending the transaction.
+///
+/// The session re-quotes in the background until [`QuoteSession::commit`] is called or the
+/// session is dropped.
+pub struct QuoteSession {
+    current: watch::Receiver<CachedQuote>,
+    max_age: Duration,
+    task: JoinHandle<()>,
+}
+
+impl QuoteSession {
+    /// Fetches the initial quote and starts refreshing it.
+    pub async fn start(
+        client: Arc<ZeroXClient>,
+        params: ZeroXQuoteParams,
+        config: QuoteSessionConfig,
+    ) -> Result<QuoteSession, ZeroXClientError> {
+        let quote = client.get_quote(params.clone()).await?;
+        let (sender, current) = watch::channel(CachedQuote {
+            quote: Arc::new(quote),
+            fetched_at: Instant::now(),
+        });
+
+        let max_age = config.max_age;
+        let task = tokio::spawn(refresh_loop(client, params, config, sender));
+
+        Ok(QuoteSession {
+            current,
+            max_age,
+            task,
+        })
+    }
+
+    /// Returns the current quote, or `StaleQuote` if refreshing has been failing for longer
+    /// than `max_age`.
+    pub fn current(&self) -> Result<CachedQuote, ZeroXClientError> {
+        let current = self.current.borrow().clone();
+
+        if current.age() > self.max_age {
+            return Err(ZeroXClientError::StaleQuote(current.age()));
+        }
+
+        Ok(current)
+    }
+
+    /// Notifies whenever the current quote is replaced.
+    pub fn subscribe(&self) -> watch::Receiver<CachedQuote> {
+        self.current.clone()
+    }
+
+    /// Stops refreshing and returns the quote to execute.
+    pub fn commit(self) -> Result<CachedQuote, ZeroXClientError> {
+        self.task.abort();
+        self.current()
+    }
+}
+
+impl Drop for QuoteSession {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+async fn refresh_loop(
+    client: Arc<ZeroXClient>,
+    params: ZeroXQuoteParams,
+    config: QuoteSessionConfig,
+    sender: watch::Sender<CachedQuote>,
+) {
+    loop {
+        tokio::time::sleep(config.interval).await;
+
+        let quote = match client.get_quote(params.clone()).await {
+            Ok(quote) => quote,
+            Err(err) => {
+                debug!("failed to refresh quote session: {}", err);
+                continue;
+            }
+        };
+
+        let replace = match config.price_drift {
+            None => true,
+            Some(threshold) => {
+                let current = sender.borrow();
+                current.age() + config.interval >= config.max_age
+                    || price_drift(current.quote.price.as_deref(), quote.price.as_deref())
+                        .is_none_or(|drift| drift > threshold)
+            }
+        };
+
+        if replace {
+            sender.send_replace(CachedQuote {
+                quote: Arc::new(quote),
+                fetched_at: Instant::now(),
+            });
+        }
+    }
+}
+
+fn price_drift(current: Option<&str>, new: Option<&str>) -> Option<f64> {
+    let current = current?.parse::<f64>().ok()?;
+    let new = new?.parse::<f64>().ok()?;
+
+    if current == 0.0 {
+        return None;
+    }
+
+    Some(((new - current) / current).abs())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::{
+        matchers::{method, path},
+        Mock, MockServer, ResponseTemplate,
+    };
+
+    async fn mount_price(server: &MockServer, price: &str, times: Option<u64>) {
+        let mock = Mock::given(method("GET"))
+            .and(path("/swap/v1/quote"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(serde_json::json!({ "price": price })),
+            );
+
+        match times {
+            Some(times) => mock.up_to_n_times(times).mount(server).await,
+            None => mock.mount(server).await,
+        }
+    }
+
+    fn client(server: &MockServer) -> Arc<ZeroXClient> {
+        Arc::new(
+            ZeroXClient::builder(1, String::from("test"))
+                .base_url(server.uri())
+                .retry_policy(crate::NoRetry)
+                .build()
+                .unwrap(),
+        )
+    }
+
+    #[test]
+    fn test_price_drift() {
+        assert_eq!(price_drift(Some("100"), Some("101")), Some(0.01));
+        assert_eq!(price_drift(Some("100"), None), None);
+    }
+
+    #[tokio::test]
+    async fn test_session_ignores_small_drift() {
+        let server = MockServer::start().await;
+        mount_price(&server, "100", Some(1)).await;
+        mount_price(&server, "100.1", None).await;
+
+        let session = QuoteSession::start(
+            client(&server),
+            ZeroXQuoteParams::default(),
+            QuoteSessionConfig {
+                interval: Duration::from_millis(20),
+                max_age: Duration::from_secs(10),
+                price_drift: Some(0.01),
+            },
+        )
+        .await
+        .unwrap();
+
+        tokio::time::sleep(Duration::from_millis(100)).await;
+
+        let quote = session.commit().unwrap();
+        assert_eq!(quote.quote.price.as_deref(), Some("100"));
+    }
+
+    #[tokio::test]
+    async fn test_session_goes_stale_when_refresh_fails() {
+        let server = MockServer::start().await;
+        mount_price(&server, "100", Some(1)).await;
+
+        let session = QuoteSession::start(
+            client(&server),
+            ZeroXQuoteParams::default(),
+            QuoteSessionConfig {
+                interval: Duration::from_millis(20),
+                max_age: Duration::from_millis(60),
+                price_drift: None,
+            },
+        )
+        .await
+        .unwrap();
+
+        assert!(session.current().is_ok());
+
+        tokio::time::sleep(Duration::from_millis(100)).await;
+
+        assert!(matches!(
+            session.commit(),
+            Err(ZeroXClientError::StaleQuote(_))
+        ));
+    }
+}
@@ -0,0 +1,166 @@
+//! Simulating a quote's transaction with `eth_call` state overrides that give the taker the
+//! sell token balance and allowance it needs, so calldata can be checked for wallets that are
+//! not funded or approved yet. Needs an RPC node that supports the state override parameter.
+
+use ethers::{
+    core::types::{
+        spoof, transaction::eip2718::TypedTransaction, Address, Bytes, TransactionRequest, H256,
+        U256,
+    },
+    providers::{call_raw::RawCall, Middleware, ProviderError},
+    utils::keccak256,
+};
+use thiserror::Error;
+
+use crate::{approval::sells_native_token, QuoteFieldError, ZeroXQuoteResponse};
+
+#[derive(Error, Debug)]
+pub enum SimulationError {
+    #[error(transparent)]
+    Quote(#[from] QuoteFieldError),
+
+    #[error("Simulation failed: {0}")]
+    Call(#[from] ProviderError),
+}
+
+/// Storage slots of an ERC-20's `balanceOf` and `allowance` mappings, which differ between
+/// token implementations.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StorageLayout {
+    pub balance_slot: U256,
+    pub allowance_slot: U256,
+}
+
+impl StorageLayout {
+    /// OpenZeppelin's `ERC20`.
+    pub const OPENZEPPELIN: StorageLayout = StorageLayout::new(0, 1);
+    /// WETH9 and tokens with the same layout, e.g. solmate's `ERC20`.
+    pub const WETH9: StorageLayout = StorageLayout::new(3, 4);
+
+    pub const fn new(balance_slot: u64, allowance_slot: u64) -> StorageLayout {
+        StorageLayout {
+            balance_slot: U256([balance_slot, 0, 0, 0]),
+            allowance_slot: U256([allowance_slot, 0, 0, 0]),
+        }
+    }
+
+    fn balance_key(&self, owner: Address) -> H256 {
+        mapping_key(owner.into(), self.balance_slot)
+    }
+
+    fn allowance_key(&self, owner: Address, spender: Address) -> H256 {
+        let inner = mapping_key(owner.into(), self.allowance_slot);
+        mapping_key(spender.into(), U256::from_big_endian(inner.as_bytes()))
+    }
+}
+
+/// Storage key of `mapping[key]` for a mapping at `slot`.
+fn mapping_key(key: H256, slot: U256) -> H256 {
+    let mut preimage = [0u8; 64];
+    preimage[..32].copy_from_slice(key.as_bytes());
+    slot.to_big_endian(&mut preimage[32..]);
+    H256(keccak256(preimage))
+}
+
+fn word(value: U256) -> H256 {
+    let mut word = H256::zero();
+    value.to_big_endian(word.as_bytes_mut());
+    word
+}
+
+/// Overrides giving `taker` native balance for value and gas, and, unless the quote sells the
+/// native token, the quote's sell amount as token balance and allowance for its allowance
+/// target.
+pub fn funding_overrides(
+    quote: &ZeroXQuoteResponse,
+    taker: Address,
+    layout: StorageLayout,
+) -> Result<spoof::State, QuoteFieldError> {
+    let mut state = spoof::state();
+    state.account(taker).balance(U256::MAX >> 1);
+
+    if !sells_native_token(quote) {
+        let amount = word(quote.sell_amount()?);
+        let spender = quote.allowance_target()?;
+        state
+            .account(quote.sell_token()?)
+            .store(layout.balance_key(taker), amount)
+            .store(layout.allowance_key(taker, spender), amount);
+    }
+
+    Ok(state)
+}
+
+/// Runs the quote's transaction from `taker` with [`funding_overrides`] and returns its
+/// output. A revert is returned as an error.
+pub async fn simulate_funded<M: Middleware>(
+    provider: &M,
+    quote: &ZeroXQuoteResponse,
+    taker: Address,
+    layout: StorageLayout,
+) -> Result<Bytes, SimulationError> {
+    let state = funding_overrides(quote, taker, layout)?;
+    let tx: TypedTransaction = TransactionRequest::new()
+        .from(taker)
+        .to(quote.to()?)
+        .data(quote.data()?)
+        .value(quote.value()?)
+        .into();
+
+    Ok(provider.provider().call_raw(&tx).state(&state).await?)
+}
+
+#[cfg(test)]
+mod tests {
+    use ethers::providers::Provider;
+
+    use super::*;
+
+    static TAKER: &str = "0xd8dA6BF26964aF9D7eEd9e03E53415D37aA96045";
+    static DAI: &str = "0x6b175474e89094c44da98b954eedeac495271d0f";
+
+    fn quote() -> ZeroXQuoteResponse {
+        serde_json::from_value(serde_json::json!({
+            "to": "0xdef1c0ded9bec7f1a1670819833240f027b25eff",
+            "data": "0xd9627aa4",
+            "value": "0",
+            "sellTokenAddress": DAI,
+            "sellAmount": "1000",
+            "allowanceTarget": "0xdef1c0ded9bec7f1a1670819833240f027b25eff",
+        }))
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_simulate_funded() {
+        let taker: Address = TAKER.parse().unwrap();
+        let state = funding_overrides(&quote(), taker, StorageLayout::OPENZEPPELIN).unwrap();
+        let state = serde_json::to_value(state).unwrap();
+
+        let token = &state[format!("{:?}", DAI.parse::<Address>().unwrap())];
+        let diff = token["stateDiff"].as_object().unwrap();
+        assert_eq!(diff.len(), 2);
+        assert!(
+            diff.values()
+                .all(|value| value
+                    == &serde_json::json!(format!("{:?}", H256::from_low_u64_be(1000))))
+        );
+        assert!(state[format!("{taker:?}")]["balance"].is_string());
+
+        let (provider, mock) = Provider::mocked();
+        mock.push::<Bytes, _>(Bytes::from(vec![1u8])).unwrap();
+        let output = simulate_funded(&provider, &quote(), taker, StorageLayout::OPENZEPPELIN)
+            .await
+            .unwrap();
+        assert_eq!(output, Bytes::from(vec![1u8]));
+    }
+
+    #[test]
+    fn test_mapping_key() {
+        // balanceOf(0x...01) of a token with balances at slot 0
+        assert_eq!(
+            format!("{:?}", mapping_key(H256::from_low_u64_be(1), U256::zero())),
+            "0xada5013122d395ba3c54772283fb069b10426056ef8ca54750cb9bb552a59e7d"
+        );
+    }
+}
@@ -0,0 +1,104 @@
+//! Publishing quotes to external market-data pipelines.
+
+use std::{
+    sync::Arc,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use async_trait::async_trait;
+use serde::Serialize;
+
+use crate::ZeroXQuoteResponse;
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum QuoteEventKind {
+    Quote,
+    Price,
+}
+
+/// A quote or price fetched for a pair.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QuoteEvent {
+    pub kind: QuoteEventKind,
+    pub chain_id: u64,
+    pub sell_token: String,
+    pub buy_token: String,
+    pub sell_amount: String,
+    /// Unix timestamp in milliseconds.
+    pub fetched_at: u64,
+    pub quote: Arc<ZeroXQuoteResponse>,
+}
+
+impl QuoteEvent {
+    pub fn new(
+        kind: QuoteEventKind,
+        chain_id: u64,
+        sell_token: impl Into<String>,
+        buy_token: impl Into<String>,
+        sell_amount: impl Into<String>,
+        quote: Arc<ZeroXQuoteResponse>,
+    ) -> QuoteEvent {
+        QuoteEvent {
+            kind,
+            chain_id,
+            sell_token: sell_token.into(),
+            buy_token: buy_token.into(),
+            sell_amount: sell_amount.into(),
+            fetched_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_millis() as u64,
+            quote,
+        }
+    }
+}
+
+/// Receives quote events, e.g. from a [`Watchlist`](crate::Watchlist), and forwards them to a
+/// message bus.
+#[async_trait]
+pub trait QuoteSink: Send + Sync {
+    async fn publish(
+        &self,
+        event: &QuoteEvent,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>>;
+}
+
+/// Publishes events as JSON to `{prefix}.{chain_id}.{sell_token}.{buy_token}`.
+#[cfg(feature = "nats")]
+pub struct NatsSink {
+    client: async_nats::Client,
+    prefix: String,
+}
+
+#[cfg(feature = "nats")]
+impl NatsSink {
+    pub fn new(client: async_nats::Client, prefix: impl Into<String>) -> NatsSink {
+        NatsSink {
+            client,
+            prefix: prefix.into(),
+        }
+    }
+
+    fn subject(&self, event: &QuoteEvent) -> String {
+        format!(
+            "{}.{}.{}.{}",
+            self.prefix, event.chain_id, event.sell_token, event.buy_token
+        )
+    }
+}
+
+#[cfg(feature = "nats")]
+#[async_trait]
+impl QuoteSink for NatsSink {
+    async fn publish(
+        &self,
+        event: &QuoteEvent,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.client
+            .publish(self.subject(event), serde_json::to_vec(event)?.into())
+            .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,296 @@
+//! Capturing quotes with their parameters to a JSON lines archive, and replaying them through
+//! [`TransportClient`] so backtests use the same typed API as live trading.
+
+use std::{
+    collections::HashMap,
+    fs::{File, OpenOptions},
+    io::{BufRead, BufReader, Write},
+    path::Path,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex,
+    },
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use async_trait::async_trait;
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use thiserror::Error;
+
+use crate::{
+    quote_query,
+    transport::{HttpResponse, HttpTransport, TransportClient},
+    ZeroXClient, ZeroXClientError, ZeroXQuoteParams, ZeroXQuoteResponse,
+};
+
+#[derive(Error, Debug)]
+pub enum SnapshotError {
+    #[error("Snapshot archive I/O error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Failed to encode snapshot: {0}")]
+    Encode(serde_json::Error),
+
+    #[error("Invalid snapshot on line {line}: {source}")]
+    Invalid {
+        line: usize,
+        source: serde_json::Error,
+    },
+}
+
+/// A quote or price as returned by the API, with the request that produced it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QuoteSnapshot {
+    /// Unix timestamp in milliseconds.
+    pub captured_at: u64,
+    pub chain_id: u64,
+    /// e.g. `/swap/v1/quote`.
+    pub path: String,
+    pub params: ZeroXQuoteParams,
+    /// The full response body, including fields [`ZeroXQuoteResponse`] does not model.
+    pub response: Value,
+}
+
+impl QuoteSnapshot {
+    pub fn response(&self) -> Result<ZeroXQuoteResponse, serde_json::Error> {
+        ZeroXQuoteResponse::deserialize(&self.response)
+    }
+
+    /// The query parameters replayed requests are matched on.
+    fn key(path: &str, query: &HashMap<&str, String>) -> String {
+        let field = |name: &str| query.get(name).map(String::as_str).unwrap_or_default();
+        format!(
+            "{} {} {} {} {}",
+            path,
+            field("sellToken").to_lowercase(),
+            field("buyToken").to_lowercase(),
+            field("sellAmount"),
+            field("buyAmount"),
+        )
+    }
+}
+
+impl ZeroXClient {
+    /// Fetches a quote and keeps the request and full response for archiving.
+    pub async fn get_quote_snapshot(
+        &self,
+        params: ZeroXQuoteParams,
+    ) -> Result<QuoteSnapshot, ZeroXClientError> {
+        self.snapshot("/swap/v1/quote", params).await
+    }
+
+    /// Fetches a price and keeps the request and full response for archiving.
+    pub async fn get_price_snapshot(
+        &self,
+        params: ZeroXQuoteParams,
+    ) -> Result<QuoteSnapshot, ZeroXClientError> {
+        self.snapshot("/swap/v1/price", params).await
+    }
+
+    async fn snapshot(
+        &self,
+        path: &str,
+        params: ZeroXQuoteParams,
+    ) -> Result<QuoteSnapshot, ZeroXClientError> {
+        self.screen(&params).await?;
+        let response = self
+            .get::<Value>(path, &self.quote_query(params.clone()))
+            .await?
+            .data;
+        self.validate_unparsed(&params, || serde_json::from_value(response.clone()))
+            .await?;
+
+        Ok(QuoteSnapshot {
+            captured_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_millis() as u64,
+            chain_id: self.chain_id(),
+            path: path.to_string(),
+            params,
+            response,
+        })
+    }
+}
+
+/// Appends snapshots to a JSON lines file, one snapshot per line.
+#[derive(Debug)]
+pub struct SnapshotWriter {
+    file: Mutex<File>,
+}
+
+impl SnapshotWriter {
+    pub fn open(path: impl AsRef<Path>) -> std::io::Result<SnapshotWriter> {
+        Ok(SnapshotWriter {
+            file: Mutex::new(OpenOptions::new().create(true).append(true).open(path)?),
+        })
+    }
+
+    pub fn append(&self, snapshot: &QuoteSnapshot) -> Result<(), SnapshotError> {
+        let line = serde_json::to_string(snapshot).map_err(SnapshotError::Encode)?;
+        writeln!(self.file.lock().unwrap(), "{line}")?;
+        Ok(())
+    }
+}
+
+/// Reads every snapshot in an archive written by [`SnapshotWriter`].
+pub fn read_snapshots(path: impl AsRef<Path>) -> Result<Vec<QuoteSnapshot>, SnapshotError> {
+    let mut snapshots = Vec::new();
+    for (index, line) in BufReader::new(File::open(path)?).lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        snapshots.push(
+            serde_json::from_str(&line).map_err(|source| SnapshotError::Invalid {
+                line: index + 1,
+                source,
+            })?,
+        );
+    }
+    Ok(snapshots)
+}
+
+/// Serves archived snapshots as an [`HttpTransport`] at a simulated time.
+///
+/// A request gets the latest snapshot captured at or before the replay time for the same
+/// endpoint, tokens and amount; other parameters are ignored. Requests with no such snapshot
+/// get a 404.
+#[derive(Debug)]
+pub struct QuoteReplay {
+    /// By match key, oldest first.
+    snapshots: HashMap<String, Vec<QuoteSnapshot>>,
+    now: AtomicU64,
+}
+
+impl QuoteReplay {
+    /// The replay time starts after the last snapshot, so the latest ones are served.
+    pub fn new(snapshots: impl IntoIterator<Item = QuoteSnapshot>) -> QuoteReplay {
+        let mut by_key: HashMap<String, Vec<QuoteSnapshot>> = HashMap::new();
+        for snapshot in snapshots {
+            let query = quote_query(snapshot.params.clone());
+            by_key
+                .entry(QuoteSnapshot::key(&snapshot.path, &query))
+                .or_default()
+                .push(snapshot);
+        }
+        for snapshots in by_key.values_mut() {
+            snapshots.sort_by_key(|snapshot| snapshot.captured_at);
+        }
+
+        QuoteReplay {
+            snapshots: by_key,
+            now: AtomicU64::new(u64::MAX),
+        }
+    }
+
+    pub fn open(path: impl AsRef<Path>) -> Result<QuoteReplay, SnapshotError> {
+        Ok(QuoteReplay::new(read_snapshots(path)?))
+    }
+
+    /// Moves the replay to `unix_millis`.
+    pub fn set_time(&self, unix_millis: u64) {
+        self.now.store(unix_millis, Ordering::Relaxed);
+    }
+
+    /// A client serving this replay for `chain_id`, with the same quote and price methods as
+    /// a live client.
+    pub fn into_client(
+        self,
+        chain_id: u64,
+    ) -> Result<TransportClient<QuoteReplay>, ZeroXClientError> {
+        Ok(TransportClient::new(chain_id, String::from("replay"), self)?.base_url("http://replay"))
+    }
+
+    fn find(&self, url: &Url) -> Option<&QuoteSnapshot> {
+        let query: HashMap<String, String> = url.query_pairs().into_owned().collect();
+        let query = query
+            .iter()
+            .map(|(name, value)| (name.as_str(), value.clone()))
+            .collect();
+        let now = self.now.load(Ordering::Relaxed);
+
+        self.snapshots
+            .get(&QuoteSnapshot::key(url.path(), &query))?
+            .iter()
+            .rev()
+            .find(|snapshot| snapshot.captured_at <= now)
+    }
+}
+
+#[async_trait]
+impl HttpTransport for QuoteReplay {
+    async fn get(
+        &self,
+        url: &str,
+        _headers: &[(&str, &str)],
+    ) -> Result<HttpResponse, Box<dyn std::error::Error + Send + Sync>> {
+        let url = Url::parse(url)?;
+        Ok(match self.find(&url) {
+            Some(snapshot) => HttpResponse {
+                status: 200,
+                body: serde_json::to_vec(&snapshot.response)?,
+            },
+            None => HttpResponse {
+                status: 404,
+                body: Vec::new(),
+            },
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot(captured_at: u64, price: &str) -> QuoteSnapshot {
+        QuoteSnapshot {
+            captured_at,
+            chain_id: 1,
+            path: String::from("/swap/v1/quote"),
+            params: ZeroXQuoteParams {
+                sell_token: String::from("ETH"),
+                buy_token: String::from("DAI"),
+                sell_amount: String::from("1000"),
+                ..Default::default()
+            },
+            response: serde_json::json!({ "price": price, "unmodeled": true }),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_archive_and_replay() {
+        let path =
+            std::env::temp_dir().join(format!("zerox-snapshots-{}.jsonl", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        let writer = SnapshotWriter::open(&path).unwrap();
+        writer.append(&snapshot(2_000, "2100")).unwrap();
+        writer.append(&snapshot(1_000, "2000")).unwrap();
+        assert_eq!(
+            read_snapshots(&path).unwrap()[0].response["unmodeled"],
+            true
+        );
+
+        let client = QuoteReplay::open(&path).unwrap().into_client(1).unwrap();
+        client.transport().set_time(1_500);
+
+        let params = ZeroXQuoteParams {
+            slippage_percentage: Some(crate::FeeBps::new(100).into()),
+            ..snapshot(0, "").params
+        };
+        let quote = client.get_quote(params.clone()).await.unwrap();
+        assert_eq!(quote.price.as_deref(), Some("2000"));
+        client.transport().set_time(500);
+        assert!(client.get_quote(params.clone()).await.is_err());
+        assert!(matches!(
+            client.get_price(params).await,
+            Err(ZeroXClientError::ZeroXInvalidResponseStatusCode(_))
+        ));
+
+        let _ = std::fs::remove_file(&path);
+    }
+}
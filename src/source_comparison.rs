@@ -0,0 +1,226 @@
+//! Estimates of what a quote would net if routed through a single liquidity source, after gas,
+//! to spot when a simpler route beats the aggregated one.
+
+use std::{cmp::Reverse, str::FromStr};
+
+use ethers::core::types::U256;
+use rust_decimal::Decimal;
+
+use crate::{parse_dec, QuoteFieldError, ZeroXQuoteResponse};
+
+/// Gas the Exchange Proxy spends on any swap, whatever the route.
+const BASE_GAS: u64 = 60_000;
+
+/// Typical gas one hop through `source` adds to a swap.
+pub fn source_gas_overhead(source: &str) -> u64 {
+    match source {
+        "Uniswap_V2" | "SushiSwap" | "PancakeSwap" | "QuickSwap" => 90_000,
+        "Uniswap_V3" | "PancakeSwap_V3" => 130_000,
+        "Curve" | "Curve_V2" => 180_000,
+        "Balancer" | "Balancer_V2" => 150_000,
+        "0x" => 100_000,
+        "Hashflow" | "Clipper" => 110_000,
+        _ => 120_000,
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourceEstimate {
+    pub source: String,
+    /// Buy amount, in base units, from routing the whole sell amount through the source.
+    pub buy_amount: U256,
+    pub gas: U256,
+    /// `buy_amount` less the gas cost converted to the buy token.
+    pub net_buy_amount: U256,
+    /// Whether the estimate comes from 0x's own price comparisons rather than the route.
+    pub from_price_comparison: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourceComparison {
+    /// The aggregated route's buy amount less its gas cost.
+    pub aggregated_net_buy_amount: U256,
+    /// Best net buy amount first.
+    pub sources: Vec<SourceEstimate>,
+}
+
+impl SourceComparison {
+    /// The best single source, if it nets more than the aggregated route.
+    pub fn better_single_source(&self) -> Option<&SourceEstimate> {
+        self.sources
+            .first()
+            .filter(|best| best.net_buy_amount > self.aggregated_net_buy_amount)
+    }
+}
+
+impl ZeroXQuoteResponse {
+    /// Compares the aggregated route against each source, net of gas priced at the quote's
+    /// `gas_price` and `buy_token_to_eth_rate`.
+    ///
+    /// Sources in `price_comparisons` (see
+    /// [`QuoteMode::Accurate`](crate::QuoteMode::Accurate)) use 0x's single-source quotes.
+    /// Others in the route are estimated: a source given share `p` of the trade at price impact
+    /// `i` is assumed to hold `p` of the liquidity, so taking the whole trade costs it about
+    /// `i / p`. Treat these as rough.
+    pub fn compare_sources(
+        &self,
+        buy_token_decimals: u32,
+    ) -> Result<SourceComparison, QuoteFieldError> {
+        let gas_price = parse_dec(
+            self.gas_price
+                .as_deref()
+                .ok_or(QuoteFieldError::Missing("gas_price"))?,
+            "gas_price",
+        )?;
+        let rate = self
+            .buy_token_to_eth_rate
+            .as_deref()
+            .ok_or(QuoteFieldError::Missing("buy_token_to_eth_rate"))?;
+        let rate = Decimal::from_str(rate).map_err(|_| QuoteFieldError::Invalid {
+            field: "buy_token_to_eth_rate",
+            value: rate.to_string(),
+        })?;
+        let gas_cost = |gas: U256| gas_cost_in_buy_token(gas, gas_price, rate, buy_token_decimals);
+
+        let buy_amount = self.buy_amount()?;
+        let aggregated_net_buy_amount = buy_amount.saturating_sub(gas_cost(self.gas()?));
+
+        let mut sources: Vec<SourceEstimate> = Vec::new();
+        for comparison in self.price_comparisons.iter().flatten() {
+            let (Some(name), Some(buy_amount)) = (&comparison.name, &comparison.buy_amount) else {
+                continue;
+            };
+            let buy_amount = parse_dec(buy_amount, "price_comparisons.buy_amount")?;
+            if buy_amount.is_zero() {
+                continue;
+            }
+            let gas = match comparison.gas.as_deref() {
+                Some(gas) => parse_dec(gas, "price_comparisons.gas")?,
+                None => U256::from(BASE_GAS + source_gas_overhead(name)),
+            };
+            sources.push(SourceEstimate {
+                source: name.clone(),
+                buy_amount,
+                gas,
+                net_buy_amount: buy_amount.saturating_sub(gas_cost(gas)),
+                from_price_comparison: true,
+            });
+        }
+
+        // Price impact as a fraction; without one the route cannot be extrapolated.
+        let impact = self
+            .estimated_price_impact
+            .as_deref()
+            .and_then(|impact| Decimal::from_str(impact).ok())
+            .map(|impact| impact / Decimal::ONE_HUNDRED);
+        for source in self.sources.iter().flatten() {
+            let (Some(name), Some(impact)) = (&source.name, impact) else {
+                continue;
+            };
+            let Some(share) = source
+                .proportion
+                .as_deref()
+                .and_then(|proportion| Decimal::from_str(proportion).ok())
+                .filter(|share| !share.is_zero())
+            else {
+                continue;
+            };
+            if sources.iter().any(|estimate| &estimate.source == name) {
+                continue;
+            }
+
+            // The quote's pre-impact output, less the extra impact of taking the whole trade.
+            let extra_impact = impact * (Decimal::ONE / share - Decimal::ONE);
+            let factor =
+                ((Decimal::ONE + impact) * (Decimal::ONE - extra_impact)).max(Decimal::ZERO);
+            let estimate = scale(buy_amount, factor);
+            let gas = U256::from(BASE_GAS + source_gas_overhead(name));
+            sources.push(SourceEstimate {
+                source: name.clone(),
+                buy_amount: estimate,
+                gas,
+                net_buy_amount: estimate.saturating_sub(gas_cost(gas)),
+                from_price_comparison: false,
+            });
+        }
+
+        sources.sort_by_key(|estimate| Reverse(estimate.net_buy_amount));
+
+        Ok(SourceComparison {
+            aggregated_net_buy_amount,
+            sources,
+        })
+    }
+}
+
+/// `gas * gas_price` wei in buy token base units, at `rate` buy tokens per ETH. Saturates
+/// instead of overflowing.
+fn gas_cost_in_buy_token(gas: U256, gas_price: U256, rate: Decimal, decimals: u32) -> U256 {
+    let wei = gas.saturating_mul(gas_price);
+    let Ok(wei) = Decimal::from_str(&wei.to_string()) else {
+        return U256::MAX;
+    };
+    let cost = (wei * Decimal::new(1, 18))
+        .checked_mul(rate)
+        .and_then(|cost| cost.checked_mul(Decimal::from(10u64.pow(decimals.min(19)))));
+    match cost {
+        Some(cost) => U256::from_dec_str(&cost.trunc().to_string()).unwrap_or(U256::MAX),
+        None => U256::MAX,
+    }
+}
+
+/// `amount * factor`, with `factor` between 0 and 1 give or take the impact.
+fn scale(amount: U256, factor: Decimal) -> U256 {
+    // Eight decimal places are plenty for a heuristic.
+    let factor = (factor * Decimal::from(100_000_000u64)).trunc();
+    let factor = U256::from_dec_str(&factor.to_string()).unwrap_or_default();
+    amount.saturating_mul(factor) / U256::from(100_000_000u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compare_sources() {
+        let quote: ZeroXQuoteResponse = serde_json::from_value(serde_json::json!({
+            "buyAmount": "2000000000",
+            "gas": "400000",
+            "gasPrice": "50000000000",
+            "buyTokenToEthRate": "2000",
+            "estimatedPriceImpact": "0.1",
+            "sources": [
+                { "name": "Uniswap_V3", "proportion": "0.9" },
+                { "name": "Curve", "proportion": "0.1" },
+                { "name": "Balancer_V2", "proportion": "0" },
+            ],
+            "priceComparisons": [
+                { "name": "SushiSwap", "buyAmount": "1990000000", "gas": "150000" },
+            ],
+        }))
+        .unwrap();
+
+        // Gas costs 0.02 ETH, 40 USDC, for the aggregated route.
+        let comparison = quote.compare_sources(6).unwrap();
+        assert_eq!(
+            comparison.aggregated_net_buy_amount,
+            U256::from(1_960_000_000u64)
+        );
+        let names: Vec<_> = comparison
+            .sources
+            .iter()
+            .map(|estimate| estimate.source.as_str())
+            .collect();
+        assert_eq!(names, ["Uniswap_V3", "SushiSwap", "Curve"]);
+
+        // 1990 USDC less 15 USDC of gas beats 1960 USDC.
+        assert_eq!(
+            comparison.sources[1].net_buy_amount,
+            U256::from(1_975_000_000u64)
+        );
+        // So does most of the route through Uniswap V3 alone, saving the Curve hop's gas.
+        let better = comparison.better_single_source().unwrap();
+        assert_eq!(better.source, "Uniswap_V3");
+        assert!(!better.from_price_comparison);
+    }
+}
@@ -0,0 +1,169 @@
+//! The liquidity sources known on a chain, used to validate `includedSources` and
+//! `excludedSources` before a quote is requested.
+
+use std::{
+    collections::HashSet,
+    sync::{Arc, Once, RwLock, Weak},
+    time::Duration,
+};
+
+use serde::Deserialize;
+use tracing::debug;
+
+use crate::{ZeroXClient, ZeroXClientError, ZeroXQuoteParams};
+
+pub(crate) struct SourcesCache {
+    refresh_every: Duration,
+    sources: RwLock<Option<Arc<HashSet<String>>>>,
+    refresher: Once,
+}
+
+impl SourcesCache {
+    pub(crate) fn new(refresh_every: Duration) -> SourcesCache {
+        SourcesCache {
+            refresh_every,
+            sources: RwLock::new(None),
+            refresher: Once::new(),
+        }
+    }
+
+    fn current(&self) -> Option<Arc<HashSet<String>>> {
+        self.sources.read().unwrap().clone()
+    }
+
+    fn set(&self, sources: Vec<String>) -> Arc<HashSet<String>> {
+        let sources = Arc::new(sources.into_iter().collect::<HashSet<_>>());
+        *self.sources.write().unwrap() = Some(sources.clone());
+        sources
+    }
+}
+
+#[derive(Deserialize)]
+struct SourcesResponse {
+    records: Vec<String>,
+}
+
+impl ZeroXClient {
+    /// Liquidity sources available on this client's chain.
+    pub async fn get_sources(&self) -> Result<Vec<String>, ZeroXClientError> {
+        Ok(self
+            .get::<SourcesResponse>("/swap/v1/sources", &Default::default())
+            .await?
+            .data
+            .records)
+    }
+
+    pub(crate) async fn validate_sources(
+        &self,
+        params: &ZeroXQuoteParams,
+    ) -> Result<(), ZeroXClientError> {
+        let Some(cache) = &self.sources_cache else {
+            return Ok(());
+        };
+
+        let names = || {
+            params
+                .included_sources
+                .iter()
+                .chain(params.excluded_sources.iter())
+                .flatten()
+        };
+        if names().next().is_none() {
+            return Ok(());
+        }
+
+        // only the very first lookup waits for the list; later ones use whatever the
+        // background refresh last fetched
+        let known = match cache.current() {
+            Some(known) => known,
+            None => cache.set(self.get_sources().await?),
+        };
+        cache
+            .refresher
+            .call_once(|| self.spawn_sources_refresh(Arc::downgrade(cache)));
+
+        match names().find(|name| !known.contains(*name)) {
+            Some(name) => Err(ZeroXClientError::UnknownSource(name.clone())),
+            None => Ok(()),
+        }
+    }
+
+    /// Refreshes `cache` until every client sharing it has been dropped.
+    fn spawn_sources_refresh(&self, cache: Weak<SourcesCache>) {
+        let mut client = self.clone();
+        client.sources_cache = None;
+
+        tokio::spawn(async move {
+            loop {
+                let Some(refresh_every) = cache.upgrade().map(|cache| cache.refresh_every) else {
+                    return;
+                };
+                tokio::time::sleep(refresh_every).await;
+
+                let sources = client.get_sources().await;
+                let Some(cache) = cache.upgrade() else {
+                    return;
+                };
+                match sources {
+                    Ok(sources) => {
+                        cache.set(sources);
+                    }
+                    Err(err) => debug!("failed to refresh liquidity sources: {}", err),
+                }
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use wiremock::{
+        matchers::{method, path},
+        Mock, MockServer, ResponseTemplate,
+    };
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_validate_sources_with_background_refresh() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/swap/v1/sources"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "records": ["Uniswap_V3", "Curve"],
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/swap/v1/price"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({})))
+            .mount(&server)
+            .await;
+
+        let client = ZeroXClient::builder(1, String::from("key"))
+            .base_url(server.uri())
+            .validate_sources(Duration::from_millis(50))
+            .build()
+            .unwrap();
+
+        let params = |source: &str| ZeroXQuoteParams {
+            excluded_sources: Some(vec![String::from(source)]),
+            ..Default::default()
+        };
+        assert!(client.get_price(params("Curve")).await.is_ok());
+        assert!(matches!(
+            client.get_price(params("Kyber")).await,
+            Err(ZeroXClientError::UnknownSource(source)) if source == "Kyber"
+        ));
+
+        tokio::time::sleep(Duration::from_millis(180)).await;
+        let fetches = server
+            .received_requests()
+            .await
+            .unwrap()
+            .iter()
+            .filter(|request| request.url.path() == "/swap/v1/sources")
+            .count();
+        assert!(fetches >= 3, "{} fetches", fetches);
+    }
+}
@@ -0,0 +1,197 @@
+//! Quoting a wallet's entire balance of a token, with a reserve of native token left for gas.
+
+use ethers::{
+    core::types::{Address, U256},
+    providers::Middleware,
+};
+use thiserror::Error;
+
+use crate::{
+    approval::NATIVE_TOKEN_ADDRESS, balance::erc20_balance_of, ZeroXClient, ZeroXClientError,
+    ZeroXQuoteParams, ZeroXQuoteResponse,
+};
+
+#[derive(Error, Debug)]
+pub enum SweepError {
+    #[error(transparent)]
+    Client(#[from] ZeroXClientError),
+
+    #[error("Middleware error: {0}")]
+    Middleware(Box<dyn std::error::Error + Send + Sync>),
+
+    #[error("Nothing to sell: balance {balance} does not exceed the {reserve} gas reserve")]
+    NothingToSell { balance: U256, reserve: U256 },
+}
+
+/// Sells all of `taker`'s `sell_token` for `buy_token`, see
+/// [`ZeroXClient::quote_entire_balance`].
+#[derive(Debug, Clone)]
+pub struct SweepRequest {
+    sell_token: Address,
+    buy_token: String,
+    taker: Address,
+    gas_reserve: U256,
+    should_sell_entire_balance: bool,
+    params: ZeroXQuoteParams,
+}
+
+impl SweepRequest {
+    /// Use [`NATIVE_TOKEN_ADDRESS`] as `sell_token` to sweep the native token.
+    pub fn new(sell_token: Address, buy_token: impl Into<String>, taker: Address) -> SweepRequest {
+        SweepRequest {
+            sell_token,
+            buy_token: buy_token.into(),
+            taker,
+            gas_reserve: U256::exp10(16),
+            should_sell_entire_balance: false,
+            params: ZeroXQuoteParams::default(),
+        }
+    }
+
+    /// Native token, in wei, left unsold to pay for the swap and later transactions. Only
+    /// applies when sweeping the native token. Defaults to 0.01.
+    pub fn gas_reserve(mut self, gas_reserve: U256) -> Self {
+        self.gas_reserve = gas_reserve;
+        self
+    }
+
+    /// Also sends `shouldSellEntireBalance`, so tokens received between quoting and execution
+    /// are sold too. Ignored for the native token.
+    pub fn sell_entire_balance(mut self, enabled: bool) -> Self {
+        self.should_sell_entire_balance = enabled;
+        self
+    }
+
+    /// Other quote parameters, e.g. slippage or fees. Tokens, amount and taker are overwritten.
+    pub fn params(mut self, params: ZeroXQuoteParams) -> Self {
+        self.params = params;
+        self
+    }
+
+    fn sells_native_token(&self) -> bool {
+        self.sell_token
+            == NATIVE_TOKEN_ADDRESS
+                .parse::<Address>()
+                .expect("valid address")
+    }
+}
+
+impl ZeroXClient {
+    /// Reads the taker's balance through `provider` and quotes selling all of it, less the gas
+    /// reserve when selling the native token.
+    pub async fn quote_entire_balance<M: Middleware + 'static>(
+        &self,
+        provider: &M,
+        request: SweepRequest,
+    ) -> Result<ZeroXQuoteResponse, SweepError> {
+        let native = request.sells_native_token();
+        let (balance, reserve) = if native {
+            let balance = provider
+                .get_balance(request.taker, None)
+                .await
+                .map_err(|err| SweepError::Middleware(Box::new(err)))?;
+            (balance, request.gas_reserve)
+        } else {
+            let balance = erc20_balance_of(provider, request.sell_token, request.taker)
+                .await
+                .map_err(|err| SweepError::Middleware(Box::new(err)))?;
+            (balance, U256::zero())
+        };
+        if balance <= reserve {
+            return Err(SweepError::NothingToSell { balance, reserve });
+        }
+
+        let params = ZeroXQuoteParams {
+            sell_token: format!("{:?}", request.sell_token),
+            buy_token: request.buy_token,
+            sell_amount: (balance - reserve).to_string(),
+            taker_address: Some(format!("{:?}", request.taker)),
+            should_sell_entire_balance: (request.should_sell_entire_balance && !native)
+                .then_some(true),
+            ..request.params
+        };
+
+        Ok(self.get_quote(params).await?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ethers::{core::types::Bytes, providers::Provider};
+    use wiremock::{
+        matchers::{method, path, query_param},
+        Mock, MockServer, ResponseTemplate,
+    };
+
+    use super::*;
+
+    static VITALIK: &str = "0xd8dA6BF26964aF9D7eEd9e03E53415D37aA96045";
+    static DAI: &str = "0x6b175474e89094c44da98b954eedeac495271d0f";
+
+    #[tokio::test]
+    async fn test_quote_entire_balance() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/swap/v1/quote"))
+            .and(query_param("sellToken", NATIVE_TOKEN_ADDRESS))
+            .and(query_param("sellAmount", "990000000000000000"))
+            .and(query_param("takerAddress", VITALIK.to_lowercase()))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(serde_json::json!({ "price": "2000" })),
+            )
+            .mount(&server)
+            .await;
+
+        let client = ZeroXClient::builder(1, String::from("test"))
+            .base_url(server.uri())
+            .build()
+            .unwrap();
+        let (provider, mock) = Provider::mocked();
+        mock.push(U256::exp10(18)).unwrap();
+
+        let request = SweepRequest::new(
+            NATIVE_TOKEN_ADDRESS.parse().unwrap(),
+            DAI,
+            VITALIK.parse().unwrap(),
+        )
+        .sell_entire_balance(true);
+        let quote = client
+            .quote_entire_balance(&provider, request)
+            .await
+            .unwrap();
+        assert_eq!(quote.price.as_deref(), Some("2000"));
+
+        mock.push(U256::exp10(15)).unwrap();
+        let request = SweepRequest::new(
+            NATIVE_TOKEN_ADDRESS.parse().unwrap(),
+            DAI,
+            VITALIK.parse().unwrap(),
+        );
+        assert!(matches!(
+            client.quote_entire_balance(&provider, request).await,
+            Err(SweepError::NothingToSell { .. })
+        ));
+
+        Mock::given(method("GET"))
+            .and(path("/swap/v1/quote"))
+            .and(query_param("sellToken", DAI))
+            .and(query_param("sellAmount", "5000"))
+            .and(query_param("shouldSellEntireBalance", "true"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(serde_json::json!({ "price": "0.0005" })),
+            )
+            .mount(&server)
+            .await;
+        mock.push::<Bytes, _>(Bytes::from(ethers::abi::encode(&[
+            ethers::abi::Token::Uint(U256::from(5000)),
+        ])))
+        .unwrap();
+        let request = SweepRequest::new(DAI.parse().unwrap(), "ETH", VITALIK.parse().unwrap())
+            .sell_entire_balance(true);
+        let quote = client
+            .quote_entire_balance(&provider, request)
+            .await
+            .unwrap();
+        assert_eq!(quote.price.as_deref(), Some("0.0005"));
+    }
+}
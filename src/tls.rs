@@ -0,0 +1,301 @@
+//! TLS trust settings: extra root certificates and certificate pinning.
+//!
+//! The rustls config is built here rather than by reqwest so that every certificate passes
+//! through [`TimingVerifier`], which is the only point where reqwest 0.11 lets the client
+//! observe a new connection.
+
+use std::{net::IpAddr, sync::Arc, time::SystemTime};
+
+use rustls::{
+    client::{ServerCertVerified, ServerCertVerifier, WebPkiVerifier},
+    Certificate, ClientConfig, OwnedTrustAnchor, RootCertStore, ServerName,
+};
+
+use crate::{metadata::HandshakeTimings, ZeroXClientError};
+
+#[derive(Debug, Clone)]
+pub(crate) struct TlsSettings {
+    /// PEM bundles trusted in addition to, or instead of, the built-in roots.
+    pub(crate) root_certificates: Vec<Vec<u8>>,
+    pub(crate) built_in_roots: bool,
+    /// SHA-256 fingerprints of DER certificates, one of which must be in the server's chain.
+    #[cfg(feature = "tls-pinning")]
+    pub(crate) pins: Vec<[u8; 32]>,
+}
+
+impl Default for TlsSettings {
+    fn default() -> TlsSettings {
+        TlsSettings {
+            root_certificates: Vec::new(),
+            built_in_roots: true,
+            #[cfg(feature = "tls-pinning")]
+            pins: Vec::new(),
+        }
+    }
+}
+
+impl TlsSettings {
+    pub(crate) fn apply(
+        &self,
+        builder: reqwest::ClientBuilder,
+        handshakes: HandshakeTimings,
+    ) -> Result<reqwest::ClientBuilder, ZeroXClientError> {
+        Ok(builder.use_preconfigured_tls(self.client_config(handshakes)?))
+    }
+
+    fn client_config(
+        &self,
+        handshakes: HandshakeTimings,
+    ) -> Result<ClientConfig, ZeroXClientError> {
+        let mut roots = RootCertStore::empty();
+        if self.built_in_roots {
+            roots.add_trust_anchors(webpki_roots::TLS_SERVER_ROOTS.iter().map(|anchor| {
+                OwnedTrustAnchor::from_subject_spki_name_constraints(
+                    anchor.subject,
+                    anchor.spki,
+                    anchor.name_constraints,
+                )
+            }));
+        }
+        for pem in &self.root_certificates {
+            let certificates = rustls_pemfile::certs(&mut pem.as_slice())
+                .map_err(|err| ZeroXClientError::InvalidTlsConfig(err.to_string()))?;
+            for der in certificates {
+                roots
+                    .add(&Certificate(der))
+                    .map_err(|err| ZeroXClientError::InvalidTlsConfig(err.to_string()))?;
+            }
+        }
+
+        let webpki = WebPkiVerifier::new(roots, None);
+        #[cfg(feature = "tls-pinning")]
+        let inner: Arc<dyn ServerCertVerifier> = if self.pins.is_empty() {
+            Arc::new(webpki)
+        } else {
+            Arc::new(pinning::PinnedVerifier {
+                inner: webpki,
+                pins: self.pins.clone(),
+            })
+        };
+        #[cfg(not(feature = "tls-pinning"))]
+        let inner: Arc<dyn ServerCertVerifier> = Arc::new(webpki);
+
+        let mut config = ClientConfig::builder()
+            .with_safe_defaults()
+            .with_custom_certificate_verifier(Arc::new(TimingVerifier { inner, handshakes }))
+            .with_no_client_auth();
+        // as reqwest configures its own rustls client
+        config.alpn_protocols = vec![b"h2".to_vec(), b"http/1.1".to_vec()];
+        Ok(config)
+    }
+}
+
+/// Verifies with `inner`, then records the time against the host, as the server's
+/// certificate arrives at the end of the handshake.
+struct TimingVerifier {
+    inner: Arc<dyn ServerCertVerifier>,
+    handshakes: HandshakeTimings,
+}
+
+impl ServerCertVerifier for TimingVerifier {
+    fn verify_server_cert(
+        &self,
+        end_entity: &Certificate,
+        intermediates: &[Certificate],
+        server_name: &ServerName,
+        scts: &mut dyn Iterator<Item = &[u8]>,
+        ocsp_response: &[u8],
+        now: SystemTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        let verified = self.inner.verify_server_cert(
+            end_entity,
+            intermediates,
+            server_name,
+            scts,
+            ocsp_response,
+            now,
+        )?;
+
+        // keyed like `Url::host_str`
+        let host = match server_name {
+            ServerName::DnsName(name) => Some(name.as_ref().to_string()),
+            ServerName::IpAddress(IpAddr::V4(ip)) => Some(ip.to_string()),
+            ServerName::IpAddress(IpAddr::V6(ip)) => Some(format!("[{}]", ip)),
+            _ => None,
+        };
+        if let Some(host) = host {
+            self.handshakes.record(host);
+        }
+
+        Ok(verified)
+    }
+}
+
+#[cfg(feature = "tls-pinning")]
+mod pinning {
+    use std::time::SystemTime;
+
+    use rustls::{
+        client::{ServerCertVerified, ServerCertVerifier, WebPkiVerifier},
+        Certificate, ServerName,
+    };
+    use sha2::{Digest, Sha256};
+
+    /// Validates the chain as usual, then requires one of its certificates to be pinned.
+    pub(super) struct PinnedVerifier {
+        pub(super) inner: WebPkiVerifier,
+        pub(super) pins: Vec<[u8; 32]>,
+    }
+
+    impl ServerCertVerifier for PinnedVerifier {
+        fn verify_server_cert(
+            &self,
+            end_entity: &Certificate,
+            intermediates: &[Certificate],
+            server_name: &ServerName,
+            scts: &mut dyn Iterator<Item = &[u8]>,
+            ocsp_response: &[u8],
+            now: SystemTime,
+        ) -> Result<ServerCertVerified, rustls::Error> {
+            let verified = self.inner.verify_server_cert(
+                end_entity,
+                intermediates,
+                server_name,
+                scts,
+                ocsp_response,
+                now,
+            )?;
+
+            let pinned = std::iter::once(end_entity)
+                .chain(intermediates)
+                .any(|certificate| {
+                    let fingerprint: [u8; 32] = Sha256::digest(&certificate.0).into();
+                    self.pins.contains(&fingerprint)
+                });
+            if !pinned {
+                return Err(rustls::Error::General(String::from(
+                    "server certificate does not match any pin",
+                )));
+            }
+
+            Ok(verified)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CA: &str = "-----BEGIN CERTIFICATE-----
+MIIBiDCCAS2gAwIBAgIUWjMzqXuVRnAdH8kdXXeUEhv+2ucwCgYIKoZIzj0EAwIw
+GDEWMBQGA1UEAwwNemVyb3ggdGVzdCBDQTAgFw0yNjEwMTYxNjAwNDBaGA8yMTI2
+MDkyMjE2MDA0MFowGDEWMBQGA1UEAwwNemVyb3ggdGVzdCBDQTBZMBMGByqGSM49
+AgEGCCqGSM49AwEHA0IABF8WOYyAq/uiBi1tk38qGT21IsDer7ttzNLwG+ewjslV
+FmznJtviuO8UuQbOTJTSQO/57ATy/BbF1D+f1vTb1AmjUzBRMB0GA1UdDgQWBBQh
+ESPaEZgzee3Jcjs/Am6gsY94XjAfBgNVHSMEGDAWgBQhESPaEZgzee3Jcjs/Am6g
+sY94XjAPBgNVHRMBAf8EBTADAQH/MAoGCCqGSM49BAMCA0kAMEYCIQDOocIlKyS4
+r3fTZOXKEERugcNLNi16UPb7ru0A3febYwIhANMsaRhxte/n1hXuoYiUcdA5UZ6p
+qC0tNlnosmSpkHKe
+-----END CERTIFICATE-----
+";
+
+    const LEAF: &str = "-----BEGIN CERTIFICATE-----
+MIIBvTCCAWOgAwIBAgIUVd20xmdrDmFXyu5AxA7PKvXXoHIwCgYIKoZIzj0EAwIw
+GDEWMBQGA1UEAwwNemVyb3ggdGVzdCBDQTAgFw0yNjEwMTYxNjAwNDBaGA8yMTI2
+MDkyMjE2MDA0MFowFDESMBAGA1UEAwwJbG9jYWxob3N0MFkwEwYHKoZIzj0CAQYI
+KoZIzj0DAQcDQgAEAmYPfLn3O8ZRBm8RoOGhjAIMZ6J7XNGnUwOREm8xKwnTUl16
+twU3RIGU8U3gSCHpHZwNJmAT0z9ndxI4G1YtY6OBjDCBiTAMBgNVHRMBAf8EAjAA
+MBQGA1UdEQQNMAuCCWxvY2FsaG9zdDAOBgNVHQ8BAf8EBAMCB4AwEwYDVR0lBAww
+CgYIKwYBBQUHAwEwHQYDVR0OBBYEFDd4/kIY9nHR+q7UPd6ERACLg1yqMB8GA1Ud
+IwQYMBaAFCERI9oRmDN57clyOz8CbqCxj3heMAoGCCqGSM49BAMCA0gAMEUCIQDm
+jZYwuCp3RYfi0zlGV2WH0BY/O2fZwNQ+ZQ+LCV6irAIgDAwox8WurJv7Qc4NuJ95
+oXvoNlgYwFS/rvJTQqBYUfQ=
+-----END CERTIFICATE-----
+";
+
+    #[test]
+    fn test_custom_roots() {
+        let mut settings = TlsSettings::default();
+        settings.root_certificates.push(CA.as_bytes().to_vec());
+        settings.built_in_roots = false;
+        assert!(settings
+            .apply(reqwest::Client::builder(), HandshakeTimings::default())
+            .is_ok());
+
+        let mut invalid = TlsSettings::default();
+        invalid
+            .root_certificates
+            .push(b"-----BEGIN CERTIFICATE-----\nAAAA\n-----END CERTIFICATE-----\n".to_vec());
+        assert!(invalid
+            .apply(reqwest::Client::builder(), HandshakeTimings::default())
+            .and_then(|builder| Ok(builder.build()?))
+            .is_err());
+    }
+
+    #[test]
+    fn test_timing_verifier_records_handshake() {
+        let der =
+            |pem: &str| Certificate(rustls_pemfile::certs(&mut pem.as_bytes()).unwrap()[0].clone());
+        let mut roots = RootCertStore::empty();
+        roots.add(&der(CA)).unwrap();
+        let handshakes = HandshakeTimings::default();
+        let verifier = TimingVerifier {
+            inner: Arc::new(WebPkiVerifier::new(roots, None)),
+            handshakes: handshakes.clone(),
+        };
+
+        let started = std::time::Instant::now();
+        assert_eq!(handshakes.connect_time("localhost", started), None);
+        verifier
+            .verify_server_cert(
+                &der(LEAF),
+                &[],
+                &"localhost".try_into().unwrap(),
+                &mut std::iter::empty(),
+                &[],
+                SystemTime::now(),
+            )
+            .unwrap();
+
+        assert!(handshakes.connect_time("localhost", started).is_some());
+        assert_eq!(handshakes.connect_time("127.0.0.1", started), None);
+    }
+
+    #[cfg(feature = "tls-pinning")]
+    #[test]
+    fn test_pinned_verifier() {
+        use sha2::{Digest, Sha256};
+
+        let der =
+            |pem: &str| Certificate(rustls_pemfile::certs(&mut pem.as_bytes()).unwrap()[0].clone());
+        let (ca, leaf) = (der(CA), der(LEAF));
+        let verifier = |pins: Vec<[u8; 32]>| {
+            let mut roots = RootCertStore::empty();
+            roots.add(&ca).unwrap();
+            pinning::PinnedVerifier {
+                inner: WebPkiVerifier::new(roots, None),
+                pins,
+            }
+        };
+        let verify = |verifier: pinning::PinnedVerifier| {
+            verifier.verify_server_cert(
+                &leaf,
+                &[],
+                &"localhost".try_into().unwrap(),
+                &mut std::iter::empty(),
+                &[],
+                SystemTime::now(),
+            )
+        };
+
+        assert!(verify(verifier(vec![Sha256::digest(&leaf.0).into()])).is_ok());
+        assert!(verify(verifier(vec![[0; 32]])).is_err());
+
+        let mut settings = TlsSettings::default();
+        settings.pins.push([0; 32]);
+        assert!(settings
+            .apply(reqwest::Client::builder(), HandshakeTimings::default())
+            .is_ok());
+    }
+}
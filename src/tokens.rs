@@ -0,0 +1,255 @@
+use ethers::core::types::{Address, Chain};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Token {
+    pub symbol: &'static str,
+    pub address: Address,
+    pub decimals: u8,
+}
+
+// (chain, symbol, address, decimals). USDC and USDT are the native (not bridged) deployments
+// where one exists.
+const TOKENS: &[(Chain, &str, &str, u8)] = &[
+    (
+        Chain::Mainnet,
+        "WETH",
+        "0xC02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2",
+        18,
+    ),
+    (
+        Chain::Mainnet,
+        "WBTC",
+        "0x2260FAC5E5542a773Aa44fBCfeDf7C193bc2C599",
+        8,
+    ),
+    (
+        Chain::Mainnet,
+        "USDC",
+        "0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48",
+        6,
+    ),
+    (
+        Chain::Mainnet,
+        "USDT",
+        "0xdAC17F958D2ee523a2206206994597C13D831ec7",
+        6,
+    ),
+    (
+        Chain::Mainnet,
+        "DAI",
+        "0x6B175474E89094C44Da98b954EedeAC495271d0F",
+        18,
+    ),
+    (
+        Chain::Arbitrum,
+        "WETH",
+        "0x82aF49447D8a07e3bd95BD0d56f35241523fBab1",
+        18,
+    ),
+    (
+        Chain::Arbitrum,
+        "WBTC",
+        "0x2f2a2543B76A4166549F7aaB2e75Bef0aefC5B0f",
+        8,
+    ),
+    (
+        Chain::Arbitrum,
+        "USDC",
+        "0xaf88d065e77c8cC2239327C5EDb3A432268e5831",
+        6,
+    ),
+    (
+        Chain::Arbitrum,
+        "USDT",
+        "0xFd086bC7CD5C481DCC9C85ebE478A1C0b69FCbb9",
+        6,
+    ),
+    (
+        Chain::Arbitrum,
+        "DAI",
+        "0xDA10009cBd5D07dd0CeCc66161FC93D7c9000da1",
+        18,
+    ),
+    (
+        Chain::Optimism,
+        "WETH",
+        "0x4200000000000000000000000000000000000006",
+        18,
+    ),
+    (
+        Chain::Optimism,
+        "WBTC",
+        "0x68f180fcCe6836688e9084f035309E29Bf0A2095",
+        8,
+    ),
+    (
+        Chain::Optimism,
+        "USDC",
+        "0x0b2C639c533813f4Aa9D7837CAf62653d097Ff85",
+        6,
+    ),
+    (
+        Chain::Optimism,
+        "USDT",
+        "0x94b008aA00579c1307B0EF2c499aD98a8ce58e58",
+        6,
+    ),
+    (
+        Chain::Optimism,
+        "DAI",
+        "0xDA10009cBd5D07dd0CeCc66161FC93D7c9000da1",
+        18,
+    ),
+    (
+        Chain::Polygon,
+        "WETH",
+        "0x7ceB23fD6bC0adD59E62ac25578270cFf1b9f619",
+        18,
+    ),
+    (
+        Chain::Polygon,
+        "WBTC",
+        "0x1BFD67037B42Cf73acF2047067bd4F2C47D9BfD6",
+        8,
+    ),
+    (
+        Chain::Polygon,
+        "USDC",
+        "0x3c499c542cEF5E3811e1192ce70d8cC03d5c3359",
+        6,
+    ),
+    (
+        Chain::Polygon,
+        "USDT",
+        "0xc2132D05D31c914a87C6611C10748AEb04B58e8F",
+        6,
+    ),
+    (
+        Chain::Polygon,
+        "DAI",
+        "0x8f3Cf7ad23Cd3CaDbD9735AFf958023239c6A063",
+        18,
+    ),
+    (
+        Chain::BinanceSmartChain,
+        "WETH",
+        "0x2170Ed0880ac9A755fd29B2688956BD959F933F8",
+        18,
+    ),
+    (
+        Chain::BinanceSmartChain,
+        "USDC",
+        "0x8AC76a51cc950d9822D68b83fE1Ad97B32Cd580d",
+        18,
+    ),
+    (
+        Chain::BinanceSmartChain,
+        "USDT",
+        "0x55d398326f99059fF775485246999027B3197955",
+        18,
+    ),
+    (
+        Chain::BinanceSmartChain,
+        "DAI",
+        "0x1AF3F329e8BE154074D8769D1FFa4eE058B1DBc3",
+        18,
+    ),
+    (
+        Chain::Avalanche,
+        "WETH",
+        "0x49D5c2BdFfac6CE2BFdB6640F4F80f226bc10bAB",
+        18,
+    ),
+    (
+        Chain::Avalanche,
+        "WBTC",
+        "0x50b7545627a5162F82A992c33b87aDc75187B52B",
+        8,
+    ),
+    (
+        Chain::Avalanche,
+        "USDC",
+        "0xB97EF9Ef8734C71904D8002F8b6Bc66Dd9c48a6E",
+        6,
+    ),
+    (
+        Chain::Avalanche,
+        "USDT",
+        "0x9702230A8Ea53601f5cD2dc00fDBc13d4dF4A8c7",
+        6,
+    ),
+    (
+        Chain::Avalanche,
+        "DAI",
+        "0xd586E7F844cEa2F87f50152665BCbc2C279D8d70",
+        18,
+    ),
+];
+
+/// Looks up a token by symbol (case-insensitive) on `chain`.
+pub fn by_symbol(chain: Chain, symbol: &str) -> Option<Token> {
+    all(chain)
+        .into_iter()
+        .find(|token| token.symbol.eq_ignore_ascii_case(symbol))
+}
+
+/// Returns every known token on `chain`.
+pub fn all(chain: Chain) -> Vec<Token> {
+    TOKENS
+        .iter()
+        .filter(|(token_chain, ..)| *token_chain == chain)
+        .map(|(_, symbol, address, decimals)| Token {
+            symbol,
+            address: address.parse().expect("valid address"),
+            decimals: *decimals,
+        })
+        .collect()
+}
+
+pub fn weth(chain: Chain) -> Option<Token> {
+    by_symbol(chain, "WETH")
+}
+
+pub fn wbtc(chain: Chain) -> Option<Token> {
+    by_symbol(chain, "WBTC")
+}
+
+pub fn usdc(chain: Chain) -> Option<Token> {
+    by_symbol(chain, "USDC")
+}
+
+pub fn usdt(chain: Chain) -> Option<Token> {
+    by_symbol(chain, "USDT")
+}
+
+pub fn dai(chain: Chain) -> Option<Token> {
+    by_symbol(chain, "DAI")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_token_accessors() {
+        let usdc = usdc(Chain::Mainnet).unwrap();
+        assert_eq!(
+            usdc.address,
+            "0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48"
+                .parse::<Address>()
+                .unwrap()
+        );
+        assert_eq!(usdc.decimals, 6);
+
+        assert_eq!(usdt(Chain::BinanceSmartChain).unwrap().decimals, 18);
+        assert!(wbtc(Chain::BinanceSmartChain).is_none());
+        assert!(dai(Chain::Moonbeam).is_none());
+    }
+
+    #[test]
+    fn test_all_addresses_parse() {
+        for (chain, ..) in TOKENS {
+            assert!(!all(*chain).is_empty());
+        }
+    }
+}
@@ -0,0 +1,210 @@
+use async_trait::async_trait;
+use reqwest::{StatusCode, Url};
+use serde::de::DeserializeOwned;
+
+use crate::{
+    default_base_url, quote_query, status_error, ZeroXClientError, ZeroXQuoteParams,
+    ZeroXQuoteResponse,
+};
+
+pub struct HttpResponse {
+    pub status: u16,
+    pub body: Vec<u8>,
+}
+
+/// A minimal HTTP GET transport, so the API can be used from async runtimes other than tokio.
+#[async_trait]
+pub trait HttpTransport: Send + Sync {
+    async fn get(
+        &self,
+        url: &str,
+        headers: &[(&str, &str)],
+    ) -> Result<HttpResponse, Box<dyn std::error::Error + Send + Sync>>;
+}
+
+/// A 0x API client that sends requests through an [`HttpTransport`] and does not need a
+/// tokio runtime.
+///
+/// Retries, hedging, rate limiting and screening depend on the tokio based [`ZeroXClient`]
+/// and are not available here.
+///
+/// [`ZeroXClient`]: crate::ZeroXClient
+pub struct TransportClient<T> {
+    chain_id: u64,
+    base_url: String,
+    api_key: String,
+    transport: T,
+}
+
+impl<T: HttpTransport> TransportClient<T> {
+    pub fn new(
+        chain_id: u64,
+        api_key: String,
+        transport: T,
+    ) -> Result<TransportClient<T>, ZeroXClientError> {
+        let base_url =
+            default_base_url(chain_id).ok_or(ZeroXClientError::InvalidChainId(chain_id))?;
+
+        Ok(TransportClient {
+            chain_id,
+            base_url,
+            api_key,
+            transport,
+        })
+    }
+
+    pub fn base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into();
+        self
+    }
+
+    pub fn chain_id(&self) -> u64 {
+        self.chain_id
+    }
+
+    pub fn transport(&self) -> &T {
+        &self.transport
+    }
+
+    pub async fn get_quote(
+        &self,
+        params: ZeroXQuoteParams,
+    ) -> Result<ZeroXQuoteResponse, ZeroXClientError> {
+        self.get("/swap/v1/quote", params).await
+    }
+
+    pub async fn get_price(
+        &self,
+        params: ZeroXQuoteParams,
+    ) -> Result<ZeroXQuoteResponse, ZeroXClientError> {
+        self.get("/swap/v1/price", params).await
+    }
+
+    async fn get<R: DeserializeOwned>(
+        &self,
+        path: &str,
+        params: ZeroXQuoteParams,
+    ) -> Result<R, ZeroXClientError> {
+        let url =
+            Url::parse_with_params(&format!("{}{}", self.base_url, path), quote_query(params))
+                .map_err(|err| ZeroXClientError::Transport(Box::new(err)))?;
+
+        let resp = self
+            .transport
+            .get(
+                url.as_str(),
+                &[
+                    ("0x-api-key", &self.api_key),
+                    ("Content-Type", "application/json"),
+                ],
+            )
+            .await
+            .map_err(ZeroXClientError::Transport)?;
+
+        if resp.status != 200 {
+            let status = StatusCode::from_u16(resp.status)
+                .map_err(|err| ZeroXClientError::Transport(Box::new(err)))?;
+            return Err(status_error(status));
+        }
+
+        Ok(serde_json::from_slice(&resp.body)?)
+    }
+}
+
+#[cfg(feature = "isahc")]
+pub use self::isahc_transport::IsahcTransport;
+
+#[cfg(feature = "isahc")]
+mod isahc_transport {
+    use isahc::{AsyncReadResponseExt, HttpClient, Request};
+
+    use super::*;
+
+    /// [`HttpTransport`] backed by isahc, which drives requests on its own thread and works
+    /// on any async runtime.
+    pub struct IsahcTransport {
+        client: HttpClient,
+    }
+
+    impl IsahcTransport {
+        pub fn new() -> Result<IsahcTransport, isahc::Error> {
+            Ok(IsahcTransport {
+                client: HttpClient::new()?,
+            })
+        }
+    }
+
+    #[async_trait]
+    impl HttpTransport for IsahcTransport {
+        async fn get(
+            &self,
+            url: &str,
+            headers: &[(&str, &str)],
+        ) -> Result<HttpResponse, Box<dyn std::error::Error + Send + Sync>> {
+            let mut request = Request::get(url);
+            for (name, value) in headers {
+                request = request.header(*name, *value);
+            }
+
+            let mut resp = self.client.send_async(request.body(())?).await?;
+
+            Ok(HttpResponse {
+                status: resp.status().as_u16(),
+                body: resp.bytes().await?,
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    #[derive(Default)]
+    struct FakeTransport {
+        urls: Mutex<Vec<String>>,
+    }
+
+    #[async_trait]
+    impl HttpTransport for FakeTransport {
+        async fn get(
+            &self,
+            url: &str,
+            headers: &[(&str, &str)],
+        ) -> Result<HttpResponse, Box<dyn std::error::Error + Send + Sync>> {
+            assert!(headers.contains(&("0x-api-key", "test")));
+            self.urls.lock().unwrap().push(url.to_string());
+
+            Ok(HttpResponse {
+                status: 200,
+                body: br#"{"price":"2000"}"#.to_vec(),
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_transport_client_get_quote() {
+        let client = TransportClient::new(1, String::from("test"), FakeTransport::default())
+            .unwrap()
+            .base_url("http://localhost");
+
+        let quote = client
+            .get_quote(ZeroXQuoteParams {
+                sell_token: String::from("ETH"),
+                buy_token: String::from("DAI"),
+                sell_amount: String::from("1000"),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(quote.price.as_deref(), Some("2000"));
+
+        let url = Url::parse(&client.transport.urls.lock().unwrap()[0]).unwrap();
+        assert_eq!(url.path(), "/swap/v1/quote");
+        assert!(url
+            .query_pairs()
+            .any(|(key, value)| key == "sellAmount" && value == "1000"));
+    }
+}
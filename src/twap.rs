@@ -0,0 +1,308 @@
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use ethers::core::types::U256;
+use tokio::{sync::Notify, task::JoinHandle};
+use tracing::warn;
+
+use crate::{QuoteHandler, ZeroXClient, ZeroXQuoteParams};
+
+/// One child order of a [`TwapPlan`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TwapSlice {
+    pub index: usize,
+    pub sell_amount: U256,
+    /// Offset from the start of the plan.
+    pub offset: Duration,
+}
+
+/// A target sell amount split into equally sized slices spread evenly over a window.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TwapPlan {
+    pub slices: Vec<TwapSlice>,
+}
+
+impl TwapPlan {
+    /// Splits `total` into `slices` child orders over `window`. The first slice is due
+    /// immediately and any rounding remainder is added to the last slice. `slices` is capped at
+    /// `total` so that no slice sells nothing.
+    pub fn new(total: U256, window: Duration, slices: u32) -> TwapPlan {
+        let slices = if !total.is_zero() && total < U256::from(slices) {
+            total.as_u32()
+        } else {
+            slices.max(1)
+        };
+        let size = total / slices;
+        let remainder = total % slices;
+
+        TwapPlan {
+            slices: (0..slices)
+                .map(|i| TwapSlice {
+                    index: i as usize,
+                    sell_amount: if i == slices - 1 {
+                        size + remainder
+                    } else {
+                        size
+                    },
+                    offset: window / slices * i,
+                })
+                .collect(),
+        }
+    }
+
+    pub fn total(&self) -> U256 {
+        self.slices
+            .iter()
+            .fold(U256::zero(), |total, slice| total + slice.sell_amount)
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TwapProgress {
+    pub total_slices: usize,
+    pub completed_slices: usize,
+    pub failed_slices: Vec<usize>,
+    /// Sum of the sell amounts of completed slices.
+    pub sold: U256,
+    /// Set once no further slice will run, whether the plan completed or was aborted.
+    pub finished: bool,
+    /// Whether [`TwapExecution::abort`] stopped the plan before its last slice.
+    pub aborted: bool,
+}
+
+/// Runs a [`TwapPlan`], quoting each slice at its offset and handing the quote to a
+/// [`QuoteHandler`]. Pass a [`SwapExecutor`](crate::SwapExecutor) as the handler to execute
+/// slices, or a callback to only collect quotes.
+///
+/// A slice whose quote or handler fails is recorded in [`TwapProgress::failed_slices`] and
+/// the plan continues. Dropping the execution aborts the remaining slices, as
+/// [`abort`](Self::abort) does.
+pub struct TwapExecution {
+    progress: Arc<Mutex<TwapProgress>>,
+    cancel: Arc<Notify>,
+    task: JoinHandle<()>,
+}
+
+impl TwapExecution {
+    /// Starts executing `plan`. `params` is the template for each slice; its `sell_amount` is
+    /// replaced by the slice's amount.
+    pub fn start(
+        client: Arc<ZeroXClient>,
+        params: ZeroXQuoteParams,
+        plan: TwapPlan,
+        handler: Arc<dyn QuoteHandler>,
+    ) -> TwapExecution {
+        let progress = Arc::new(Mutex::new(TwapProgress {
+            total_slices: plan.slices.len(),
+            ..Default::default()
+        }));
+        let cancel = Arc::new(Notify::new());
+        let task = tokio::spawn(run(
+            client,
+            params,
+            plan,
+            handler,
+            progress.clone(),
+            cancel.clone(),
+        ));
+
+        TwapExecution {
+            progress,
+            cancel,
+            task,
+        }
+    }
+
+    pub fn progress(&self) -> TwapProgress {
+        self.progress.lock().unwrap().clone()
+    }
+
+    /// Stops before the next slice. A slice that is already being executed is completed and
+    /// recorded first, so progress matches what was executed once
+    /// [`is_finished`](Self::is_finished).
+    pub fn abort(&self) {
+        // stores a permit if the task is mid-slice, which it sees before the next one
+        self.cancel.notify_one();
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.task.is_finished()
+    }
+}
+
+impl Drop for TwapExecution {
+    fn drop(&mut self) {
+        self.abort();
+    }
+}
+
+async fn run(
+    client: Arc<ZeroXClient>,
+    params: ZeroXQuoteParams,
+    plan: TwapPlan,
+    handler: Arc<dyn QuoteHandler>,
+    progress: Arc<Mutex<TwapProgress>>,
+    cancel: Arc<Notify>,
+) {
+    let start = tokio::time::Instant::now();
+
+    for slice in plan.slices {
+        tokio::select! {
+            biased;
+            _ = cancel.notified() => {
+                progress.lock().unwrap().aborted = true;
+                break;
+            }
+            _ = tokio::time::sleep_until(start + slice.offset) => {}
+        }
+
+        let params = ZeroXQuoteParams {
+            sell_amount: slice.sell_amount.to_string(),
+            ..params.clone()
+        };
+
+        let result = match client.get_quote(params).await {
+            Ok(quote) => handler.handle(quote).await,
+            Err(err) => Err(err.into()),
+        };
+
+        let mut progress = progress.lock().unwrap();
+        match result {
+            Ok(()) => {
+                progress.completed_slices += 1;
+                progress.sold += slice.sell_amount;
+            }
+            Err(err) => {
+                warn!("twap slice {} failed: {}", slice.index, err);
+                progress.failed_slices.push(slice.index);
+            }
+        }
+    }
+
+    progress.lock().unwrap().finished = true;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ZeroXQuoteResponse;
+    use wiremock::{
+        matchers::{method, path, query_param},
+        Mock, MockServer, ResponseTemplate,
+    };
+
+    #[test]
+    fn test_twap_plan() {
+        let plan = TwapPlan::new(U256::from(1000), Duration::from_secs(60), 3);
+
+        assert_eq!(plan.slices.len(), 3);
+        assert_eq!(plan.slices[0].sell_amount, U256::from(333));
+        assert_eq!(plan.slices[2].sell_amount, U256::from(334));
+        assert_eq!(plan.slices[0].offset, Duration::ZERO);
+        assert_eq!(plan.slices[2].offset, Duration::from_secs(40));
+        assert_eq!(plan.total(), U256::from(1000));
+
+        // fewer units than slices: one unit per slice rather than zero-amount slices
+        let plan = TwapPlan::new(U256::from(2), Duration::from_secs(60), 5);
+        assert_eq!(plan.slices.len(), 2);
+        assert!(plan
+            .slices
+            .iter()
+            .all(|slice| slice.sell_amount == U256::one()));
+        assert_eq!(plan.slices[1].offset, Duration::from_secs(30));
+    }
+
+    #[tokio::test]
+    async fn test_twap_execution_progress() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/swap/v1/quote"))
+            .and(query_param("sellAmount", "50"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({})))
+            .mount(&server)
+            .await;
+
+        let client = ZeroXClient::builder(1, String::from("test"))
+            .base_url(server.uri())
+            .build()
+            .unwrap();
+
+        let handler =
+            |_: ZeroXQuoteResponse| -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+                Ok(())
+            };
+
+        // 101 over 2 slices: 50 then 51, and only 50 is quotable.
+        let execution = TwapExecution::start(
+            Arc::new(client),
+            ZeroXQuoteParams::default(),
+            TwapPlan::new(U256::from(101), Duration::from_millis(40), 2),
+            Arc::new(handler),
+        );
+
+        for _ in 0..100 {
+            if execution.is_finished() {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+
+        let progress = execution.progress();
+        assert!(progress.finished);
+        assert_eq!(progress.total_slices, 2);
+        assert_eq!(progress.completed_slices, 1);
+        assert_eq!(progress.failed_slices, vec![1]);
+        assert_eq!(progress.sold, U256::from(50));
+    }
+
+    #[tokio::test]
+    async fn test_twap_abort_records_in_flight_slice() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/swap/v1/quote"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(serde_json::json!({}))
+                    .set_delay(Duration::from_millis(100)),
+            )
+            .mount(&server)
+            .await;
+
+        let client = ZeroXClient::builder(1, String::from("test"))
+            .base_url(server.uri())
+            .build()
+            .unwrap();
+
+        let handler =
+            |_: ZeroXQuoteResponse| -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+                Ok(())
+            };
+
+        let execution = TwapExecution::start(
+            Arc::new(client),
+            ZeroXQuoteParams::default(),
+            TwapPlan::new(U256::from(300), Duration::from_millis(300), 3),
+            Arc::new(handler),
+        );
+
+        // the first slice is in flight
+        tokio::time::sleep(Duration::from_millis(30)).await;
+        execution.abort();
+
+        for _ in 0..100 {
+            if execution.is_finished() {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+
+        let progress = execution.progress();
+        assert!(progress.finished);
+        assert!(progress.aborted);
+        assert_eq!(progress.completed_slices, 1);
+        assert!(progress.failed_slices.is_empty());
+        assert_eq!(progress.sold, U256::from(100));
+    }
+}
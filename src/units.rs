@@ -0,0 +1,175 @@
+//! Fee and slippage units. v1 takes fractions (`0.01` for 1%) while v2 and gasless take basis
+//! points (`100` for 1%); these types make the conversion explicit.
+
+use std::{fmt, str::FromStr};
+
+use rust_decimal::{prelude::ToPrimitive, Decimal};
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::ZeroXQuoteParams;
+
+/// Basis points: 1 bps is 0.01%, 10 000 bps is 100%.
+#[derive(
+    Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default,
+)]
+#[serde(transparent)]
+pub struct FeeBps(u32);
+
+impl FeeBps {
+    pub const fn new(bps: u32) -> FeeBps {
+        FeeBps(bps)
+    }
+
+    pub const fn get(self) -> u32 {
+        self.0
+    }
+}
+
+impl fmt::Display for FeeBps {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// A proportion, stored as a fraction where `1` is 100%.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Percentage(Decimal);
+
+impl Percentage {
+    /// From a fraction, e.g. `0.01` for 1%, as v1's `slippagePercentage` expects.
+    pub fn from_fraction(fraction: Decimal) -> Percentage {
+        Percentage(fraction.normalize())
+    }
+
+    /// From a percent value, e.g. `1` for 1%.
+    pub fn from_percent(percent: Decimal) -> Percentage {
+        Percentage::from_fraction(percent / Decimal::ONE_HUNDRED)
+    }
+
+    pub fn fraction(self) -> Decimal {
+        self.0
+    }
+
+    pub fn percent(self) -> Decimal {
+        (self.0 * Decimal::ONE_HUNDRED).normalize()
+    }
+
+    /// The value in whole basis points, or `None` if it is negative or has a fraction of a
+    /// basis point.
+    pub fn to_bps(self) -> Option<FeeBps> {
+        let bps = self.0 * Decimal::from(10_000);
+        if !bps.fract().is_zero() {
+            return None;
+        }
+        bps.to_u32().map(FeeBps)
+    }
+}
+
+impl From<FeeBps> for Percentage {
+    fn from(bps: FeeBps) -> Percentage {
+        Percentage::from_fraction(Decimal::new(bps.0.into(), 4))
+    }
+}
+
+/// Serializes as the fraction string v1 takes, e.g. `"0.01"` for 1%.
+impl Serialize for Percentage {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(&self.0)
+    }
+}
+
+/// Accepts a fraction as a string or a number. Percent strings such as `"1%"` are rejected.
+impl<'de> Deserialize<'de> for Percentage {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Percentage, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Repr {
+            Text(String),
+            Number(f64),
+        }
+
+        let fraction = match Repr::deserialize(deserializer)? {
+            Repr::Text(text) => Decimal::from_str(&text).map_err(de::Error::custom)?,
+            Repr::Number(number) => Decimal::try_from(number).map_err(de::Error::custom)?,
+        };
+        Ok(Percentage::from_fraction(fraction))
+    }
+}
+
+#[cfg(feature = "proptest")]
+impl proptest::arbitrary::Arbitrary for Percentage {
+    type Parameters = ();
+    type Strategy = proptest::strategy::Map<std::ops::RangeInclusive<u32>, fn(u32) -> Percentage>;
+
+    fn arbitrary_with(_: ()) -> Self::Strategy {
+        proptest::strategy::Strategy::prop_map(0..=10_000, |bps| FeeBps(bps).into())
+    }
+}
+
+/// Formats as a percent, e.g. `0.5%`.
+impl fmt::Display for Percentage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}%", self.percent())
+    }
+}
+
+impl ZeroXQuoteParams {
+    pub fn buy_token_fee(mut self, fee: impl Into<Percentage>) -> Self {
+        self.buy_token_percentage_fee = Some(fee.into());
+        self
+    }
+
+    /// Sets `slippage_percentage` in the fractional form v1 expects.
+    pub fn slippage(mut self, slippage: impl Into<Percentage>) -> Self {
+        self.slippage_percentage = Some(slippage.into());
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_conversions() {
+        let one_percent = Percentage::from_percent(Decimal::ONE);
+        assert_eq!(one_percent, Percentage::from(FeeBps::new(100)));
+        assert_eq!(one_percent.fraction().to_string(), "0.01");
+        assert_eq!(one_percent.to_bps(), Some(FeeBps::new(100)));
+        assert_eq!(one_percent.to_string(), "1%");
+
+        let fractional = Percentage::from_fraction(Decimal::new(12345, 8));
+        assert_eq!(fractional.to_bps(), None);
+        assert_eq!(
+            Percentage::from_percent(Decimal::NEGATIVE_ONE).to_bps(),
+            None
+        );
+
+        let params = ZeroXQuoteParams::default()
+            .buy_token_fee(FeeBps::new(25))
+            .slippage(Percentage::from_percent(Decimal::new(5, 1)));
+        assert_eq!(
+            params.buy_token_percentage_fee,
+            Some(Percentage::from(FeeBps::new(25)))
+        );
+        assert_eq!(
+            params.slippage_percentage.map(Percentage::fraction),
+            Some(Decimal::new(5, 3))
+        );
+    }
+
+    #[test]
+    fn test_percentage_serde() {
+        let quarter = Percentage::from(FeeBps::new(25));
+        assert_eq!(serde_json::to_value(quarter).unwrap(), "0.0025");
+        assert_eq!(
+            serde_json::from_value::<Percentage>(serde_json::json!("0.0025")).unwrap(),
+            quarter
+        );
+        assert_eq!(
+            serde_json::from_value::<Percentage>(serde_json::json!(0.0025)).unwrap(),
+            quarter
+        );
+        assert!(serde_json::from_value::<Percentage>(serde_json::json!("0.25%")).is_err());
+    }
+}
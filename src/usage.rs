@@ -0,0 +1,333 @@
+//! Per-key API usage counters, read with [`ZeroXClient::usage_stats`], and a logger that
+//! appends them to a JSON lines file, e.g. once a day.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs::OpenOptions,
+    io::Write,
+    path::{Path, PathBuf},
+    sync::Mutex,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use serde::Serialize;
+use tokio::{task::JoinHandle, time::MissedTickBehavior};
+use tracing::debug;
+
+use crate::{scheduler::MIN_INTERVAL, ZeroXClient};
+
+const MONTH: Duration = Duration::from_secs(30 * 24 * 60 * 60);
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct EndpointUsage {
+    /// HTTP attempts, including retries and hedges.
+    pub requests: u64,
+    pub errors: u64,
+}
+
+impl EndpointUsage {
+    pub fn error_rate(&self) -> f64 {
+        if self.requests == 0 {
+            return 0.0;
+        }
+        self.errors as f64 / self.requests as f64
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KeyUsage {
+    /// By route, e.g. `/swap/v1/quote` or `/gasless/status/{tradeHash}`.
+    pub endpoints: BTreeMap<String, EndpointUsage>,
+    /// Share of the monthly quota used so far, if the client was given one.
+    pub quota_used: Option<f64>,
+    /// Share of the monthly quota a 30-day period would use at the rate seen so far.
+    pub projected_quota_used: Option<f64>,
+}
+
+impl KeyUsage {
+    pub fn total(&self) -> EndpointUsage {
+        self.endpoints
+            .values()
+            .fold(EndpointUsage::default(), |total, usage| EndpointUsage {
+                requests: total.requests + usage.requests,
+                errors: total.errors + usage.errors,
+            })
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UsageStats {
+    /// Unix timestamp in milliseconds of when counting started: when the client was built or
+    /// last reset.
+    pub since: u64,
+    /// By API key, identified by its last four characters.
+    pub keys: BTreeMap<String, KeyUsage>,
+}
+
+#[derive(Debug)]
+struct Counters {
+    since: SystemTime,
+    usage: HashMap<(String, String), EndpointUsage>,
+}
+
+/// Shared by all clones of a client.
+#[derive(Debug)]
+pub(crate) struct UsageTracker {
+    monthly_quota: Option<u64>,
+    counters: Mutex<Counters>,
+}
+
+/// The route template of `path`, so per-trade paths share one entry instead of growing the
+/// map without bound.
+fn route(path: &str) -> &str {
+    if path.starts_with("/gasless/status/") {
+        "/gasless/status/{tradeHash}"
+    } else {
+        path
+    }
+}
+
+impl UsageTracker {
+    pub(crate) fn new(monthly_quota: Option<u64>) -> UsageTracker {
+        UsageTracker {
+            monthly_quota,
+            counters: Mutex::new(Counters {
+                since: SystemTime::now(),
+                usage: HashMap::new(),
+            }),
+        }
+    }
+
+    pub(crate) fn record(&self, api_key: &str, path: &str, ok: bool) {
+        let mut counters = self.counters.lock().unwrap();
+        let usage = counters
+            .usage
+            .entry((key_label(api_key), route(path).to_string()))
+            .or_default();
+        usage.requests += 1;
+        if !ok {
+            usage.errors += 1;
+        }
+    }
+
+    pub(crate) fn reset(&self) {
+        let mut counters = self.counters.lock().unwrap();
+        counters.since = SystemTime::now();
+        counters.usage.clear();
+    }
+
+    pub(crate) fn snapshot(&self) -> UsageStats {
+        let counters = self.counters.lock().unwrap();
+
+        let mut keys: BTreeMap<String, KeyUsage> = BTreeMap::new();
+        for ((key, path), usage) in &counters.usage {
+            keys.entry(key.clone())
+                .or_default()
+                .endpoints
+                .insert(path.clone(), *usage);
+        }
+
+        if let Some(quota) = self.monthly_quota.filter(|quota| *quota > 0) {
+            let elapsed = counters.since.elapsed().unwrap_or_default();
+            for usage in keys.values_mut() {
+                let used = usage.total().requests as f64 / quota as f64;
+                usage.quota_used = Some(used);
+                // Too early to extrapolate from the first few seconds.
+                usage.projected_quota_used = (elapsed >= Duration::from_secs(60))
+                    .then(|| used * MONTH.as_secs_f64() / elapsed.as_secs_f64());
+            }
+        }
+
+        UsageStats {
+            since: unix_millis(counters.since),
+            keys,
+        }
+    }
+}
+
+fn key_label(api_key: &str) -> String {
+    let chars: Vec<char> = api_key.chars().collect();
+    if chars.len() < 8 {
+        return String::from("…");
+    }
+    format!("…{}", chars[chars.len() - 4..].iter().collect::<String>())
+}
+
+fn unix_millis(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
+}
+
+/// Appends the client's [`UsageStats`] as a JSON line to a file every `interval`, so quota
+/// consumption can be followed across restarts. Write errors are logged and retried on the
+/// next tick.
+///
+/// The background task stops when the logger is dropped.
+pub struct UsageLogger {
+    task: JoinHandle<()>,
+}
+
+impl UsageLogger {
+    /// Logs every `interval`, at least [`MIN_INTERVAL`].
+    pub fn start(client: ZeroXClient, path: impl Into<PathBuf>, interval: Duration) -> UsageLogger {
+        let task = tokio::spawn(log_loop(client, path.into(), interval.max(MIN_INTERVAL)));
+
+        UsageLogger { task }
+    }
+
+    /// Logs once a day.
+    pub fn daily(client: ZeroXClient, path: impl Into<PathBuf>) -> UsageLogger {
+        UsageLogger::start(client, path, Duration::from_secs(24 * 60 * 60))
+    }
+}
+
+impl Drop for UsageLogger {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct UsageRecord<'a> {
+    logged_at: u64,
+    chain_id: u64,
+    #[serde(flatten)]
+    stats: &'a UsageStats,
+}
+
+async fn log_loop(client: ZeroXClient, path: PathBuf, interval: Duration) {
+    let mut ticks = tokio::time::interval_at(tokio::time::Instant::now() + interval, interval);
+    ticks.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+    loop {
+        ticks.tick().await;
+
+        let stats = client.usage_stats();
+        let record = UsageRecord {
+            logged_at: unix_millis(SystemTime::now()),
+            chain_id: client.chain_id(),
+            stats: &stats,
+        };
+        if let Err(err) = append_line(&path, &record) {
+            debug!("failed to log usage to {}: {}", path.display(), err);
+        }
+    }
+}
+
+fn append_line(
+    path: &Path,
+    record: &UsageRecord<'_>,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let line = serde_json::to_string(record)?;
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    writeln!(file, "{line}")?;
+    Ok(())
+}
+
+impl ZeroXClient {
+    /// Requests and errors per API key and endpoint since the client was built or
+    /// [`reset_usage_stats`](ZeroXClient::reset_usage_stats) was last called. Shared by all
+    /// clones of the client.
+    pub fn usage_stats(&self) -> UsageStats {
+        self.usage.snapshot()
+    }
+
+    /// Clears the counters, e.g. when the plan's billing period starts.
+    pub fn reset_usage_stats(&self) {
+        self.usage.reset();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use wiremock::{
+        matchers::{method, path},
+        Mock, MockServer, ResponseTemplate,
+    };
+
+    use super::*;
+    use crate::ZeroXQuoteParams;
+
+    #[tokio::test]
+    async fn test_usage_stats() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/swap/v1/price"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({})))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/swap/v1/quote"))
+            .respond_with(ResponseTemplate::new(400))
+            .mount(&server)
+            .await;
+
+        let client = ZeroXClient::builder(1, String::from("test-key-1234"))
+            .base_url(server.uri())
+            .retry_policy(crate::NoRetry)
+            .monthly_request_quota(100)
+            .build()
+            .unwrap();
+
+        client.get_price(ZeroXQuoteParams::default()).await.unwrap();
+        client.get_price(ZeroXQuoteParams::default()).await.unwrap();
+        client
+            .clone()
+            .get_quote(ZeroXQuoteParams::default())
+            .await
+            .unwrap_err();
+
+        let stats = client.usage_stats();
+        let key = &stats.keys["…1234"];
+        assert_eq!(
+            key.endpoints["/swap/v1/price"],
+            EndpointUsage {
+                requests: 2,
+                errors: 0
+            }
+        );
+        assert_eq!(key.endpoints["/swap/v1/quote"].error_rate(), 1.0);
+        assert_eq!(key.total().requests, 3);
+        assert_eq!(key.quota_used, Some(0.03));
+
+        client.reset_usage_stats();
+        assert!(client.usage_stats().keys.is_empty());
+
+        client.get_gasless_status("0xaa").await.unwrap_err();
+        client.get_gasless_status("0xbb").await.unwrap_err();
+        let stats = client.usage_stats();
+        let endpoints = &stats.keys["…1234"].endpoints;
+        assert_eq!(endpoints.len(), 1);
+        assert_eq!(endpoints["/gasless/status/{tradeHash}"].requests, 2);
+    }
+
+    #[tokio::test]
+    async fn test_usage_logger_zero_interval() {
+        let path = std::env::temp_dir().join(format!("zerox-usage-{}.jsonl", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        // a zero interval used to panic the logging task
+        let logger = UsageLogger::start(
+            ZeroXClient::new(1, String::from("test")).unwrap(),
+            &path,
+            Duration::ZERO,
+        );
+        let logged = || {
+            std::fs::read_to_string(&path).is_ok_and(|contents| contents.contains("\"chainId\":1"))
+        };
+        for _ in 0..50 {
+            if logged() {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+
+        assert!(logged());
+        drop(logger);
+        std::fs::remove_file(&path).unwrap();
+    }
+}
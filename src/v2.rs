@@ -0,0 +1,606 @@
+//! The 0x Swap API v2, which serves every chain from one host and selects the chain with a
+//! `chainId` query parameter.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use ethers::core::types::{Address, Bytes, U256};
+use reqwest::StatusCode;
+use tracing::debug;
+
+use crate::{
+    parse_dec, parse_field, required, screening::ScreeningRequest, ApiVersion, FeeBps, Percentage,
+    QuoteFieldError, ZeroXClient, ZeroXClientError, ZeroXQuoteParams, ZeroXQuoteResponse,
+};
+
+pub const V2_BASE_URL: &str = "https://api.0x.org";
+
+/// How the taker grants the 0x settler access to the sell token.
+#[derive(Serialize, Deserialize, Default, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum SwapFlow {
+    /// A plain ERC-20 approval to the AllowanceHolder contract.
+    #[default]
+    AllowanceHolder,
+    /// A Permit2 signature submitted with the transaction.
+    Permit2,
+}
+
+impl SwapFlow {
+    fn path(self, endpoint: &str) -> String {
+        match self {
+            SwapFlow::AllowanceHolder => format!("/swap/allowance-holder/{}", endpoint),
+            SwapFlow::Permit2 => format!("/swap/permit2/{}", endpoint),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Default, Clone, Debug)]
+pub struct ZeroXQuoteParamsV2 {
+    pub sell_token: String,
+    pub buy_token: String,
+    pub sell_amount: String,
+    /// Address holding the sell tokens, e.g. a smart contract wallet.
+    pub taker: Option<String>,
+    /// EOA that submits the transaction when it differs from `taker`, as when a relayer
+    /// executes on behalf of a smart account.
+    pub tx_origin: Option<String>,
+    pub slippage_bps: Option<FeeBps>,
+    pub excluded_sources: Option<Vec<String>>,
+    /// Receives the integrator fee. Replaces v1's `feeRecipient`.
+    pub swap_fee_recipient: Option<String>,
+    /// Integrator fee in basis points. Replaces v1's `buyTokenPercentageFee`.
+    pub swap_fee_bps: Option<FeeBps>,
+    /// Token the integrator fee is taken in; must be the sell or buy token. Defaults to the
+    /// buy token.
+    pub swap_fee_token: Option<String>,
+    /// Receives positive slippage. Replaces v1's `feeRecipientTradeSurplus`.
+    pub trade_surplus_recipient: Option<String>,
+    pub flow: SwapFlow,
+}
+
+impl ZeroXQuoteParamsV2 {
+    /// The equivalent v1 parameters, or `None` if the request uses something v1 cannot
+    /// express: `txOrigin`, `tradeSurplusRecipient` or a fee taken in the sell token.
+    pub fn to_v1(&self) -> Option<ZeroXQuoteParams> {
+        let fee_in_buy_token = self
+            .swap_fee_token
+            .as_ref()
+            .is_none_or(|token| token.eq_ignore_ascii_case(&self.buy_token));
+        if self.tx_origin.is_some()
+            || self.trade_surplus_recipient.is_some()
+            || (self.swap_fee_bps.is_some() && !fee_in_buy_token)
+        {
+            return None;
+        }
+
+        Some(ZeroXQuoteParams {
+            sell_token: self.sell_token.clone(),
+            buy_token: self.buy_token.clone(),
+            sell_amount: self.sell_amount.clone(),
+            fee_recipient: self.swap_fee_recipient.clone(),
+            buy_token_percentage_fee: self.swap_fee_bps.map(Percentage::from),
+            taker_address: self.taker.clone(),
+            slippage_percentage: self.slippage_bps.map(Percentage::from),
+            excluded_sources: self.excluded_sources.clone(),
+            ..Default::default()
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct FeeV2 {
+    pub amount: Option<String>,
+    pub token: Option<String>,
+    #[serde(rename = "type")]
+    pub type_: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct FeesV2 {
+    /// The fee requested with `swapFeeBps`, if any.
+    pub integrator_fee: Option<FeeV2>,
+    pub zero_ex_fee: Option<FeeV2>,
+    pub gas_fee: Option<FeeV2>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct RouteFill {
+    pub from: Option<String>,
+    pub to: Option<String>,
+    pub source: Option<String>,
+    pub proportion_bps: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct RouteToken {
+    pub address: Option<String>,
+    pub symbol: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct Route {
+    pub fills: Option<Vec<RouteFill>>,
+    pub tokens: Option<Vec<RouteToken>>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct TransactionV2 {
+    pub to: Option<String>,
+    pub data: Option<String>,
+    pub gas: Option<String>,
+    pub gas_price: Option<String>,
+    pub value: Option<String>,
+}
+
+/// A v2 quote or price. Prices have no `transaction`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct ZeroXQuoteResponseV2 {
+    pub block_number: Option<String>,
+    pub buy_amount: Option<String>,
+    pub buy_token: Option<String>,
+    pub sell_amount: Option<String>,
+    pub sell_token: Option<String>,
+    pub min_buy_amount: Option<String>,
+    pub liquidity_available: Option<bool>,
+    pub fees: Option<FeesV2>,
+    pub issues: Option<Value>,
+    pub route: Option<Route>,
+    pub total_network_fee: Option<String>,
+    pub transaction: Option<TransactionV2>,
+    /// Permit2 typed data to sign, for the [`SwapFlow::Permit2`] flow.
+    pub permit2: Option<Value>,
+    pub zid: Option<String>,
+}
+
+/// Typed accessors, mirroring those of [`ZeroXQuoteResponse`].
+impl ZeroXQuoteResponseV2 {
+    pub fn buy_amount(&self) -> Result<U256, QuoteFieldError> {
+        parse_dec(required(&self.buy_amount, "buy_amount")?, "buy_amount")
+    }
+
+    pub fn sell_amount(&self) -> Result<U256, QuoteFieldError> {
+        parse_dec(required(&self.sell_amount, "sell_amount")?, "sell_amount")
+    }
+
+    /// The buy amount after slippage; the swap reverts if it would receive less.
+    pub fn min_buy_amount(&self) -> Result<U256, QuoteFieldError> {
+        parse_dec(
+            required(&self.min_buy_amount, "min_buy_amount")?,
+            "min_buy_amount",
+        )
+    }
+
+    pub fn buy_token(&self) -> Result<Address, QuoteFieldError> {
+        parse_field(required(&self.buy_token, "buy_token")?, "buy_token")
+    }
+
+    pub fn sell_token(&self) -> Result<Address, QuoteFieldError> {
+        parse_field(required(&self.sell_token, "sell_token")?, "sell_token")
+    }
+
+    pub fn to(&self) -> Result<Address, QuoteFieldError> {
+        parse_field(required(&self.transaction()?.to, "to")?, "to")
+    }
+
+    pub fn data(&self) -> Result<Bytes, QuoteFieldError> {
+        parse_field(required(&self.transaction()?.data, "data")?, "data")
+    }
+
+    /// Native token sent with the swap, zero if absent.
+    pub fn value(&self) -> Result<U256, QuoteFieldError> {
+        self.transaction()?
+            .value
+            .as_deref()
+            .map_or(Ok(U256::zero()), |value| parse_dec(value, "value"))
+    }
+
+    pub fn gas(&self) -> Result<U256, QuoteFieldError> {
+        parse_dec(required(&self.transaction()?.gas, "gas")?, "gas")
+    }
+
+    pub fn gas_price(&self) -> Result<U256, QuoteFieldError> {
+        parse_dec(
+            required(&self.transaction()?.gas_price, "gas_price")?,
+            "gas_price",
+        )
+    }
+
+    pub fn integrator_fee(&self) -> Option<&FeeV2> {
+        self.fees.as_ref()?.integrator_fee.as_ref()
+    }
+
+    fn transaction(&self) -> Result<&TransactionV2, QuoteFieldError> {
+        self.transaction
+            .as_ref()
+            .ok_or(QuoteFieldError::Missing("transaction"))
+    }
+}
+
+/// A quote from either API version, so application code can be written once while both are
+/// in use.
+#[derive(Debug, Serialize)]
+#[serde(untagged)]
+pub enum QuoteResponse {
+    V1(ZeroXQuoteResponse),
+    V2(ZeroXQuoteResponseV2),
+}
+
+impl QuoteResponse {
+    pub fn version(&self) -> ApiVersion {
+        match self {
+            QuoteResponse::V1(_) => ApiVersion::V1,
+            QuoteResponse::V2(_) => ApiVersion::V2,
+        }
+    }
+
+    /// The decimal-adjusted price. v2 no longer returns one, so this is `None` for v2 quotes;
+    /// compare [`buy_amount`](Self::buy_amount) and [`sell_amount`](Self::sell_amount) instead.
+    pub fn price(&self) -> Option<&str> {
+        match self {
+            QuoteResponse::V1(quote) => quote.price(),
+            QuoteResponse::V2(_) => None,
+        }
+    }
+
+    pub fn buy_amount(&self) -> Result<U256, QuoteFieldError> {
+        match self {
+            QuoteResponse::V1(quote) => quote.buy_amount(),
+            QuoteResponse::V2(quote) => quote.buy_amount(),
+        }
+    }
+
+    pub fn sell_amount(&self) -> Result<U256, QuoteFieldError> {
+        match self {
+            QuoteResponse::V1(quote) => quote.sell_amount(),
+            QuoteResponse::V2(quote) => quote.sell_amount(),
+        }
+    }
+
+    pub fn buy_token(&self) -> Result<Address, QuoteFieldError> {
+        match self {
+            QuoteResponse::V1(quote) => quote.buy_token(),
+            QuoteResponse::V2(quote) => quote.buy_token(),
+        }
+    }
+
+    pub fn sell_token(&self) -> Result<Address, QuoteFieldError> {
+        match self {
+            QuoteResponse::V1(quote) => quote.sell_token(),
+            QuoteResponse::V2(quote) => quote.sell_token(),
+        }
+    }
+
+    pub fn to(&self) -> Result<Address, QuoteFieldError> {
+        match self {
+            QuoteResponse::V1(quote) => quote.to(),
+            QuoteResponse::V2(quote) => quote.to(),
+        }
+    }
+
+    pub fn data(&self) -> Result<Bytes, QuoteFieldError> {
+        match self {
+            QuoteResponse::V1(quote) => quote.data(),
+            QuoteResponse::V2(quote) => quote.data(),
+        }
+    }
+
+    pub fn value(&self) -> Result<U256, QuoteFieldError> {
+        match self {
+            QuoteResponse::V1(quote) => quote.value(),
+            QuoteResponse::V2(quote) => quote.value(),
+        }
+    }
+
+    pub fn gas(&self) -> Result<U256, QuoteFieldError> {
+        match self {
+            QuoteResponse::V1(quote) => quote.gas(),
+            QuoteResponse::V2(quote) => quote.gas(),
+        }
+    }
+
+    pub fn gas_price(&self) -> Result<U256, QuoteFieldError> {
+        match self {
+            QuoteResponse::V1(quote) => quote.gas_price(),
+            QuoteResponse::V2(quote) => quote.gas_price(),
+        }
+    }
+}
+
+impl From<ZeroXQuoteResponse> for QuoteResponse {
+    fn from(quote: ZeroXQuoteResponse) -> Self {
+        QuoteResponse::V1(quote)
+    }
+}
+
+impl From<ZeroXQuoteResponseV2> for QuoteResponse {
+    fn from(quote: ZeroXQuoteResponseV2) -> Self {
+        QuoteResponse::V2(quote)
+    }
+}
+
+fn quote_query_v2(chain_id: u64, params: ZeroXQuoteParamsV2) -> HashMap<&'static str, String> {
+    let mut map = HashMap::new();
+    map.insert("chainId", chain_id.to_string());
+    map.insert("sellToken", params.sell_token);
+    map.insert("buyToken", params.buy_token);
+    map.insert("sellAmount", params.sell_amount);
+
+    if let Some(taker) = params.taker {
+        map.insert("taker", taker);
+    }
+
+    if let Some(tx_origin) = params.tx_origin {
+        map.insert("txOrigin", tx_origin);
+    }
+
+    if let Some(slippage_bps) = params.slippage_bps {
+        map.insert("slippageBps", slippage_bps.to_string());
+    }
+
+    if let Some(excluded_sources) = params.excluded_sources {
+        map.insert("excludedSources", excluded_sources.join(","));
+    }
+
+    if let Some(swap_fee_recipient) = params.swap_fee_recipient {
+        map.insert("swapFeeRecipient", swap_fee_recipient);
+    }
+
+    if let Some(swap_fee_bps) = params.swap_fee_bps {
+        map.insert("swapFeeBps", swap_fee_bps.to_string());
+    }
+
+    if let Some(swap_fee_token) = params.swap_fee_token {
+        map.insert("swapFeeToken", swap_fee_token);
+    }
+
+    if let Some(trade_surplus_recipient) = params.trade_surplus_recipient {
+        map.insert("tradeSurplusRecipient", trade_surplus_recipient);
+    }
+
+    map
+}
+
+impl ZeroXClient {
+    pub async fn get_quote_v2(
+        &self,
+        params: ZeroXQuoteParamsV2,
+    ) -> Result<ZeroXQuoteResponseV2, ZeroXClientError> {
+        self.swap_v2("quote", params).await
+    }
+
+    /// Fetches an indicative v2 price, without the transaction.
+    pub async fn get_price_v2(
+        &self,
+        params: ZeroXQuoteParamsV2,
+    ) -> Result<ZeroXQuoteResponseV2, ZeroXClientError> {
+        self.swap_v2("price", params).await
+    }
+
+    /// Fetches a v2 quote. With [`v1_fallback`](crate::ZeroXClientBuilder::v1_fallback)
+    /// enabled, a 404 or 400 from v2 (which is how it reports unsupported chains) retries on
+    /// the chain's v1 endpoint, provided the parameters can be expressed in v1. The returned
+    /// quote's [`version`](QuoteResponse::version) says which API served it.
+    pub async fn get_quote_versioned(
+        &self,
+        params: ZeroXQuoteParamsV2,
+    ) -> Result<QuoteResponse, ZeroXClientError> {
+        let v1_params = params.to_v1().filter(|_| self.v1_fallback);
+
+        match (self.get_quote_v2(params).await, v1_params) {
+            (Err(ZeroXClientError::ZeroXInvalidResponseStatusCode(status)), Some(v1_params))
+                if status == StatusCode::NOT_FOUND || status == StatusCode::BAD_REQUEST =>
+            {
+                debug!("v2 quote failed with {}, falling back to v1", status);
+                Ok(QuoteResponse::V1(self.get_quote(v1_params).await?))
+            }
+            (res, _) => res.map(QuoteResponse::V2),
+        }
+    }
+
+    async fn swap_v2(
+        &self,
+        endpoint: &str,
+        params: ZeroXQuoteParamsV2,
+    ) -> Result<ZeroXQuoteResponseV2, ZeroXClientError> {
+        let params = self.defaults.apply_v2(params, self.chain_id);
+        self.screen_request(ScreeningRequest {
+            chain_id: self.chain_id,
+            sell_token: &params.sell_token,
+            buy_token: &params.buy_token,
+            taker: params.taker.as_deref(),
+        })
+        .await?;
+        if let Some(policy) = &self.policy {
+            policy
+                .check_v2_slippage(params.slippage_bps)
+                .map_err(ZeroXClientError::PolicyViolation)?;
+        }
+
+        let path = params.flow.path(endpoint);
+        let query = quote_query_v2(self.chain_id, params);
+        let quote = self.get_v2(&path, &query).await?.data;
+        if let Some(policy) = &self.policy {
+            policy
+                .check_quote_v2(self.chain_id, &quote)
+                .map_err(ZeroXClientError::PolicyViolation)?;
+        }
+        Ok(quote)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use wiremock::{
+        matchers::{header, method, path, query_param},
+        Mock, MockServer, ResponseTemplate,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_quote_query_v2() {
+        let query = quote_query_v2(
+            8453,
+            ZeroXQuoteParamsV2 {
+                sell_token: String::from("WETH"),
+                buy_token: String::from("USDC"),
+                sell_amount: String::from("1"),
+                taker: Some(String::from("0x04")),
+                tx_origin: Some(String::from("0x05")),
+                slippage_bps: Some(FeeBps::new(50)),
+                trade_surplus_recipient: Some(String::from("0x03")),
+                ..Default::default()
+            },
+        );
+
+        assert_eq!(query["chainId"], "8453");
+        assert_eq!(query["taker"], "0x04");
+        assert_eq!(query["txOrigin"], "0x05");
+        assert_eq!(query["slippageBps"], "50");
+        assert_eq!(query["tradeSurplusRecipient"], "0x03");
+        assert!(!query.contains_key("swapFeeBps"));
+    }
+
+    #[test]
+    fn test_quote_response_accessors() {
+        let v1: QuoteResponse = serde_json::from_value::<ZeroXQuoteResponse>(serde_json::json!({
+            "price": "2000",
+            "buyAmount": "2000",
+            "to": "0xdef1c0ded9bec7f1a1670819833240f027b25eff",
+        }))
+        .unwrap()
+        .into();
+        let v2: QuoteResponse = serde_json::from_value::<ZeroXQuoteResponseV2>(serde_json::json!({
+            "buyAmount": "2000",
+            "transaction": { "to": "0x0d0e364aa7852291883c162b22d6d81f6355428f", "gas": "21000" },
+        }))
+        .unwrap()
+        .into();
+
+        assert_eq!(v1.version(), ApiVersion::V1);
+        assert_eq!(v1.price(), Some("2000"));
+        assert_eq!(v2.price(), None);
+        assert_eq!(v1.buy_amount().unwrap(), v2.buy_amount().unwrap());
+        assert_eq!(
+            v2.to().unwrap(),
+            "0x0d0e364aa7852291883c162b22d6d81f6355428f"
+                .parse::<Address>()
+                .unwrap()
+        );
+        assert_eq!(v2.gas().unwrap(), U256::from(21000));
+        assert_eq!(v2.value().unwrap(), U256::zero());
+        assert_eq!(v2.data(), Err(QuoteFieldError::Missing("data")));
+    }
+
+    #[tokio::test]
+    async fn test_v1_fallback() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/swap/allowance-holder/quote"))
+            .respond_with(ResponseTemplate::new(404))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/swap/v1/quote"))
+            .and(query_param("buyTokenPercentageFee", "0.0025"))
+            .and(query_param("slippagePercentage", "0.01"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(serde_json::json!({ "price": "2000" })),
+            )
+            .mount(&server)
+            .await;
+
+        let builder = ZeroXClient::builder(1, String::from("key"))
+            .base_url(server.uri())
+            .v2_base_url(server.uri());
+        let params = ZeroXQuoteParamsV2 {
+            sell_token: String::from("WETH"),
+            buy_token: String::from("USDC"),
+            sell_amount: String::from("1"),
+            slippage_bps: Some(FeeBps::new(100)),
+            swap_fee_recipient: Some(String::from("0x02")),
+            swap_fee_bps: Some(FeeBps::new(25)),
+            ..Default::default()
+        };
+
+        let client = builder.v1_fallback(true).build().unwrap();
+        let quote = client.get_quote_versioned(params.clone()).await.unwrap();
+        assert_eq!(quote.version(), ApiVersion::V1);
+        assert_eq!(quote.price(), Some("2000"));
+
+        // v1 has no way to take the fee in the sell token
+        let sell_token_fee = ZeroXQuoteParamsV2 {
+            swap_fee_token: Some(String::from("WETH")),
+            ..params
+        };
+        assert!(sell_token_fee.to_v1().is_none());
+        assert!(matches!(
+            client.get_quote_versioned(sell_token_fee).await,
+            Err(ZeroXClientError::ZeroXInvalidResponseStatusCode(
+                StatusCode::NOT_FOUND
+            ))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_get_quote_v2_with_integrator_fee() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/swap/permit2/quote"))
+            .and(header("0x-version", "v2"))
+            .and(query_param("chainId", "137"))
+            .and(query_param("swapFeeBps", "25"))
+            .and(query_param("swapFeeToken", "USDC"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "buyAmount": "997500",
+                "fees": {
+                    "integratorFee": { "amount": "2500", "token": "USDC", "type": "volume" },
+                    "zeroExFee": null,
+                    "gasFee": null,
+                },
+                "transaction": { "to": "0x01", "data": "0x" },
+            })))
+            .mount(&server)
+            .await;
+
+        let client = ZeroXClient::builder(137, String::from("key"))
+            .v2_base_url(server.uri())
+            .build()
+            .unwrap();
+        let quote = client
+            .get_quote_v2(ZeroXQuoteParamsV2 {
+                sell_token: String::from("WETH"),
+                buy_token: String::from("USDC"),
+                sell_amount: String::from("1000000000000000"),
+                swap_fee_recipient: Some(String::from("0x02")),
+                swap_fee_bps: Some(FeeBps::new(25)),
+                swap_fee_token: Some(String::from("USDC")),
+                flow: SwapFlow::Permit2,
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+
+        let fee = quote.fees.unwrap().integrator_fee.unwrap();
+        assert_eq!(fee.amount.as_deref(), Some("2500"));
+        assert_eq!(fee.type_.as_deref(), Some("volume"));
+        assert_eq!(quote.transaction.unwrap().to.as_deref(), Some("0x01"));
+    }
+}
@@ -0,0 +1,336 @@
+//! Checks run on every v1 quote and price before it is returned, registered with
+//! [`ZeroXClientBuilder::quote_validator`](crate::ZeroXClientBuilder::quote_validator).
+//!
+//! Validators cover `get_quote` and `get_price`, their `_with_metadata`, `_body` and
+//! `_snapshot` variants, `get_quote_raw`, and everything built on them. They take the v1 response type, so
+//! v2 and gasless quotes are not validated; limits that must hold for those belong in a
+//! [`TradingPolicy`](crate::TradingPolicy), which checks every version.
+
+use std::{str::FromStr, sync::Arc};
+
+use async_trait::async_trait;
+use ethers::core::types::Address;
+use rust_decimal::Decimal;
+
+use crate::{oracle::PriceOracle, Percentage, ZeroXQuoteParams, ZeroXQuoteResponse};
+
+/// A quote that failed a [`QuoteValidator`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QuoteRejected {
+    /// [`QuoteValidator::name`] of the failing rule.
+    pub rule: String,
+    pub reason: String,
+}
+
+impl std::fmt::Display for QuoteRejected {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.rule, self.reason)
+    }
+}
+
+/// A rule a quote must pass. Validators run in registration order and the first failure
+/// rejects the quote.
+#[async_trait]
+pub trait QuoteValidator: Send + Sync {
+    fn name(&self) -> &str;
+
+    /// Returns why the quote is rejected, if it is.
+    async fn validate(
+        &self,
+        params: &ZeroXQuoteParams,
+        quote: &ZeroXQuoteResponse,
+    ) -> Result<(), String>;
+}
+
+type ValidatorFn =
+    dyn Fn(&ZeroXQuoteParams, &ZeroXQuoteResponse) -> Result<(), String> + Send + Sync;
+
+/// A named closure as a [`QuoteValidator`].
+pub struct FnValidator {
+    name: String,
+    check: Box<ValidatorFn>,
+}
+
+impl FnValidator {
+    pub fn new<F>(name: impl Into<String>, check: F) -> FnValidator
+    where
+        F: Fn(&ZeroXQuoteParams, &ZeroXQuoteResponse) -> Result<(), String> + Send + Sync + 'static,
+    {
+        FnValidator {
+            name: name.into(),
+            check: Box::new(check),
+        }
+    }
+}
+
+#[async_trait]
+impl QuoteValidator for FnValidator {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn validate(
+        &self,
+        params: &ZeroXQuoteParams,
+        quote: &ZeroXQuoteResponse,
+    ) -> Result<(), String> {
+        (self.check)(params, quote)
+    }
+}
+
+/// Rejects quotes whose estimated price impact exceeds a limit. Quotes without an estimate
+/// pass.
+#[derive(Debug, Clone, Copy)]
+pub struct MaxPriceImpact(pub Percentage);
+
+#[async_trait]
+impl QuoteValidator for MaxPriceImpact {
+    fn name(&self) -> &str {
+        "max_price_impact"
+    }
+
+    async fn validate(
+        &self,
+        _params: &ZeroXQuoteParams,
+        quote: &ZeroXQuoteResponse,
+    ) -> Result<(), String> {
+        let Some(impact) = quote.estimated_price_impact.as_deref() else {
+            return Ok(());
+        };
+        // 0x reports the impact in percent.
+        let impact = Decimal::from_str(impact)
+            .map_err(|_| format!("unparseable price impact {impact:?}"))?;
+        if impact > self.0.percent() {
+            return Err(format!("price impact {impact}% exceeds {}", self.0));
+        }
+        Ok(())
+    }
+}
+
+/// Only allows quotes between tokens in a fixed set, compared case-insensitively. Tokens are
+/// checked as requested, so symbols such as `ETH` must be listed as they are passed.
+#[derive(Debug, Clone, Default)]
+pub struct TokenAllowlist {
+    tokens: Vec<String>,
+}
+
+impl TokenAllowlist {
+    pub fn new<I, S>(tokens: I) -> TokenAllowlist
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        TokenAllowlist {
+            tokens: tokens
+                .into_iter()
+                .map(|token| token.into().to_lowercase())
+                .collect(),
+        }
+    }
+}
+
+#[async_trait]
+impl QuoteValidator for TokenAllowlist {
+    fn name(&self) -> &str {
+        "token_allowlist"
+    }
+
+    async fn validate(
+        &self,
+        params: &ZeroXQuoteParams,
+        _quote: &ZeroXQuoteResponse,
+    ) -> Result<(), String> {
+        for token in [&params.sell_token, &params.buy_token] {
+            if !self.tokens.contains(&token.to_lowercase()) {
+                return Err(format!("{token} is not allowlisted"));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Rejects quotes whose price deviates from an oracle's price by more than `max_deviation`.
+pub struct OracleDeviation {
+    oracle: Arc<dyn PriceOracle>,
+    max_deviation: Percentage,
+}
+
+impl OracleDeviation {
+    pub fn new(
+        oracle: Arc<dyn PriceOracle>,
+        max_deviation: impl Into<Percentage>,
+    ) -> OracleDeviation {
+        OracleDeviation {
+            oracle,
+            max_deviation: max_deviation.into(),
+        }
+    }
+}
+
+#[async_trait]
+impl QuoteValidator for OracleDeviation {
+    fn name(&self) -> &str {
+        "oracle_deviation"
+    }
+
+    async fn validate(
+        &self,
+        _params: &ZeroXQuoteParams,
+        quote: &ZeroXQuoteResponse,
+    ) -> Result<(), String> {
+        let address = |address: Option<&str>| {
+            address
+                .and_then(|address| Address::from_str(address).ok())
+                .ok_or_else(|| String::from("quote has no token addresses"))
+        };
+        let sell_token = address(quote.sell_token_address.as_deref())?;
+        let buy_token = address(quote.buy_token_address.as_deref())?;
+        let price = quote
+            .price
+            .as_deref()
+            .and_then(|price| Decimal::from_str(price).ok())
+            .ok_or_else(|| String::from("quote has no price"))?;
+
+        let reference = self
+            .oracle
+            .price(sell_token, buy_token)
+            .await
+            .map_err(|err| format!("oracle price unavailable: {err}"))?;
+        if reference.is_zero() {
+            return Err(String::from("oracle price is zero"));
+        }
+
+        let deviation = ((price - reference) / reference).abs();
+        if deviation > self.max_deviation.fraction() {
+            return Err(format!(
+                "price {price} deviates {}% from oracle price {reference}",
+                (deviation * Decimal::ONE_HUNDRED).round_dp(2).normalize()
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Runs `validators` in order, stopping at the first rejection.
+pub(crate) async fn validate_all(
+    validators: &[Arc<dyn QuoteValidator>],
+    params: &ZeroXQuoteParams,
+    quote: &ZeroXQuoteResponse,
+) -> Result<(), QuoteRejected> {
+    for validator in validators {
+        validator
+            .validate(params, quote)
+            .await
+            .map_err(|reason| QuoteRejected {
+                rule: validator.name().to_string(),
+                reason,
+            })?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct FixedOracle(Decimal);
+
+    #[async_trait]
+    impl PriceOracle for FixedOracle {
+        async fn price(
+            &self,
+            _base: Address,
+            _quote: Address,
+        ) -> Result<Decimal, Box<dyn std::error::Error + Send + Sync>> {
+            Ok(self.0)
+        }
+    }
+
+    fn quote(price: &str, impact: &str) -> ZeroXQuoteResponse {
+        serde_json::from_value(serde_json::json!({
+            "price": price,
+            "estimatedPriceImpact": impact,
+            "sellTokenAddress": "0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2",
+            "buyTokenAddress": "0x6b175474e89094c44da98b954eedeac495271d0f",
+        }))
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_builtin_validators() {
+        let params = ZeroXQuoteParams {
+            sell_token: String::from("WETH"),
+            buy_token: String::from("DAI"),
+            ..Default::default()
+        };
+
+        let validators: Vec<Arc<dyn QuoteValidator>> = vec![
+            Arc::new(TokenAllowlist::new(["weth", "dai"])),
+            Arc::new(MaxPriceImpact(Percentage::from_percent(Decimal::ONE))),
+            Arc::new(OracleDeviation::new(
+                Arc::new(FixedOracle(Decimal::from(2000))),
+                Percentage::from_percent(Decimal::from(2)),
+            )),
+        ];
+
+        assert!(validate_all(&validators, &params, &quote("2010", "0.5"))
+            .await
+            .is_ok());
+
+        let rejected = validate_all(&validators, &params, &quote("2010", "1.5"))
+            .await
+            .unwrap_err();
+        assert_eq!(rejected.rule, "max_price_impact");
+
+        let rejected = validate_all(&validators, &params, &quote("2100", "0.5"))
+            .await
+            .unwrap_err();
+        assert_eq!(rejected.rule, "oracle_deviation");
+        assert_eq!(
+            rejected.reason,
+            "price 2100 deviates 5% from oracle price 2000"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_validators_run_on_unparsed_quotes() {
+        use wiremock::{matchers::method, Mock, MockServer, ResponseTemplate};
+
+        use crate::{ZeroXClient, ZeroXClientError};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "price": "2010",
+                "estimatedPriceImpact": "1.5",
+            })))
+            .mount(&server)
+            .await;
+
+        let client = ZeroXClient::builder(1, String::from("key"))
+            .base_url(server.uri())
+            .quote_validator(MaxPriceImpact(Percentage::from_percent(Decimal::ONE)))
+            .build()
+            .unwrap();
+        let rejected = |err: ZeroXClientError| matches!(err, ZeroXClientError::QuoteRejected(rejected) if rejected.rule == "max_price_impact");
+
+        assert!(rejected(
+            client
+                .get_quote_raw(ZeroXQuoteParams::default())
+                .await
+                .unwrap_err()
+        ));
+        assert!(rejected(
+            client
+                .get_quote_body(ZeroXQuoteParams::default())
+                .await
+                .unwrap_err()
+        ));
+        assert!(rejected(
+            client
+                .get_price_snapshot(ZeroXQuoteParams::default())
+                .await
+                .unwrap_err()
+        ));
+    }
+}
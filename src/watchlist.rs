@@ -0,0 +1,364 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use tokio::{
+    sync::{broadcast, Notify},
+    task::JoinHandle,
+};
+use tracing::debug;
+
+use crate::{
+    price_stream::WatchlistPrices,
+    sink::{QuoteEvent, QuoteEventKind, QuoteSink},
+    ZeroXClient, ZeroXQuoteParams, ZeroXQuoteResponse,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct WatchedPair {
+    pub sell_token: String,
+    pub buy_token: String,
+    pub sell_amount: String,
+}
+
+impl WatchedPair {
+    pub fn new(
+        sell_token: impl Into<String>,
+        buy_token: impl Into<String>,
+        sell_amount: impl Into<String>,
+    ) -> WatchedPair {
+        WatchedPair {
+            sell_token: sell_token.into(),
+            buy_token: buy_token.into(),
+            sell_amount: sell_amount.into(),
+        }
+    }
+
+    pub(crate) fn to_params(&self) -> ZeroXQuoteParams {
+        ZeroXQuoteParams {
+            sell_token: self.sell_token.clone(),
+            buy_token: self.buy_token.clone(),
+            sell_amount: self.sell_amount.clone(),
+            ..Default::default()
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CachedQuote {
+    pub quote: Arc<ZeroXQuoteResponse>,
+    pub fetched_at: Instant,
+}
+
+impl CachedQuote {
+    pub fn age(&self) -> Duration {
+        self.fetched_at.elapsed()
+    }
+}
+
+#[derive(Default)]
+struct Entry {
+    latest: Option<CachedQuote>,
+    last_attempt: Option<Instant>,
+}
+
+struct Shared {
+    entries: Mutex<HashMap<WatchedPair, Entry>>,
+    changed: Notify,
+    updates: broadcast::Sender<(WatchedPair, CachedQuote)>,
+}
+
+/// Keeps a continuously refreshed quote for each registered pair.
+///
+/// Pairs are refreshed one at a time, oldest first, at most once per `refresh_interval`.
+/// Requests go through the client, so they are paced by its rate limit if one is configured.
+/// The background task stops when the watchlist is dropped.
+pub struct Watchlist {
+    shared: Arc<Shared>,
+    task: JoinHandle<()>,
+}
+
+impl Watchlist {
+    pub fn new(client: Arc<ZeroXClient>, refresh_interval: Duration) -> Watchlist {
+        Watchlist::start(client, refresh_interval, None)
+    }
+
+    /// Like [`Watchlist::new`], also publishing every refreshed quote to `sink`. Publish
+    /// failures are logged and do not affect the watchlist.
+    pub fn with_sink(
+        client: Arc<ZeroXClient>,
+        refresh_interval: Duration,
+        sink: Arc<dyn QuoteSink>,
+    ) -> Watchlist {
+        Watchlist::start(client, refresh_interval, Some(sink))
+    }
+
+    fn start(
+        client: Arc<ZeroXClient>,
+        refresh_interval: Duration,
+        sink: Option<Arc<dyn QuoteSink>>,
+    ) -> Watchlist {
+        let shared = Arc::new(Shared {
+            entries: Mutex::new(HashMap::new()),
+            changed: Notify::new(),
+            updates: broadcast::channel(256).0,
+        });
+
+        let task = tokio::spawn(refresh_loop(client, shared.clone(), refresh_interval, sink));
+
+        Watchlist { shared, task }
+    }
+
+    pub fn register(&self, pair: WatchedPair) {
+        self.shared.entries.lock().unwrap().entry(pair).or_default();
+        self.shared.changed.notify_one();
+    }
+
+    pub fn unregister(&self, pair: &WatchedPair) {
+        self.shared.entries.lock().unwrap().remove(pair);
+    }
+
+    pub fn pairs(&self) -> Vec<WatchedPair> {
+        self.shared
+            .entries
+            .lock()
+            .unwrap()
+            .keys()
+            .cloned()
+            .collect()
+    }
+
+    /// Registers `pair` and streams its prices as they are refreshed, see
+    /// [`PriceStreamExt`](crate::PriceStreamExt) for adapters. The stream ends when the
+    /// watchlist is dropped.
+    pub fn prices(&self, pair: WatchedPair) -> WatchlistPrices {
+        let updates = self.shared.updates.subscribe();
+        self.register(pair.clone());
+        WatchlistPrices { pair, updates }
+    }
+
+    /// Returns the most recent successful quote for `pair`, if any.
+    pub fn latest(&self, pair: &WatchedPair) -> Option<CachedQuote> {
+        self.shared
+            .entries
+            .lock()
+            .unwrap()
+            .get(pair)
+            .and_then(|entry| entry.latest.clone())
+    }
+}
+
+impl Drop for Watchlist {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+async fn refresh_loop(
+    client: Arc<ZeroXClient>,
+    shared: Arc<Shared>,
+    refresh_interval: Duration,
+    sink: Option<Arc<dyn QuoteSink>>,
+) {
+    loop {
+        let next = {
+            let entries = shared.entries.lock().unwrap();
+            entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.last_attempt)
+                .map(|(pair, entry)| {
+                    let due = entry
+                        .last_attempt
+                        .map(|last| last + refresh_interval)
+                        .unwrap_or_else(Instant::now);
+                    (pair.clone(), due)
+                })
+        };
+
+        let Some((pair, due)) = next else {
+            shared.changed.notified().await;
+            continue;
+        };
+
+        let wait = due.saturating_duration_since(Instant::now());
+        if !wait.is_zero() {
+            tokio::select! {
+                _ = tokio::time::sleep(wait) => {}
+                _ = shared.changed.notified() => continue,
+            }
+        }
+
+        if let Some(entry) = shared.entries.lock().unwrap().get_mut(&pair) {
+            entry.last_attempt = Some(Instant::now());
+        }
+
+        let result = client.get_quote(pair.to_params()).await;
+
+        let refreshed = {
+            let mut entries = shared.entries.lock().unwrap();
+            match (result, entries.get_mut(&pair)) {
+                (Ok(quote), Some(entry)) => {
+                    let latest = CachedQuote {
+                        quote: Arc::new(quote),
+                        fetched_at: Instant::now(),
+                    };
+                    entry.latest = Some(latest.clone());
+                    // No subscribers is not an error.
+                    let _ = shared.updates.send((pair.clone(), latest.clone()));
+                    Some(latest.quote)
+                }
+                (Err(err), Some(_)) => {
+                    debug!("failed to refresh {:?}: {}", pair, err);
+                    None
+                }
+                (_, None) => None,
+            }
+        };
+
+        if let (Some(quote), Some(sink)) = (refreshed, &sink) {
+            let event = QuoteEvent::new(
+                QuoteEventKind::Quote,
+                client.chain_id(),
+                &pair.sell_token,
+                &pair.buy_token,
+                &pair.sell_amount,
+                quote,
+            );
+            if let Err(err) = sink.publish(&event).await {
+                debug!("failed to publish quote for {:?}: {}", pair, err);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use async_trait::async_trait;
+
+    use super::*;
+    use wiremock::{
+        matchers::{method, path, query_param},
+        Mock, MockServer, ResponseTemplate,
+    };
+
+    #[tokio::test]
+    async fn test_watchlist_refreshes_registered_pairs() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/swap/v1/quote"))
+            .and(query_param("buyToken", "DAI"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(serde_json::json!({ "price": "2000" })),
+            )
+            .mount(&server)
+            .await;
+
+        let client = ZeroXClient::builder(1, String::from("test"))
+            .base_url(server.uri())
+            .build()
+            .unwrap();
+
+        let watchlist = Watchlist::new(Arc::new(client), Duration::from_millis(50));
+        let pair = WatchedPair::new("ETH", "DAI", "1000000000000000000");
+
+        assert!(watchlist.latest(&pair).is_none());
+
+        watchlist.register(pair.clone());
+
+        let mut latest = None;
+        for _ in 0..50 {
+            latest = watchlist.latest(&pair);
+            if latest.is_some() {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+
+        let latest = latest.unwrap();
+        assert_eq!(latest.quote.price.as_deref(), Some("2000"));
+        assert!(latest.age() < Duration::from_secs(1));
+
+        watchlist.unregister(&pair);
+        assert!(watchlist.latest(&pair).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_watchlist_price_stream() {
+        use crate::{PriceStream, PriceStreamExt};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/swap/v1/quote"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(serde_json::json!({ "price": "2000" })),
+            )
+            .mount(&server)
+            .await;
+
+        let client = ZeroXClient::builder(1, String::from("test"))
+            .base_url(server.uri())
+            .build()
+            .unwrap();
+
+        let watchlist = Watchlist::new(Arc::new(client), Duration::from_millis(20));
+        let pair = WatchedPair::new("ETH", "DAI", "1000000000000000000");
+        let mut prices = watchlist
+            .prices(pair.clone())
+            .dedupe_unchanged()
+            .with_staleness_timeout(Duration::from_millis(200));
+
+        let tick = prices.next().await.unwrap().unwrap();
+        assert_eq!(tick.pair, pair);
+        assert_eq!(tick.price, rust_decimal::Decimal::from(2000));
+        // The price never changes, so later refreshes are deduplicated.
+        assert!(prices.next().await.unwrap().is_err());
+    }
+
+    struct ChannelSink(tokio::sync::mpsc::UnboundedSender<QuoteEvent>);
+
+    #[async_trait]
+    impl QuoteSink for ChannelSink {
+        async fn publish(
+            &self,
+            event: &QuoteEvent,
+        ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+            Ok(self.0.send(event.clone())?)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_watchlist_publishes_to_sink() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/swap/v1/quote"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(serde_json::json!({ "price": "2000" })),
+            )
+            .mount(&server)
+            .await;
+
+        let client = ZeroXClient::builder(1, String::from("test"))
+            .base_url(server.uri())
+            .build()
+            .unwrap();
+
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        let watchlist = Watchlist::with_sink(
+            Arc::new(client),
+            Duration::from_millis(50),
+            Arc::new(ChannelSink(tx)),
+        );
+        watchlist.register(WatchedPair::new("ETH", "DAI", "1"));
+
+        let event = tokio::time::timeout(Duration::from_secs(1), rx.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(event.kind, QuoteEventKind::Quote);
+        assert_eq!(event.chain_id, 1);
+        assert_eq!(event.buy_token, "DAI");
+        assert_eq!(event.quote.price(), Some("2000"));
+    }
+}